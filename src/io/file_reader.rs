@@ -1,13 +1,22 @@
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader};
 use std::path::Path;
 
-pub fn read_file_lines<P>(filename: P) -> Vec<String>
+/// Reads the lines of a text file, without their line endings
+///
+/// Lines that are not valid UTF-8 are skipped. Fails if the file can not be opened or read.
+pub fn read_file_lines<P>(filename: P) -> io::Result<Vec<String>>
 where P: AsRef<Path>, {
-    let file = File::open(filename).unwrap();
-    let lines: Vec<String> = BufReader::new(file)
-        .lines()
-        .filter_map(Result::ok)
-        .collect();
-    lines
-}
\ No newline at end of file
+    let file = File::open(filename)?;
+    let mut lines: Vec<String> = Vec::new();
+    for line in BufReader::new(file).split(b'\n') {
+        let mut line: Vec<u8> = line?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        if let Ok(line) = String::from_utf8(line) {
+            lines.push(line);
+        }
+    }
+    Ok(lines)
+}
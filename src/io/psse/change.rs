@@ -0,0 +1,392 @@
+use std::{collections::HashMap, fmt::{self, Display}, path::Path};
+
+use super::{components::structs::*, error::ParseError, options::ParseOptions, parsers::archive::open_case, pssedata::{PSSEData, PSSEDatatype, SourceMap}};
+
+/// A record added to or modified in a case by a change file
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangedRecord {
+    /// ## Section of the record
+    pub section: PSSEDatatype,
+    /// ## Record identified by its buses and ID
+    pub record: String,
+}
+
+impl Display for ChangedRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} data {}", self.section, self.record)
+    }
+}
+
+/// The records a change file added to a case and the ones it modified
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangeReport {
+    /// ## Records whose key was not in the case, appended to their section
+    pub added: Vec<ChangedRecord>,
+    /// ## Records whose key was in the case with different data, updated in place
+    pub modified: Vec<ChangedRecord>,
+}
+
+/// Parses a change file (IC = 1) and merges its records into `data`
+///
+/// See [`apply_changes`] for how records are matched and merged. The file is read with [`open_case`] in strict mode and may be
+/// of another revision than the case. A file that is not a change case (IC = 0) is rejected with [`ParseError::NotChangeFile`].
+///
+/// ```no_run
+/// use pf_gat_parser::prelude::*;
+///
+/// let mut case: PSSEData = read_raw("case.raw").unwrap();
+/// let report: ChangeReport = apply_change_file(&mut case, "changes.raw").unwrap();
+/// println!("{} records added, {} modified", report.added.len(), report.modified.len());
+/// ```
+pub fn apply_change_file<P: AsRef<Path>>(data: &mut PSSEData, filepath: P) -> Result<ChangeReport, ParseError> {
+    let (changes, source, _) = open_case(filepath, &ParseOptions::strict())?;
+    if changes.header.ic != 1 {
+        return Err(ParseError::NotChangeFile { ic: changes.header.ic });
+    }
+    Ok(apply_changes(data, changes, &source))
+}
+
+/// Merges the records of a change case into `data`, keyed by their natural IDs
+///
+/// A record whose key is already in the case is updated field by field: only the fields the change record gave a value for
+/// are taken, the fields it left blank or left off its lines keep their values in the case, as PSS/E does.
+/// The given fields are looked up in `source`, the [`SourceMap`] the change case was parsed with. Records it has no fields for
+/// (read from .RAWX or built in code, and those of the impedance correction, multi-terminal DC, GNE and substation sections)
+/// replace the record in the case whole.
+/// A record with a new key is appended to its section. Records left identical to the ones in the case are left out of the report.
+/// The keys are the bus number (buses), bus and ID (loads, shunts, machines), from bus, to bus and circuit in either
+/// direction (branches, switching devices), the buses and circuit (transformers, multi-section lines), the area, zone
+/// or owner number, and the name (DC lines, FACTS and GNE devices). When the case holds several records with the same key
+/// the change is applied to the first one, the record [`CaseIndex`](super::index::CaseIndex) finds. The case header is kept.
+pub fn apply_changes(data: &mut PSSEData, changes: PSSEData, source: &SourceMap) -> ChangeReport {
+    let mut merge: Merge = Merge { source, report: ChangeReport::default() };
+    merge.section(PSSEDatatype::Bus, &mut data.buses, changes.buses, |bus| bus.id.to_string());
+    merge.section(PSSEDatatype::Load, &mut data.loads, changes.loads, |load| format!("{} '{}'", load.bus_id, load.id));
+    merge.section(PSSEDatatype::FixedShunt, &mut data.fixed_shunts, changes.fixed_shunts, |shunt| format!("{} '{}'", shunt.bus_id, shunt.id));
+    merge.section(PSSEDatatype::Generator, &mut data.generators, changes.generators, |generator| format!("{} '{}'", generator.bus_id, generator.id));
+    merge.section(PSSEDatatype::Branch, &mut data.branches, changes.branches, |branch| branch_key(branch.from_bus, branch.to_bus, &branch.circuit));
+    merge.section(PSSEDatatype::SystemSwitchingDevice, &mut data.switching_devices, changes.switching_devices, |device| branch_key(device.from_bus, device.to_bus, &device.circuit));
+    merge.section(PSSEDatatype::Transformer, &mut data.transformers, changes.transformers, |transformer| {
+        format!("{}-{}-{} '{}'", transformer.from_bus, transformer.to_bus, transformer.tertiary_bus, transformer.circuit)
+    });
+    merge.section(PSSEDatatype::Area, &mut data.areas, changes.areas, |area| area.area_id.to_string());
+    merge.section(PSSEDatatype::TwoTerminalDc, &mut data.two_terminal_dc, changes.two_terminal_dc, |dc_line| format!("'{}'", dc_line.name));
+    merge.section(PSSEDatatype::VscDc, &mut data.vsc_dc, changes.vsc_dc, |dc_line| format!("'{}'", dc_line.name));
+    merge.section(PSSEDatatype::ImpedanceCorrection, &mut data.impedance_correction, changes.impedance_correction, |table| table.index.to_string());
+    merge.section(PSSEDatatype::MultiTerminalDc, &mut data.multi_terminal_line, changes.multi_terminal_line, |dc_line| format!("'{}'", dc_line.name));
+    merge.section(PSSEDatatype::MultiSectionLine, &mut data.multi_section_line, changes.multi_section_line, |section_line| {
+        format!("{}-{} '{}'", section_line.from_bus, section_line.to_bus, section_line.circuit)
+    });
+    merge.section(PSSEDatatype::Zone, &mut data.zones, changes.zones, |zone| zone.zone_id.to_string());
+    merge.section(PSSEDatatype::InterAreaTransfer, &mut data.inter_area_transfer, changes.inter_area_transfer, |transfer| {
+        format!("{}-{} '{}'", transfer.from_area, transfer.to_area, transfer.transfer_id)
+    });
+    merge.section(PSSEDatatype::Owner, &mut data.owners, changes.owners, |owner| owner.owner_id.to_string());
+    merge.section(PSSEDatatype::Facts, &mut data.facts, changes.facts, |facts| format!("'{}'", facts.deivce_name));
+    merge.section(PSSEDatatype::SwitchedShunt, &mut data.switched_shunts, changes.switched_shunts, |shunt| format!("{} '{}'", shunt.bus_id, shunt.id));
+    merge.section(PSSEDatatype::GneDevice, &mut data.gne_devices, changes.gne_devices, |gne| format!("'{}'", gne.name));
+    merge.section(PSSEDatatype::InductionMachine, &mut data.induction_machines, changes.induction_machines, |machine| format!("{} '{}'", machine.bus_id, machine.id));
+    merge.section(PSSEDatatype::Substation, &mut data.substations, changes.substations, |substation| substation.id.to_string());
+    merge.report
+}
+
+//Branches are the same whichever end is given first
+fn branch_key(from_bus: i32, to_bus: i32, circuit: &str) -> String {
+    format!("{}-{} '{}'", from_bus.min(to_bus), from_bus.max(to_bus), circuit)
+}
+
+/// A change case being merged into a case section by section
+struct Merge<'a> {
+    source: &'a SourceMap,
+    report: ChangeReport,
+}
+
+impl Merge<'_> {
+    /// Merges the change records of a section into its records in the case
+    fn section<T: PartialEq + GivenFields>(&mut self, section: PSSEDatatype, records: &mut Vec<T>, changes: Vec<T>, key: impl Fn(&T) -> String) {
+        //The first record of a duplicated key is the one changed, as CaseIndex finds it
+        let mut positions: HashMap<String, usize> = HashMap::with_capacity(records.len());
+        for (position, record) in records.iter().enumerate() {
+            positions.entry(key(record)).or_insert(position);
+        }
+        for (index, change) in changes.into_iter().enumerate() {
+            let record: String = key(&change);
+            match positions.get(&record) {
+                Some(&position) => {
+                    let merged: T = match self.source.given_fields(section, index) {
+                        Some(given) => {
+                            let mut merged: T = records[position].clone();
+                            merged.take_given(&change, given);
+                            merged
+                        }
+                        None => change,
+                    };
+                    if records[position] != merged {
+                        records[position] = merged;
+                        self.report.modified.push(ChangedRecord { section, record });
+                    }
+                }
+                None => {
+                    positions.insert(record.clone(), records.len());
+                    records.push(change);
+                    self.report.added.push(ChangedRecord { section, record });
+                }
+            }
+        }
+    }
+}
+
+/// Takes the fields a change record gave a value for
+trait GivenFields: Clone {
+    /// Overwrites the fields named in `given`, the mnemonics given by each line of `change`
+    ///
+    /// Records whose fields are not tracked are replaced whole.
+    fn take_given(&mut self, change: &Self, _given: &[Vec<&'static str>]) {
+        self.clone_from(change);
+    }
+}
+
+/// Implements [`GivenFields`] from the mnemonics read from each line of a record and the members they fill
+macro_rules! given_fields {
+    ($record:ty $(, [$($field:expr => $member:ident $([$index:literal])?),* $(,)?])*) => {
+        impl GivenFields for $record {
+            fn take_given(&mut self, change: &Self, given: &[Vec<&'static str>]) {
+                let mut lines = given.iter();
+                $(
+                    let line: &[&str] = lines.next().map_or(&[], Vec::as_slice);
+                    $(
+                        if line.contains(&$field) {
+                            given_fields!(@take self, change, $member $([$index])?);
+                        }
+                    )*
+                )*
+            }
+        }
+    };
+    (@take $record:ident, $change:ident, $member:ident) => {
+        $record.$member.clone_from(&$change.$member)
+    };
+    (@take $record:ident, $change:ident, $member:ident [$index:literal]) => {
+        if let (Some(value), Some(changed)) = ($record.$member.get_mut($index), $change.$member.get($index)) {
+            value.clone_from(changed);
+        }
+    };
+}
+
+given_fields!(Bus, [
+    "I" => id, "NAME" => name, "BASKV" => base_kv, "IDE" => type_code, "AREA" => area, "ZONE" => zone, "OWNER" => owner,
+    "VM" => vm_pu, "VA" => va_deg, "NVHI" => nvhi, "NVLO" => nvlo, "EVHI" => evhi, "EVLO" => evlo, "LONGNAME" => long_name,
+]);
+given_fields!(Load, [
+    "I" => bus_id, "ID" => id, "STATUS" => status, "AREA" => area, "ZONE" => zone, "PL" => pl_mw, "QL" => ql_mvar,
+    "IP" => ip_mw, "IQ" => iq_mvar, "YP" => yp_mw, "YQ" => yq_mvar, "OWNER" => owner, "SCALE" => scale, "INTRPT" => interruptable,
+    "DGENP" => dgen_mw, "DGENQ" => dgen_mvar, "DGENM" => dgen_mode, "LOADTYPE" => load_type, "PRIORITY" => priority,
+]);
+given_fields!(FixedShunt, ["I" => bus_id, "ID" => id, "STATUS" => status, "GL" => gl_mw, "BL" => bl_mvar]);
+given_fields!(Generator, [
+    "I" => bus_id, "ID" => id, "PG" => pgen, "QG" => qgen, "QT" => qmax, "QB" => qmin, "VS" => voltage_set,
+    "IREG" => reg_bus_id, "NREG" => reg_bus_node, "MBASE" => mbase, "ZR" => zr, "ZX" => zx, "RT" => rt, "XT" => xt, "GTAP" => gtap,
+    "STATUS" => status, "RMPCT" => rmpct, "PT" => pmax, "PB" => pmin, "BASLOD" => base_loaded,
+    "O1" => owner1, "F1" => owner1_percent, "O2" => owner2, "F2" => owner2_percent, "O3" => owner3, "F3" => owner3_percent,
+    "O4" => owner4, "F4" => owner4_percent, "WMOD" => machine_mode, "WPF" => machine_powerfactor, "GENTYPE" => gen_type,
+]);
+given_fields!(Branch, [
+    "I" => from_bus, "J" => to_bus, "CKT" => circuit, "R" => r, "X" => x, "B" => b, "NAME" => name,
+    "RATE1" => rate1, "RATE2" => rate2, "RATE3" => rate3, "RATE4" => rate4, "RATE5" => rate5, "RATE6" => rate6,
+    "RATE7" => rate7, "RATE8" => rate8, "RATE9" => rate9, "RATE10" => rate10, "RATE11" => rate11, "RATE12" => rate12,
+    "GI" => gi, "BI" => bi, "GJ" => gj, "BJ" => bj, "ST" => status, "MET" => meter_end, "LEN" => length,
+    "O1" => owner1, "F1" => owner1_percent, "O2" => owner2, "F2" => owner2_percent, "O3" => owner3, "F3" => owner3_percent,
+    "O4" => owner4, "F4" => owner4_percent, "BRTYPE" => branch_type,
+]);
+given_fields!(SystemSwitchingDevice, [
+    "I" => from_bus, "J" => to_bus, "CKT" => circuit, "X" => x,
+    "RATE1" => rate1, "RATE2" => rate2, "RATE3" => rate3, "RATE4" => rate4, "RATE5" => rate5, "RATE6" => rate6,
+    "RATE7" => rate7, "RATE8" => rate8, "RATE9" => rate9, "RATE10" => rate10, "RATE11" => rate11, "RATE12" => rate12,
+    "STATUS" => status, "NSTATUS" => normal_status, "METERD" => meter_end, "STYPE" => device_type, "NAME" => name,
+]);
+given_fields!(Transformer, [
+    "I" => from_bus, "J" => to_bus, "K" => tertiary_bus, "CKT" => circuit, "CW" => cw, "CZ" => cz, "CM" => cm,
+    "MAG1" => mag1, "MAG2" => mag2, "NMETER" => metered_end, "NAME" => name, "ST" => status,
+    "O1" => owner1, "F1" => owner1_percent, "O2" => owner2, "F2" => owner2_percent, "O3" => owner3, "F3" => owner3_percent,
+    "O4" => owner4, "F4" => owner4_percent, "VECGRP" => vector_group, "ZCOD" => zcod, "DESC" => description,
+], [
+    "R12" => r12, "X12" => x12, "SBASE12" => sbase12, "R23" => r23, "X23" => x23, "SBASE23" => sbase23,
+    "R31" => r31, "X31" => x31, "SBASE31" => sbase31, "VMSTAR" => star_vm, "ANSTAR" => star_ang,
+], [
+    "WINDV1" => winding_1_volt, "NOMV1" => nominal_volt1, "ANG1" => angle1,
+    "RATE1" => w1_rate1, "RATE2" => w1_rate2, "RATE3" => w1_rate3, "RATE4" => w1_rate4, "RATE5" => w1_rate5, "RATE6" => w1_rate6,
+    "RATE7" => w1_rate7, "RATE8" => w1_rate8, "RATE9" => w1_rate9, "RATE10" => w1_rate10, "RATE11" => w1_rate11, "RATE12" => w1_rate12,
+    "COD1" => control_mode_1, "CONT1" => controlled_bus_id_1, "RMA1" => rma1, "RMI1" => rmi1, "VMA1" => vma1, "VMI1" => vmi1,
+    "NTP1" => tap_positions_1, "TAB1" => impedance_correction_table_1, "CR1" => load_drop_comp_r1, "CX1" => load_drop_comp_x1,
+    "CNXA1" => connection_ang_1, "TAPSTEP1" => tap_step_1,
+], [
+    "WINDV2" => winding_2_volt, "NOMV2" => nominal_volt2, "ANG2" => angle2,
+    "RATE1" => w2_rate1, "RATE2" => w2_rate2, "RATE3" => w2_rate3, "RATE4" => w2_rate4, "RATE5" => w2_rate5, "RATE6" => w2_rate6,
+    "RATE7" => w2_rate7, "RATE8" => w2_rate8, "RATE9" => w2_rate9, "RATE10" => w2_rate10, "RATE11" => w2_rate11, "RATE12" => w2_rate12,
+    "COD2" => control_mode_2, "CONT2" => controlled_bus_id_2, "RMA2" => rma2, "RMI2" => rmi2, "VMA2" => vma2, "VMI2" => vmi2,
+    "NTP2" => tap_positions_2, "TAB2" => impedance_correction_table_2, "CR2" => load_drop_comp_r2, "CX2" => load_drop_comp_x2,
+    "CNXA2" => connection_ang_2, "TAPSTEP2" => tap_step_2,
+], [
+    "WINDV3" => winding_3_volt, "NOMV3" => nominal_volt3, "ANG3" => angle3,
+    "RATE1" => w3_rate1, "RATE2" => w3_rate2, "RATE3" => w3_rate3, "RATE4" => w3_rate4, "RATE5" => w3_rate5, "RATE6" => w3_rate6,
+    "RATE7" => w3_rate7, "RATE8" => w3_rate8, "RATE9" => w3_rate9, "RATE10" => w3_rate10, "RATE11" => w3_rate11, "RATE12" => w3_rate12,
+    "COD3" => control_mode_3, "CONT3" => controlled_bus_id_3, "RMA3" => rma3, "RMI3" => rmi3, "VMA3" => vma3, "VMI3" => vmi3,
+    "NTP3" => tap_positions_3, "TAB3" => impedance_correction_table_3, "CR3" => load_drop_comp_r3, "CX3" => load_drop_comp_x3,
+    "CNXA3" => connection_ang_3, "TAPSTEP3" => tap_step_3,
+]);
+given_fields!(Area, ["I" => area_id, "ISW" => swing_bus_id, "PDES" => desired_interchange, "PTOL" => mw_tolerance, "ARNAME" => name]);
+given_fields!(TwoTerminalDc, [
+    "NAME" => name, "MDC" => power_ctrl_mode, "RDC" => resistance, "SETVL" => sending_pow, "VSCHD" => voltage,
+    "VCMOD" => voltage_ctrl_mode, "RCOMP" => r_comp, "DELTI" => dc_pow_margin, "METER" => metered_end, "DCVMIN" => min_volt,
+    "CCCITMX" => iteration_limit, "CCCACC" => acceleration_fac,
+], [
+    "IPR" => rec_bus_id, "NBR" => rec_num_bridges, "ANMXR" => rec_max_firing_angle, "ANMNR" => rec_min_firing_angle,
+    "RCR" => rec_comm_xfmr_r, "XCR" => rec_comm_xfmr_x, "EBASR" => rec_base_volt_ac, "TRR" => rec_xfmr_ratio, "TAPR" => rec_tap_setting,
+    "TMXR" => rec_max_tap_set, "TMNR" => rec_min_tap_set, "STPR" => rec_tap_step, "ICR" => rec_comm_bus_id, "NDR" => rec_comm_bus_node,
+    "IFR" => rec_from_bus, "ITR" => rec_to_bus, "IDR" => rec_ckt_id, "XCAPR" => rec_comm_cap_x,
+], [
+    "IPI" => inv_bus_id, "NBI" => inv_num_bridges, "ANMXI" => inv_max_firing_angle, "ANMNI" => inv_min_firing_angle,
+    "RCI" => inv_comm_xfmr_r, "XCI" => inv_comm_xfmr_x, "EBASI" => inv_base_volt_ac, "TRI" => inv_xfmr_ratio, "TAPI" => inv_tap_setting,
+    "TMXI" => inv_max_tap_set, "TMNI" => inv_min_tap_set, "STPI" => inv_tap_step, "ICI" => inv_comm_bus_id, "NDI" => inv_comm_bus_node,
+    "IFI" => inv_from_bus, "ITI" => inv_to_bus, "IDI" => inv_ckt_id, "XCAPI" => inv_comm_cap_x,
+]);
+given_fields!(VSCDc, [
+    "NAME" => name, "MDC" => status, "RDC" => resistance,
+    "O1" => owner1, "F1" => owner1_percent, "O2" => owner2, "F2" => owner2_percent, "O3" => owner3, "F3" => owner3_percent,
+    "O4" => owner4, "F4" => owner4_percent,
+], [
+    "IBUS" => converter_1_bus_id, "TYPE" => converter_1_dc_control, "MODE" => converter_1_ac_control, "DCSET" => dc_setpoint_1,
+    "ACSET" => ac_setpoint_1, "ALOSS" => cont_loss_coeff_1, "BLOSS" => prop_loss_coeff_1, "MINLOSS" => min_converter_loss_1,
+    "SMAX" => mva_rating_1, "IMAX" => ac_rating_1, "PWF" => power_weighting_factor_1, "MAXQ" => max_reac_1, "MINQ" => min_reac_1,
+    "VSREG" => control_bus_id_1, "NREG" => control_node_1, "RMPCT" => control_bus_percent_mvar_1,
+], [
+    "IBUS" => converter_2_bus_id, "TYPE" => converter_2_dc_control, "MODE" => converter_2_ac_control, "DCSET" => dc_setpoint_2,
+    "ACSET" => ac_setpoint_2, "ALOSS" => cont_loss_coeff_2, "BLOSS" => prop_loss_coeff_2, "MINLOSS" => min_converter_loss_2,
+    "SMAX" => mva_rating_2, "IMAX" => ac_rating_2, "PWF" => power_weighting_factor_2, "MAXQ" => max_reac_2, "MINQ" => min_reac_2,
+    "VSREG" => control_bus_id_2, "NREG" => control_node_2, "RMPCT" => control_bus_percent_mvar_2,
+]);
+given_fields!(MultiSectionLine, [
+    "I" => from_bus, "J" => to_bus, "CKT" => circuit, "MET" => meter_end,
+    "DUM1" => dummy_bus_ids[0], "DUM2" => dummy_bus_ids[1], "DUM3" => dummy_bus_ids[2], "DUM4" => dummy_bus_ids[3], "DUM5" => dummy_bus_ids[4],
+    "DUM6" => dummy_bus_ids[5], "DUM7" => dummy_bus_ids[6], "DUM8" => dummy_bus_ids[7], "DUM9" => dummy_bus_ids[8],
+]);
+given_fields!(Zone, ["I" => zone_id, "NAME" => zone_name]);
+given_fields!(InterAreaTransfer, ["ARFROM" => from_area, "ARTO" => to_area, "TRID" => transfer_id, "PTRAN" => power_transfer]);
+given_fields!(Owner, ["I" => owner_id, "OWNAME" => owner_name]);
+given_fields!(Facts, [
+    "NAME" => deivce_name, "I" => from_bus, "J" => to_bus, "MODE" => control_mode, "PDES" => desired_active_power,
+    "QDES" => desired_reactive_power, "VSET" => from_bus_volt_setpoint, "SHMX" => max_shunt_current, "TRMX" => max_bridge_active_power,
+    "VTMN" => to_bus_min_voltage, "VTMX" => to_bus_max_voltage, "VSMX" => max_series_voltage, "IMX" => max_series_current,
+    "LINX" => series_reactance, "RMPCT" => shunt_mvar_share, "OWNER" => owner, "SET1" => series_setpoint_1, "SET2" => series_setpoint_2,
+    "VSREF" => volt_ref_code, "FCREG" => regulated_bus_id, "NREG" => regulated_bus_node, "MNAME" => master_device_name,
+]);
+given_fields!(SwitchedShunt, [
+    "I" => bus_id, "ID" => id, "MODSW" => control_mode, "ADJM" => adjust_method, "STAT" => status, "VSWHI" => upper_limit,
+    "VSWLO" => x_lower_limit, "SWREG" => controlled_bus_id, "NREG" => controlled_bus_node, "RMPCT" => mvar_contribution_perc,
+    "RMIDNT" => controlled_bus_name, "BINIT" => b_init,
+    "S1" => block_status[0], "S2" => block_status[1], "S3" => block_status[2], "S4" => block_status[3],
+    "S5" => block_status[4], "S6" => block_status[5], "S7" => block_status[6], "S8" => block_status[7],
+    "N1" => steps[0], "N2" => steps[1], "N3" => steps[2], "N4" => steps[3], "N5" => steps[4], "N6" => steps[5], "N7" => steps[6], "N8" => steps[7],
+    "B1" => b_increment[0], "B2" => b_increment[1], "B3" => b_increment[2], "B4" => b_increment[3],
+    "B5" => b_increment[4], "B6" => b_increment[5], "B7" => b_increment[6], "B8" => b_increment[7],
+]);
+given_fields!(InductionMachine, [
+    "I" => bus_id, "ID" => id, "STAT" => status, "SCODE" => standard_code, "DCODE" => design_code, "AREA" => area, "ZONE" => zone,
+    "OWNER" => owner, "TCODE" => torque_code, "BCODE" => base_power_code, "MBASE" => sbase, "RATEKV" => rated_voltage,
+    "PCODE" => power_code, "PSET" => sched_active_power, "H" => inertia,
+    "A" => torque_params[0], "B" => torque_params[1], "D" => torque_params[2], "E" => torque_params[3],
+    "RA" => arm_resistance, "XA" => arm_reactance, "XM" => mag_reactance, "R1" => windng1_resist, "X1" => windng1_react,
+    "R2" => windng2_resist, "X2" => windng2_react, "X3" => windng3_react, "E1" => volt_point_1, "SE1" => v_sat1,
+    "E2" => volt_point_2, "SE2" => v_sat2, "IA1" => stator_current_1, "IA2" => stator_current_2, "XAMULT" => saturation_mult,
+]);
+//The sections of variable length have no source lines and are replaced whole
+impl GivenFields for ImpedanceCorrectionTable {}
+impl GivenFields for MultiTermDCLine {}
+impl GivenFields for GneDevice {}
+impl GivenFields for Substation {}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::psse::{error::Diagnostic, parsers::fast_parser::{parse_bytes, parse_fast}};
+
+    use super::*;
+
+    #[test]
+    fn apply_change_file_v33() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let (mut data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(format!("{}/test-cases/TEST_parse_psse_v33.RAW", dir), &ParseOptions::strict()).unwrap();
+        let buses: usize = data.buses.len();
+        let report: ChangeReport = apply_change_file(&mut data, format!("{}/test-cases/TEST_change_v33.RAW", dir)).unwrap();
+        assert_eq!(report.added, vec![
+            ChangedRecord { section: PSSEDatatype::Bus, record: "9001".to_string() },
+            ChangedRecord { section: PSSEDatatype::Branch, record: "347-9001 '1'".to_string() },
+        ]);
+        //The branch is given from its other end
+        assert_eq!(report.modified, vec![
+            ChangedRecord { section: PSSEDatatype::Bus, record: "347".to_string() },
+            ChangedRecord { section: PSSEDatatype::Branch, record: "347-876543 '1'".to_string() },
+        ]);
+        assert_eq!(data.buses.len(), buses + 1);
+        assert_eq!(data.buses[0].name, "RENAMED".to_string());
+        assert_eq!(data.branches[0].from_bus, 876543);
+        assert_eq!(data.branches[0].r, 0.002);
+        //The case header is kept
+        assert_eq!(data.header.ic, 0);
+
+        //Base cases are not change files
+        let err: ParseError = apply_change_file(&mut data, format!("{}/test-cases/TEST_parse_psse_v33.RAW", dir)).unwrap_err();
+        assert!(matches!(err, ParseError::NotChangeFile { ic: 0 }));
+    }
+
+    #[test]
+    fn blank_and_omitted_fields_kept() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let (mut data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(format!("{}/test-cases/TEST_parse_psse_v33.RAW", dir), &ParseOptions::strict()).unwrap();
+        let (bus, load) = (data.buses[0].clone(), data.loads[0].clone());
+        //Of two records with the same key the first one is changed
+        data.buses.push(bus.clone());
+        let raw: &str = "1, 100.00, 33, 0, 1, 60.00 / Change case\n\n\n347,'NEWNAME'\n0 / end of bus data, begin load data\n347,'1 ',,,,  20.0\n0 / end of load data\nQ\n";
+        let (changes, source, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(raw.as_bytes(), &ParseOptions::strict()).unwrap();
+        let report: ChangeReport = apply_changes(&mut data, changes, &source);
+        assert_eq!(report.modified, vec![
+            ChangedRecord { section: PSSEDatatype::Bus, record: "347".to_string() },
+            ChangedRecord { section: PSSEDatatype::Load, record: "347 '1'".to_string() },
+        ]);
+        assert_eq!(data.buses[0].name, "NEWNAME".to_string());
+        assert_eq!(data.buses[0].base_kv, bus.base_kv);
+        assert_eq!(data.buses[0].area, bus.area);
+        assert_eq!(data.buses[0].vm_pu, bus.vm_pu);
+        assert_eq!(data.buses.last(), Some(&bus));
+        //Blank fields in the middle of the line keep their values too
+        assert_eq!(data.loads[0].pl_mw, 20.0);
+        assert_eq!(data.loads[0].status, load.status);
+        assert_eq!(data.loads[0].area, load.area);
+        assert_eq!(data.loads[0].ql_mvar, load.ql_mvar);
+        assert_eq!(data.loads[0].owner, load.owner);
+    }
+
+    #[test]
+    fn fields_merged_by_line() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let (mut data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(format!("{}/test-cases/TEST_parse_psse_v33.RAW", dir), &ParseOptions::strict()).unwrap();
+        let transformer: Transformer = data.transformers[0].clone();
+        let raw: &str = "1, 100.00, 33, 0, 1, 60.00 / Change case\n\n\n\
+            0 / end of bus data, begin load data\n0 / end of load data, begin fixed shunt data\n0 / end of fixed shunt data, begin generator data\n\
+            0 / end of generator data, begin branch data\n0 / end of branch data, begin transformer data\n\
+            24568, 24569, 0, '1 '\n, 0.3\n1.05\n1.0\n0 / end of transformer data\nQ\n";
+        let (changes, source, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(raw.as_bytes(), &ParseOptions::strict()).unwrap();
+        let report: ChangeReport = apply_changes(&mut data, changes, &source);
+        assert_eq!(report.modified, vec![ChangedRecord { section: PSSEDatatype::Transformer, record: "24568-24569-0 '1'".to_string() }]);
+        //Only the fields each line gives are taken
+        assert_eq!(data.transformers[0].x12, 0.3);
+        assert_eq!(data.transformers[0].r12, transformer.r12);
+        assert_eq!(data.transformers[0].winding_1_volt, 1.05);
+        assert_eq!(data.transformers[0].w1_rate1, transformer.w1_rate1);
+        assert_eq!(data.transformers[0].rma1, transformer.rma1);
+        assert_eq!(data.transformers[0].winding_2_volt, 1.0);
+        assert_eq!(data.transformers[0].nominal_volt2, transformer.nominal_volt2);
+        assert_eq!(data.transformers[0].name, transformer.name);
+    }
+}
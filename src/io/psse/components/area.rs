@@ -1,6 +1,6 @@
-use std::str::from_utf8;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::io::psse::{error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -34,19 +34,17 @@ pub struct Area {
     pub name: String,
 }
 
-pub fn parse_areas(lines: &[&[u8]]) -> Vec<Area> {
+pub fn parse_areas(lines: &[&[u8]], ctx: &SectionContext) -> Result<Vec<Area>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
-    lines.par_iter().filter_map(|line_bytes| {
-        from_utf8(line_bytes).ok().and_then(|line| {
-            let parts: Vec<&str> = line.split(",").map(|s| s.trim()).collect();
-            Some(Area{
-                area_id: parts[0].parse().unwrap_or(0),
-                swing_bus_id: parts[1].parse().unwrap_or(0),
-                desired_interchange: parts[2].parse().unwrap_or(0.0),
-                mw_tolerance: parts[3].parse().unwrap_or(0.0),
-                name: parts[4].replace("'", "").trim().to_string(),
-            })
+    if lines.is_empty() {return Ok(Vec::new());}
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::Area)?;
+        Ok(Area{
+            area_id: rec.required(0, "I", 0)?,
+            swing_bus_id: rec.required(1, "ISW", 0)?,
+            desired_interchange: rec.required(2, "PDES", 0.0)?,
+            mw_tolerance: rec.required(3, "PTOL", 0.0)?,
+            name: rec.required_str(4, "ARNAME")?,
         })
     }).collect()
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
-use std::str::from_utf8;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::io::psse::{components::codes::Status, error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -22,6 +22,10 @@ pub struct Branch {
     /// ### Type: String
     /// ### Default: '1'
     pub circuit: String,
+    /// ## BRTYPE: Branch type, from V36
+    /// ### Type: String
+    /// ### Default: ""
+    pub branch_type: String,
     /// ## R: Branch resistance
     /// ### Type: f64
     /// ### Unit: p.u.
@@ -181,51 +185,60 @@ pub struct Branch {
     pub owner4_percent: f64,
 }
 
-pub fn parse_lines(lines: &[&[u8]], psse_version: i8) -> Vec<Branch> {
+impl Branch {
+    /// Status of the ST code
+    pub fn service_status(&self) -> Status {
+        Status::from(self.status)
+    }
+}
+
+pub fn parse_lines(lines: &[&[u8]], ctx: &SectionContext, psse_version: i8) -> Result<Vec<Branch>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
+    if lines.is_empty() {return Ok(Vec::new());}
     // a bool to int to add to the line parsing since V34 introduced new variables in the middle of everything
     let parse_adder: usize = (psse_version >= 34) as usize;
     let rating_adder: usize = parse_adder * 10;
-    lines.par_iter().filter_map(|line_bytes| {
-        from_utf8(line_bytes).ok().and_then(|line| {
-            let parts: Vec<&str> = line.split(",").map(|s| s.trim()).collect();
-            Some(Branch{
-                from_bus: parts[0].parse().unwrap_or(0),
-                to_bus: parts[1].parse().unwrap_or(0),
-                circuit: parts[2].replace("'", "").trim().to_string(),
-                r: parts[3].parse().unwrap_or(0.0),
-                x: parts[4].parse().unwrap_or(0.0001),
-                b: parts[5].parse().unwrap_or(0.0),
-                name: (psse_version >= 34).then_some(parts[6].replace("'", "").trim().to_string()).unwrap_or(" ".to_string()),
-                rate1: parts[6 + parse_adder].parse().unwrap_or(0.0),
-                rate2: parts[7 + parse_adder].parse().unwrap_or(0.0),
-                rate3: parts[8 + parse_adder].parse().unwrap_or(0.0),
-                rate4: (psse_version >= 34).then_some(parts[9 + parse_adder].parse().unwrap_or(0.0)).unwrap_or(0.0),
-                rate5: (psse_version >= 34).then_some(parts[10 + parse_adder].parse().unwrap_or(0.0)).unwrap_or(0.0),
-                rate6: (psse_version >= 34).then_some(parts[11 + parse_adder].parse().unwrap_or(0.0)).unwrap_or(0.0),
-                rate7: (psse_version >= 34).then_some(parts[12 + parse_adder].parse().unwrap_or(0.0)).unwrap_or(0.0),
-                rate8: (psse_version >= 34).then_some(parts[13 + parse_adder].parse().unwrap_or(0.0)).unwrap_or(0.0),
-                rate9: (psse_version >= 34).then_some(parts[14 + parse_adder].parse().unwrap_or(0.0)).unwrap_or(0.0),
-                rate10: (psse_version >= 34).then_some(parts[15 + parse_adder].parse().unwrap_or(0.0)).unwrap_or(0.0),
-                rate11: (psse_version >= 34).then_some(parts[16 + parse_adder].parse().unwrap_or(0.0)).unwrap_or(0.0),
-                rate12: (psse_version >= 34).then_some(parts[17 + parse_adder].parse().unwrap_or(0.0)).unwrap_or(0.0),
-                gi: parts[9 + rating_adder].parse().unwrap_or(0.0),
-                bi: parts[10 + rating_adder].parse().unwrap_or(0.0),
-                gj: parts[11 + rating_adder].parse().unwrap_or(0.0),
-                bj: parts[12 + rating_adder].parse().unwrap_or(0.0),
-                status: parts[13 + rating_adder].parse().unwrap_or(1),
-                meter_end: parts[14 + rating_adder].parse().unwrap_or(0),
-                length: parts[15 + rating_adder].parse().unwrap_or(0.0),
-                owner1: parts[16 + rating_adder].parse().unwrap_or(1),
-                owner1_percent: parts[17 + rating_adder].parse().unwrap_or(1.0),
-                owner2: parts.get(18 + rating_adder).and_then(|s| s.parse().ok()).unwrap_or(0),
-                owner2_percent: parts.get(19 + rating_adder).and_then(|s| s.parse().ok()).unwrap_or(1.0),
-                owner3: parts.get(20 + rating_adder).and_then(|s| s.parse().ok()).unwrap_or(0),
-                owner3_percent: parts.get(21 + rating_adder).and_then(|s| s.parse().ok()).unwrap_or(1.0),
-                owner4: parts.get(22 + rating_adder).and_then(|s| s.parse().ok()).unwrap_or(0),
-                owner4_percent: parts.get(23 + rating_adder).and_then(|s| s.parse().ok()).unwrap_or(1.0),
-            })
+    // MET was added in V32, shifting the length and owners
+    let meter_adder: usize = rating_adder + (psse_version >= 32) as usize;
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::Branch)?;
+        Ok(Branch{
+            from_bus: rec.required(0, "I", 0)?,
+            to_bus: rec.required(1, "J", 0)?,
+            circuit: rec.required_str(2, "CKT")?,
+            // BRTYPE follows CKT from V36
+            branch_type: if psse_version >= 36 { rec.optional_str(34, "BRTYPE", "")? } else { String::new() },
+            r: rec.required(3, "R", 0.0)?,
+            x: rec.required(4, "X", 0.0001)?,
+            b: rec.required(5, "B", 0.0)?,
+            name: if psse_version >= 34 { rec.required_str(6, "NAME")? } else { " ".to_string() },
+            rate1: rec.required(6 + parse_adder, "RATE1", 0.0)?,
+            rate2: rec.required(7 + parse_adder, "RATE2", 0.0)?,
+            rate3: rec.required(8 + parse_adder, "RATE3", 0.0)?,
+            rate4: if psse_version >= 34 { rec.required(9 + parse_adder, "RATE4", 0.0)? } else { 0.0 },
+            rate5: if psse_version >= 34 { rec.required(10 + parse_adder, "RATE5", 0.0)? } else { 0.0 },
+            rate6: if psse_version >= 34 { rec.required(11 + parse_adder, "RATE6", 0.0)? } else { 0.0 },
+            rate7: if psse_version >= 34 { rec.required(12 + parse_adder, "RATE7", 0.0)? } else { 0.0 },
+            rate8: if psse_version >= 34 { rec.required(13 + parse_adder, "RATE8", 0.0)? } else { 0.0 },
+            rate9: if psse_version >= 34 { rec.required(14 + parse_adder, "RATE9", 0.0)? } else { 0.0 },
+            rate10: if psse_version >= 34 { rec.required(15 + parse_adder, "RATE10", 0.0)? } else { 0.0 },
+            rate11: if psse_version >= 34 { rec.required(16 + parse_adder, "RATE11", 0.0)? } else { 0.0 },
+            rate12: if psse_version >= 34 { rec.required(17 + parse_adder, "RATE12", 0.0)? } else { 0.0 },
+            gi: rec.required(9 + rating_adder, "GI", 0.0)?,
+            bi: rec.required(10 + rating_adder, "BI", 0.0)?,
+            gj: rec.required(11 + rating_adder, "GJ", 0.0)?,
+            bj: rec.required(12 + rating_adder, "BJ", 0.0)?,
+            status: rec.required(13 + rating_adder, "ST", 1)?,
+            meter_end: if psse_version >= 32 { rec.required(14 + rating_adder, "MET", 0)? } else { 1 },
+            length: rec.required(14 + meter_adder, "LEN", 0.0)?,
+            owner1: rec.required(15 + meter_adder, "O1", 1)?,
+            owner1_percent: rec.required(16 + meter_adder, "F1", 1.0)?,
+            owner2: rec.optional(17 + meter_adder, "O2", 0)?,
+            owner2_percent: rec.optional(18 + meter_adder, "F2", 1.0)?,
+            owner3: rec.optional(19 + meter_adder, "O3", 0)?,
+            owner3_percent: rec.optional(20 + meter_adder, "F3", 1.0)?,
+            owner4: rec.optional(21 + meter_adder, "O4", 0)?,
+            owner4_percent: rec.optional(22 + meter_adder, "F4", 1.0)?,
         })
     }).collect()
 }
@@ -238,8 +251,8 @@ mod tests {
     fn parse_branch_v35() {
         let branch_str: &'static str = "1111,   2222,'5 ',9.087000E-03,8.765000E-03,   1.22295,'                                        ',   550.00,   890.00,     700.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,   0.00000,   0.00000,   0.00000,   0.00000, 2,3,   5.20000, 863,  1.0000,";
         let lines: Vec<&[u8]> = branch_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<Branch> = parse_lines(&lines, 35);
-        let branch: Option<&Branch> = binding.get(0);
+        let binding: Vec<Branch> = parse_lines(&lines, &SectionContext::default(), 35).unwrap();
+        let branch: Option<&Branch> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(branch.unwrap().from_bus, 1111);
@@ -254,8 +267,8 @@ mod tests {
     fn parse_branch_v33() {
         let branch_str: &'static str = " 1111,   2222,'5 ',9.087000E-03,8.765000E-03,   1.22295,   550.00,   890.00,     700.00,   0.00000,   0.00000,   0.00000,   0.00000, 2,3,   5.20000, 863,  1.0000,";
         let lines: Vec<&[u8]> = branch_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<Branch> = parse_lines(&lines, 33);
-        let branch: Option<&Branch> = binding.get(0);
+        let binding: Vec<Branch> = parse_lines(&lines, &SectionContext::default(), 33).unwrap();
+        let branch: Option<&Branch> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(branch.unwrap().from_bus, 1111);
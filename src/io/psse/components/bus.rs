@@ -1,6 +1,6 @@
-use std::str::from_utf8;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::io::psse::{components::{codes::BusType, fixed_shunt::FixedShunt}, error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -16,6 +16,10 @@ pub struct Bus {
     /// ### Type: String
     /// ### Default: twelve (12) blanks
     pub name: String,
+    /// ## LONGNAME: Long bus name, from V36
+    /// ### Type: String
+    /// ### Default: ""
+    pub long_name: String,
     /// ## BASKV: Bus base voltage (kV)
     /// ### Type: f64
     /// ### Default: 0.0
@@ -79,31 +83,82 @@ pub struct Bus {
     pub evlo: f64,
 }
 
-pub fn parse_buses(lines: &[&[u8]]) -> Vec<Bus> {
+impl Bus {
+    /// Bus type of the IDE code
+    pub fn bus_type(&self) -> BusType {
+        BusType::from(self.type_code)
+    }
+}
+
+pub fn parse_buses(lines: &[&[u8]], ctx: &SectionContext, psse_version: i8) -> Result<Vec<Bus>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
-    lines.par_iter().filter_map(|line_bytes| {
-        from_utf8(line_bytes).ok().and_then(|line| {
-            let parts: Vec<&str> = line.split(",").map(|s| s.trim()).collect();
-            Some(Bus{
-                id: parts[0].parse().unwrap_or(0),
-                name: parts[1].replace("'", "").trim().to_string(),
-                base_kv: parts[2].parse().unwrap_or(999.99),
-                type_code: parts[3].parse().unwrap_or(1),
-                area: parts[4].parse().unwrap_or(0),
-                zone: parts[5].parse().unwrap_or(0),
-                owner: parts[6].parse().unwrap_or(0),
-                vm_pu: parts[7].parse().unwrap_or(0.95),
-                va_deg: parts[8].parse().unwrap_or(0.0),
-                nvhi: parts.get(9).and_then(|s| s.parse().ok()).unwrap_or(1.1),
-                nvlo: parts.get(10).and_then(|s| s.parse().ok()).unwrap_or(0.9),
-                evhi: parts.get(11).and_then(|s| s.parse().ok()).unwrap_or(1.1),
-                evlo: parts.get(12).and_then(|s| s.parse().ok()).unwrap_or(0.9),
+    if lines.is_empty() {return Ok(Vec::new());}
+    // V30 has the bus shunt (GL, BL) after IDE and the owner at the end, which moved to the fixed shunt data in V31
+    if psse_version <= 30 {
+        return lines.par_iter().enumerate().map(|(i, line_bytes)| {
+            let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::Bus)?;
+            Ok(Bus{
+                id: rec.required(0, "I", 0)?,
+                name: rec.required_str(1, "NAME")?,
+                long_name: String::new(),
+                base_kv: rec.required(2, "BASKV", 999.99)?,
+                type_code: rec.required(3, "IDE", 1)?,
+                area: rec.required(6, "AREA", 0)?,
+                zone: rec.required(7, "ZONE", 0)?,
+                vm_pu: rec.required(8, "VM", 0.95)?,
+                va_deg: rec.required(9, "VA", 0.0)?,
+                owner: rec.required(10, "OWNER", 0)?,
+                nvhi: 1.1,
+                nvlo: 0.9,
+                evhi: 1.1,
+                evlo: 0.9,
             })
+        }).collect();
+    }
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::Bus)?;
+        Ok(Bus{
+            id: rec.required(0, "I", 0)?,
+            name: rec.required_str(1, "NAME")?,
+            // V36 inserted LONGNAME after NAME, it is read through the column map
+            long_name: if psse_version >= 36 { rec.optional_str(13, "LONGNAME", "")? } else { String::new() },
+            base_kv: rec.required(2, "BASKV", 999.99)?,
+            type_code: rec.required(3, "IDE", 1)?,
+            area: rec.required(4, "AREA", 0)?,
+            zone: rec.required(5, "ZONE", 0)?,
+            owner: rec.required(6, "OWNER", 0)?,
+            vm_pu: rec.required(7, "VM", 0.95)?,
+            va_deg: rec.required(8, "VA", 0.0)?,
+            nvhi: rec.optional(9, "NVHI", 1.1)?,
+            nvlo: rec.optional(10, "NVLO", 0.9)?,
+            evhi: rec.optional(11, "EVHI", 1.1)?,
+            evlo: rec.optional(12, "EVLO", 0.9)?,
         })
     }).collect()
 }
 
+/// Reads the bus shunts of a V30 bus section as fixed shunts with ID 1, skipping buses without a shunt
+pub fn parse_bus_shunts(lines: &[&[u8]], ctx: &SectionContext) -> Result<Vec<FixedShunt>, ParseError> {
+    //Check if there is even data before proceeding
+    if lines.is_empty() {return Ok(Vec::new());}
+    let shunts: Vec<Option<FixedShunt>> = lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::Bus)?;
+        let gl_mw: f64 = rec.required(4, "GL", 0.0)?;
+        let bl_mvar: f64 = rec.required(5, "BL", 0.0)?;
+        if gl_mw == 0.0 && bl_mvar == 0.0 {
+            return Ok(None);
+        }
+        Ok(Some(FixedShunt{
+            bus_id: rec.required(0, "I", 0)?,
+            id: "1".to_string(),
+            status: 1,
+            gl_mw,
+            bl_mvar,
+        }))
+    }).collect::<Result<_, ParseError>>()?;
+    Ok(shunts.into_iter().flatten().collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,15 +167,32 @@ mod tests {
     fn parse_bus_v35() {
         let bus_str: &'static str = "11223, 'TESTBUS',   235.0000,   5, 806, 5560,  890,   1.026054109, 228.564689898,     1.1000,     0.9000,     1.1000,     0.9000";
         let buses: Vec<&[u8]> = bus_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<Bus> = parse_buses(&buses);
-        let bus: Option<&Bus> = binding.get(0);
+        let binding: Vec<Bus> = parse_buses(&buses, &SectionContext::default(), 35).unwrap();
+        let bus: Option<&Bus> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(bus.unwrap().id, 11223);
         assert_eq!(bus.unwrap().name, "TESTBUS".to_string());
         assert_eq!(bus.unwrap().base_kv, 235.0);
         assert_eq!(bus.unwrap().type_code, 5);
+        assert_eq!(bus.unwrap().bus_type(), BusType::Unknown(5));
         assert_eq!(bus.unwrap().evhi, 1.1);
         assert_eq!(bus.unwrap().evlo, 0.9);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn parse_bus_v30() {
+        let bus_str: &'static str = "  101,'NUC-A       ',  21.6000,2,     1.500,    -2.250,   1,   2,1.02000,  -4.5000,   3";
+        let buses: Vec<&[u8]> = bus_str.lines().map(|line| line.as_bytes()).collect();
+        let binding: Vec<Bus> = parse_buses(&buses, &SectionContext::default(), 30).unwrap();
+        let bus: Option<&Bus> = binding.first();
+        //To avoid checking everything, check bits and pieces to make sure things are in the right place
+        assert_eq!(bus.unwrap().name, "NUC-A".to_string());
+        assert_eq!(bus.unwrap().area, 1);
+        assert_eq!(bus.unwrap().zone, 2);
+        assert_eq!(bus.unwrap().vm_pu, 1.02);
+        assert_eq!(bus.unwrap().owner, 3);
+        let shunts: Vec<FixedShunt> = parse_bus_shunts(&buses, &SectionContext::default()).unwrap();
+        assert_eq!(shunts, vec![FixedShunt { bus_id: 101, id: "1".to_string(), status: 1, gl_mw: 1.5, bl_mvar: -2.25 }]);
+    }
+}
@@ -0,0 +1,289 @@
+//! Typed versions of the status and code fields of the components
+//!
+//! The component structs keep the raw codes read from the case so they are written back unchanged. Each enum
+//! converts from and to its raw code without loss, codes outside the PSS/E range are kept in an `Unknown` variant.
+//!
+//! ```
+//! use pf_gat_parser::io::psse::components::codes::BusType;
+//!
+//! assert_eq!(BusType::from(3), BusType::Swing);
+//! assert_eq!(i8::from(BusType::Unknown(7)), 7);
+//! ```
+
+//Declares a fieldless code enum with an Unknown fallback and its conversions from and to the raw code
+macro_rules! code_enum {
+    ($(#[$meta:meta])* $name:ident : $raw:ty { $($(#[$variant_meta:meta])* $variant:ident = $code:literal,)+ }) => {
+        $(#[$meta])*
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+            /// Code outside the PSS/E range, kept as read
+            Unknown($raw),
+        }
+
+        impl From<$raw> for $name {
+            fn from(code: $raw) -> Self {
+                match code {
+                    $($code => $name::$variant,)+
+                    code => $name::Unknown(code),
+                }
+            }
+        }
+
+        impl From<$name> for $raw {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => $code,)+
+                    $name::Unknown(code) => code,
+                }
+            }
+        }
+    };
+}
+
+code_enum! {
+    /// ## IDE: Bus type code
+    BusType: i8 {
+        /// 1 : Load / Passive Bus
+        Load = 1,
+        /// 2 : Generator / Plant Bus
+        Generator = 2,
+        /// 3 : Swing Bus
+        Swing = 3,
+        /// 4 : Disconnected Bus (isolated)
+        Isolated = 4,
+    }
+}
+
+code_enum! {
+    /// ## ST / STAT: Status of a branch, load, machine or shunt
+    Status: i8 {
+        /// 0 : Out-of-service
+        OutOfService = 0,
+        /// 1 : In-service
+        InService = 1,
+    }
+}
+
+code_enum! {
+    /// ## STAT: Transformer status
+    TransformerStatus: i8 {
+        /// 0 : Transformer out-of-service
+        OutOfService = 0,
+        /// 1 : Transformer in service
+        InService = 1,
+        /// 2 : Winding 2 out-of-service
+        Winding2OutOfService = 2,
+        /// 3 : Winding 3 out-of-service
+        Winding3OutOfService = 3,
+        /// 4 : Winding 1 out-of-service
+        Winding1OutOfService = 4,
+    }
+}
+
+code_enum! {
+    /// ## CW: Transformer winding data I/O code, the units of the turns ratios
+    WindingIoCode: i8 {
+        /// 1 : Off-nominal turns ratio in p.u. of the winding bus base voltage
+        BusBaseRatio = 1,
+        /// 2 : Winding voltage in kV
+        WindingVoltage = 2,
+        /// 3 : Off-nominal turns ratio in p.u. of nominal winding voltage, NOMV1, NOMV2, NOMV3
+        NominalVoltageRatio = 3,
+    }
+}
+
+code_enum! {
+    /// ## CZ: Transformer impedance data I/O code, the units of the winding impedances
+    ImpedanceIoCode: i8 {
+        /// 1 : Resistance and Reactance in p.u. on a system MVA base and winding voltage base
+        SystemBase = 1,
+        /// 2 : Resistance and Reactance in p.u. on a specified MVA base and winding voltage base
+        WindingBase = 2,
+        /// 3 : Transformer load loss in watts and impedance magnitude in p.u. on a specified MVA and winding voltage base
+        LoadLoss = 3,
+    }
+}
+
+code_enum! {
+    /// ## CM: Transformer magnetizing admittance data I/O code, the units of MAG1 and MAG2
+    MagnetizingIoCode: i8 {
+        /// 1 : Complex admittance in p.u. on the system MVA base and winding 1 base voltage
+        SystemBase = 1,
+        /// 2 : No-load loss in watts and exciting current in p.u.
+        NoLoadLoss = 2,
+    }
+}
+
+code_enum! {
+    /// ## MODSW: Switched shunt control mode
+    SwitchedShuntControlMode: i8 {
+        /// 0 : Locked
+        Locked = 0,
+        /// 1 : Discrete adjustment, controlling the voltage locally or at the remote bus
+        DiscreteVoltage = 1,
+        /// 2 : Continuous adjustment, controlling the voltage locally or at the remote bus
+        ContinuousVoltage = 2,
+        /// 3 : Discrete adjustment, controlling the reactive power of the plant at the remote bus
+        PlantReactivePower = 3,
+        /// 4 : Discrete adjustment, controlling the reactive power at VSC-DC line at the remote bus
+        VscReactivePower = 4,
+        /// 5 : Discrete adjustment, controlling the admittance setting of the switched shunt at the remote bus
+        ShuntAdmittance = 5,
+        /// 6 : Discrete adjustment, controlling the reactive power of the FACTS device at the remote bus
+        FactsReactivePower = 6,
+    }
+}
+
+code_enum! {
+    /// ## MODE: FACTS control mode
+    FactsControlMode: i8 {
+        /// 0 : Out of service
+        OutOfService = 0,
+        /// 1 : In service
+        InService = 1,
+        /// 2 : Series link bypassed and shunt link operating as a STATCOM
+        Statcom = 2,
+        /// 3 : Series link operating at constant series impedance
+        ConstantImpedance = 3,
+        /// 4 : Series link operating at constant series voltage
+        ConstantVoltage = 4,
+        /// 5 : Master of device with PQ specified
+        PqMaster = 5,
+        /// 6 : Slave of device with PQ specified
+        PqSlave = 6,
+        /// 7 : Master of device with constant series voltage specified
+        VoltageMaster = 7,
+        /// 8 : Slave of device with constant series voltage specified
+        VoltageSlave = 8,
+    }
+}
+
+/// ## COD: Transformer tap control mode
+///
+/// The sign of the code is kept in `enabled`: a negative code keeps the control data of the winding
+/// with its automatic adjustment turned off.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TapControlMode {
+    /// 0 : Fixed tap and phase shift
+    Fixed,
+    /// ± 1 : Voltage control
+    Voltage { enabled: bool },
+    /// ± 2 : Reactive power flow control
+    ReactivePower { enabled: bool },
+    /// ± 3 : Active power flow control
+    ActivePower { enabled: bool },
+    /// ± 4 : Control of a DC line quantity
+    DcLine { enabled: bool },
+    /// ± 5 : Asymmetric active power flow control
+    AsymmetricActivePower { enabled: bool },
+    /// Code outside the PSS/E range, kept as read
+    Unknown(i32),
+}
+
+impl TapControlMode {
+    /// Whether the tap or phase shift is adjusted automatically during a solution
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            TapControlMode::Voltage { enabled }
+            | TapControlMode::ReactivePower { enabled }
+            | TapControlMode::ActivePower { enabled }
+            | TapControlMode::DcLine { enabled }
+            | TapControlMode::AsymmetricActivePower { enabled } => *enabled,
+            TapControlMode::Fixed | TapControlMode::Unknown(_) => false,
+        }
+    }
+}
+
+impl From<i32> for TapControlMode {
+    fn from(code: i32) -> Self {
+        let enabled: bool = code > 0;
+        match code.unsigned_abs() {
+            0 => TapControlMode::Fixed,
+            1 => TapControlMode::Voltage { enabled },
+            2 => TapControlMode::ReactivePower { enabled },
+            3 => TapControlMode::ActivePower { enabled },
+            4 => TapControlMode::DcLine { enabled },
+            5 => TapControlMode::AsymmetricActivePower { enabled },
+            _ => TapControlMode::Unknown(code),
+        }
+    }
+}
+
+impl From<TapControlMode> for i32 {
+    fn from(value: TapControlMode) -> Self {
+        let (code, enabled): (i32, bool) = match value {
+            TapControlMode::Fixed => return 0,
+            TapControlMode::Unknown(code) => return code,
+            TapControlMode::Voltage { enabled } => (1, enabled),
+            TapControlMode::ReactivePower { enabled } => (2, enabled),
+            TapControlMode::ActivePower { enabled } => (3, enabled),
+            TapControlMode::DcLine { enabled } => (4, enabled),
+            TapControlMode::AsymmetricActivePower { enabled } => (5, enabled),
+        };
+        if enabled { code } else { -code }
+    }
+}
+
+/// ## METER: Metered end of a two-terminal DC line
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MeteredEnd {
+    /// "I" : Meter at the inverter terminal
+    Inverter,
+    /// "R" : Meter at the rectifier terminal
+    Rectifier,
+    /// Code other than "I" or "R", kept as read
+    Unknown(String),
+}
+
+impl From<&str> for MeteredEnd {
+    fn from(code: &str) -> Self {
+        match code {
+            "I" => MeteredEnd::Inverter,
+            "R" => MeteredEnd::Rectifier,
+            code => MeteredEnd::Unknown(code.to_string()),
+        }
+    }
+}
+
+impl From<MeteredEnd> for String {
+    fn from(value: MeteredEnd) -> Self {
+        match value {
+            MeteredEnd::Inverter => "I".to_string(),
+            MeteredEnd::Rectifier => "R".to_string(),
+            MeteredEnd::Unknown(code) => code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip() {
+        for code in i8::MIN..=i8::MAX {
+            assert_eq!(i8::from(BusType::from(code)), code);
+            assert_eq!(i8::from(Status::from(code)), code);
+            assert_eq!(i8::from(TransformerStatus::from(code)), code);
+            assert_eq!(i8::from(WindingIoCode::from(code)), code);
+            assert_eq!(i8::from(ImpedanceIoCode::from(code)), code);
+            assert_eq!(i8::from(MagnetizingIoCode::from(code)), code);
+            assert_eq!(i8::from(SwitchedShuntControlMode::from(code)), code);
+            assert_eq!(i8::from(FactsControlMode::from(code)), code);
+            assert_eq!(i32::from(TapControlMode::from(code as i32)), code as i32);
+        }
+        assert_eq!(BusType::from(4), BusType::Isolated);
+        assert_eq!(BusType::from(0), BusType::Unknown(0));
+        assert_eq!(TapControlMode::from(-3), TapControlMode::ActivePower { enabled: false });
+        assert!(TapControlMode::from(1).is_enabled());
+        assert_eq!(TapControlMode::from(i32::MIN), TapControlMode::Unknown(i32::MIN));
+        assert_eq!(i32::from(TapControlMode::from(i32::MIN)), i32::MIN);
+        for code in ["I", "R", "X", ""] {
+            assert_eq!(String::from(MeteredEnd::from(code)), code);
+        }
+    }
+}
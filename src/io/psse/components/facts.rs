@@ -1,6 +1,6 @@
-use std::str::from_utf8;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::io::psse::{components::codes::FactsControlMode, error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -95,6 +95,16 @@ pub struct Facts {
     /// ## Range
     /// * 1 - 9999
     pub owner: i16,
+    /// ## SET1: Series element setpoint 1 (the real part of the constant series impedance or voltage, depending on the control mode)
+    /// ### Type: f64
+    /// ### Units: p.u.
+    /// ### Default: 0.0
+    pub series_setpoint_1: f64,
+    /// ## SET2: Series element setpoint 2 (the imaginary part of the constant series impedance or voltage, depending on the control mode)
+    /// ### Type: f64
+    /// ### Units: p.u.
+    /// ### Default: 0.0
+    pub series_setpoint_2: f64,
     /// ## VSREF: FACTS series voltage reference code
     /// ### Type: i8
     /// ### Default: 0
@@ -118,36 +128,44 @@ pub struct Facts {
     pub master_device_name: String
 }
 
-pub fn parse_facts(lines: &[&[u8]], psse_version: i8) -> Vec<Facts> {
+impl Facts {
+    /// Control mode of the MODE code
+    pub fn facts_control_mode(&self) -> FactsControlMode {
+        FactsControlMode::from(self.control_mode)
+    }
+}
+
+pub fn parse_facts(lines: &[&[u8]], ctx: &SectionContext, psse_version: i8) -> Result<Vec<Facts>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
+    if lines.is_empty() {return Ok(Vec::new());}
     let parse_adder: usize = (psse_version >= 34) as usize;
 
-    lines.par_iter().filter_map(|line_bytes| {
-        from_utf8(line_bytes).ok().and_then(|line| {
-            let parts: Vec<&str> = line.split(",").map(|s| s.trim()).collect();
-            Some(Facts{
-                deivce_name: parts[0].replace("'", "").replace("\"", "").trim().to_string(),
-                from_bus: parts[1].parse().unwrap_or(0),
-                to_bus: parts[2].parse().unwrap_or(0),
-                control_mode: parts[3].parse().unwrap_or(1),
-                desired_active_power: parts[4].parse().unwrap_or(0.0),
-                desired_reactive_power: parts[5].parse().unwrap_or(0.0),
-                from_bus_volt_setpoint: parts[6].parse().unwrap_or(1.0),
-                max_shunt_current: parts[7].parse().unwrap_or(9999.0),
-                max_bridge_active_power: parts[8].parse().unwrap_or(9999.0),
-                to_bus_min_voltage: parts[9].parse().unwrap_or(0.9),
-                to_bus_max_voltage: parts[10].parse().unwrap_or(1.1),
-                max_series_voltage: parts[11].parse().unwrap_or(1.0),
-                max_series_current: parts[12].parse().unwrap_or(0.0),
-                series_reactance: parts[13].parse().unwrap_or(0.05),
-                shunt_mvar_share: parts[14].parse().unwrap_or(100.0),
-                owner: parts[15].parse().unwrap_or(1),
-                volt_ref_code: parts[16].parse().unwrap_or(0),
-                regulated_bus_id: parts[17].parse().unwrap_or(0),
-                regulated_bus_node: (psse_version >= 34).then_some(parts[18].parse().unwrap_or(0)).unwrap_or(0),
-                master_device_name: parts[18 + parse_adder].replace("'", "").trim().to_string(),
-            })
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::Facts)?;
+        Ok(Facts{
+            deivce_name: rec.required_str(0, "NAME")?,
+            from_bus: rec.required(1, "I", 0)?,
+            to_bus: rec.required(2, "J", 0)?,
+            control_mode: rec.required(3, "MODE", 1)?,
+            desired_active_power: rec.required(4, "PDES", 0.0)?,
+            desired_reactive_power: rec.required(5, "QDES", 0.0)?,
+            from_bus_volt_setpoint: rec.required(6, "VSET", 1.0)?,
+            max_shunt_current: rec.required(7, "SHMX", 9999.0)?,
+            max_bridge_active_power: rec.required(8, "TRMX", 9999.0)?,
+            to_bus_min_voltage: rec.required(9, "VTMN", 0.9)?,
+            to_bus_max_voltage: rec.required(10, "VTMX", 1.1)?,
+            max_series_voltage: rec.required(11, "VSMX", 1.0)?,
+            max_series_current: rec.required(12, "IMX", 0.0)?,
+            series_reactance: rec.required(13, "LINX", 0.05)?,
+            shunt_mvar_share: rec.required(14, "RMPCT", 100.0)?,
+            owner: rec.required(15, "OWNER", 1)?,
+            series_setpoint_1: rec.required(16, "SET1", 0.0)?,
+            series_setpoint_2: rec.required(17, "SET2", 0.0)?,
+            volt_ref_code: rec.required(18, "VSREF", 0)?,
+            // The remote bus and IPFC master device were added in V31
+            regulated_bus_id: if psse_version >= 31 { rec.required(19, "FCREG", 0)? } else { 0 },
+            regulated_bus_node: if psse_version >= 34 { rec.required(20, "NREG", 0)? } else { 0 },
+            master_device_name: if psse_version >= 31 { rec.required_str(20 + parse_adder, "MNAME")? } else { String::new() },
         })
     }).collect()
 }
\ No newline at end of file
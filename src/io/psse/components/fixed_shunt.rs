@@ -1,6 +1,6 @@
-use std::str::from_utf8;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::io::psse::{components::codes::Status, error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -35,12 +35,19 @@ pub struct FixedShunt {
     pub bl_mvar: f64,
 }
 
+impl FixedShunt {
+    /// Status of the STATUS code
+    pub fn service_status(&self) -> Status {
+        Status::from(self.status)
+    }
+}
+
 impl From<Vec<String>> for FixedShunt {
 
     fn from(values: Vec<String>) -> Self {
 
         FixedShunt {
-            bus_id: values.get(0).and_then(|s| s.parse().ok()).unwrap_or(0),
+            bus_id: values.first().and_then(|s| s.parse().ok()).unwrap_or(0),
             id: values.get(1).and_then(|s| s.parse().ok()).unwrap_or("1".to_string()),
             status: values.get(2).and_then(|s| s.parse().ok()).unwrap_or(1),
             gl_mw: values.get(3).and_then(|s| s.parse().ok()).unwrap_or(0.0),
@@ -49,19 +56,17 @@ impl From<Vec<String>> for FixedShunt {
     }
 }
 
-pub fn parse_fixedshunts(lines: &[&[u8]]) -> Vec<FixedShunt> {
+pub fn parse_fixedshunts(lines: &[&[u8]], ctx: &SectionContext) -> Result<Vec<FixedShunt>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
-    lines.par_iter().filter_map(|line_bytes| {
-        from_utf8(line_bytes).ok().and_then(|line| {
-            let parts: Vec<&str> = line.split(",").map(|s| s.trim()).collect();
-            Some(FixedShunt{
-                bus_id: parts[0].parse().unwrap_or(0),
-                id: parts[1].replace("'", "").trim().to_string(),
-                status: parts[2].parse().unwrap_or(1),
-                gl_mw: parts[3].parse().unwrap_or(0.0),
-                bl_mvar: parts[4].parse().unwrap_or(0.0)
-            })
+    if lines.is_empty() {return Ok(Vec::new());}
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::FixedShunt)?;
+        Ok(FixedShunt{
+            bus_id: rec.required(0, "I", 0)?,
+            id: rec.required_str(1, "ID")?,
+            status: rec.required(2, "STATUS", 1)?,
+            gl_mw: rec.required(3, "GL", 0.0)?,
+            bl_mvar: rec.required(4, "BL", 0.0)?,
         })
     }).collect()
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
-use std::str::from_utf8;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::io::psse::{components::codes::Status, error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -16,6 +16,10 @@ pub struct Generator {
     /// ### Type: String
     /// ### Default: '1'
     pub id: String,
+    /// ## GENTYPE: Generator type, from V36
+    /// ### Type: String
+    /// ### Default: ""
+    pub gen_type: String,
     /// ## PG: Generator active power output
     /// ### Type: f64
     /// ### Units: MW
@@ -166,46 +170,55 @@ pub struct Generator {
     pub machine_powerfactor: f64,
 }
 
-pub fn parse_generators(lines: &[&[u8]], psse_version: i8) -> Vec<Generator> {
+impl Generator {
+    /// Status of the STAT code
+    pub fn service_status(&self) -> Status {
+        Status::from(self.status)
+    }
+}
+
+pub fn parse_generators(lines: &[&[u8]], ctx: &SectionContext, psse_version: i8) -> Result<Vec<Generator>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
-    // a bool to int to add to the line parsing since V34 added more ratings
+    if lines.is_empty() {return Ok(Vec::new());}
+    // a bool to int to add to the line parsing since V34 added NREG after IREG
     let parse_adder: usize = (psse_version >= 34) as usize;
-    lines.par_iter().filter_map(|line_bytes| {
-        from_utf8(line_bytes).ok().and_then(|line| {
-            let parts: Vec<&str> = line.split(",").map(|s| s.trim()).collect();
-            Some(Generator{
-                bus_id: parts[0].parse().unwrap_or(0),
-                id: parts[1].replace("'", "").trim().to_string(),
-                pgen: parts[2].parse().unwrap_or(0.0),
-                qgen: parts[3].parse().unwrap_or(0.0),
-                qmax: parts[4].parse().unwrap_or(9999.0),
-                qmin: parts[5].parse().unwrap_or(-9999.0),
-                voltage_set: parts[6].parse().unwrap_or(1.0),
-                reg_bus_id: parts[7].parse().unwrap_or(0),
-                reg_bus_node: parts[8].parse().unwrap_or(0) * (parse_adder as i32),
-                mbase: parts[8 + parse_adder].parse().unwrap_or(100.0),
-                zr: parts[9 + parse_adder].parse().unwrap_or(0.0),
-                zx: parts[10 + parse_adder].parse().unwrap_or(1.0),
-                rt: parts[11 + parse_adder].parse().unwrap_or(0.0),
-                xt: parts[12 + parse_adder].parse().unwrap_or(0.0),
-                gtap: parts[13 + parse_adder].parse().unwrap_or(1.0),
-                status: parts[14 + parse_adder].parse().unwrap_or(1),
-                rmpct: parts[15 + parse_adder].parse().unwrap_or(100.0),
-                pmax: parts[16 + parse_adder].parse().unwrap_or(9999.0),
-                pmin: parts[17 + parse_adder].parse().unwrap_or(-9999.0),
-                base_loaded: parts[18 + parse_adder].parse().unwrap_or(0),
-                owner1: parts.get(19 + parse_adder).and_then(|s| s.parse().ok()).unwrap_or(1),
-                owner1_percent: parts.get(20 + parse_adder).and_then(|s| s.parse().ok()).unwrap_or(1.0),
-                owner2: parts.get(21 + parse_adder).and_then(|s| s.parse().ok()).unwrap_or(0),
-                owner2_percent: parts.get(22 + parse_adder).and_then(|s| s.parse().ok()).unwrap_or(1.0),
-                owner3: parts.get(23 + parse_adder).and_then(|s| s.parse().ok()).unwrap_or(0),
-                owner3_percent: parts.get(24 + parse_adder).and_then(|s| s.parse().ok()).unwrap_or(1.0),
-                owner4: parts.get(25 + parse_adder).and_then(|s| s.parse().ok()).unwrap_or(0),
-                owner4_percent: parts.get(26 + parse_adder).and_then(|s| s.parse().ok()).unwrap_or(1.0),
-                machine_mode: parts.get(27 + parse_adder).and_then(|s| s.parse().ok()).unwrap_or(0),
-                machine_powerfactor: parts.get(28 + parse_adder).and_then(|s| s.parse().ok()).unwrap_or(1.0),
-            })
+    // V34 also added BASLOD after PB, which shifts the owner fields by a second column
+    let owner_adder: usize = parse_adder * 2;
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::Generator)?;
+        Ok(Generator{
+            bus_id: rec.required(0, "I", 0)?,
+            id: rec.required_str(1, "ID")?,
+            // GENTYPE follows ID from V36
+            gen_type: if psse_version >= 36 { rec.optional_str(30, "GENTYPE", "")? } else { String::new() },
+            pgen: rec.required(2, "PG", 0.0)?,
+            qgen: rec.required(3, "QG", 0.0)?,
+            qmax: rec.required(4, "QT", 9999.0)?,
+            qmin: rec.required(5, "QB", -9999.0)?,
+            voltage_set: rec.required(6, "VS", 1.0)?,
+            reg_bus_id: rec.required(7, "IREG", 0)?,
+            reg_bus_node: if psse_version >= 34 { rec.required(8, "NREG", 0)? } else { 0 },
+            mbase: rec.required(8 + parse_adder, "MBASE", 100.0)?,
+            zr: rec.required(9 + parse_adder, "ZR", 0.0)?,
+            zx: rec.required(10 + parse_adder, "ZX", 1.0)?,
+            rt: rec.required(11 + parse_adder, "RT", 0.0)?,
+            xt: rec.required(12 + parse_adder, "XT", 0.0)?,
+            gtap: rec.required(13 + parse_adder, "GTAP", 1.0)?,
+            status: rec.required(14 + parse_adder, "STATUS", 1)?,
+            rmpct: rec.required(15 + parse_adder, "RMPCT", 100.0)?,
+            pmax: rec.required(16 + parse_adder, "PT", 9999.0)?,
+            pmin: rec.required(17 + parse_adder, "PB", -9999.0)?,
+            base_loaded: if psse_version >= 34 { rec.required(18 + parse_adder, "BASLOD", 0)? } else { 0 },
+            owner1: rec.optional(18 + owner_adder, "O1", 1)?,
+            owner1_percent: rec.optional(19 + owner_adder, "F1", 1.0)?,
+            owner2: rec.optional(20 + owner_adder, "O2", 0)?,
+            owner2_percent: rec.optional(21 + owner_adder, "F2", 1.0)?,
+            owner3: rec.optional(22 + owner_adder, "O3", 0)?,
+            owner3_percent: rec.optional(23 + owner_adder, "F3", 1.0)?,
+            owner4: rec.optional(24 + owner_adder, "O4", 0)?,
+            owner4_percent: rec.optional(25 + owner_adder, "F4", 1.0)?,
+            machine_mode: rec.optional(26 + owner_adder, "WMOD", 0)?,
+            machine_powerfactor: rec.optional(27 + owner_adder, "WPF", 1.0)?,
         })
     }).collect()
 }
\ No newline at end of file
@@ -0,0 +1,216 @@
+use crate::io::psse::{error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
+
+//The real, integer and character data are written 10 values to a line
+const VALUES_PER_LINE: usize = 10;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+/// A struct containing the information for each GNE (Generic Network Element) device in the PSS/E .RAW case
+pub struct GneDevice {
+    /// ## NAME: GNE device name
+    /// ### Type: String
+    /// ### Default: " "
+    pub name: String,
+    /// ## MODEL: Name of the BOSL model used by the GNE device
+    /// ### Type: String
+    /// ### Default: " "
+    pub model: String,
+    /// ## NTERM: Number of buses the GNE device is connected to
+    /// ### Type: i32
+    /// ### Default: 1
+    pub num_terminals: i32,
+    /// ## BUSi: Bus IDs the GNE device is connected to (1-NTERM)
+    /// ### Type: Vec\<i32>
+    /// ### Default: Vec\<0; NTERM>
+    pub bus_ids: Vec<i32>,
+    /// ## NREAL: Number of real data values used by the model
+    /// ### Type: i32
+    /// ### Default: 0
+    pub num_real: i32,
+    /// ## NINTG: Number of integer data values used by the model
+    /// ### Type: i32
+    /// ### Default: 0
+    pub num_int: i32,
+    /// ## NCHAR: Number of character data values used by the model
+    /// ### Type: i32
+    /// ### Default: 0
+    pub num_char: i32,
+    /// ## STATUS: GNE device status
+    /// ### Type: i8
+    /// ### Default: 1
+    /// ## Parameters
+    /// * 0 : Out-of-service
+    /// * 1 : In-service
+    pub status: i8,
+    /// ## OWNER: GNE device owner number
+    /// ### Type: i32
+    /// ### Default: 1
+    pub owner: i32,
+    /// ## NMETR: Terminal of the GNE device treated as the metered end
+    /// ### Type: i32
+    /// ### Default: NTERM
+    pub metered_end: i32,
+    /// ## REALi: Real data values (1-NREAL)
+    /// ### Type: Vec\<f64>
+    /// ### Default: Vec\<0.0; NREAL>
+    pub real_data: Vec<f64>,
+    /// ## INTGi: Integer data values (1-NINTG)
+    /// ### Type: Vec\<i32>
+    /// ### Default: Vec\<0; NINTG>
+    pub int_data: Vec<i32>,
+    /// ## CHARi: Character data values (1-NCHAR)
+    /// ### Type: Vec\<String>
+    /// ### Default: Vec\<" "; NCHAR>
+    pub char_data: Vec<String>,
+}
+
+pub fn parse_gne_devices(lines: &[&[u8]], ctx: &SectionContext) -> Result<Vec<GneDevice>, ParseError> {
+    //Check if there is even data before proceeding
+    if lines.is_empty() {return Ok(Vec::new());}
+    let mut gne_devices: Vec<GneDevice> = Vec::new();
+    let mut i: usize = 0;
+
+    while i < lines.len() {
+        //The first line holds the terminal buses and the size of the data that follows
+        let rec1: Record = ctx.record(lines[i], i, PSSEDatatype::GneDevice)?;
+        //The terminal buses are followed by NREAL, NINTG and NCHAR on the line
+        let num_terminals: i32 = rec1.count(2, "NTERM", 1, rec1.len().saturating_sub(6))?;
+        let terminals: usize = num_terminals as usize;
+        let mut bus_ids: Vec<i32> = Vec::with_capacity(terminals);
+        for terminal in 0..terminals {
+            bus_ids.push(rec1.required(3 + terminal, "BUSi", 0)?);
+        }
+        let num_real: i32 = rec1.required(3 + terminals, "NREAL", 0)?;
+        let num_int: i32 = rec1.required(4 + terminals, "NINTG", 0)?;
+        let num_char: i32 = rec1.required(5 + terminals, "NCHAR", 0)?;
+
+        //Each block of data takes as many lines as needed to hold 10 values a line
+        let real_lines: usize = (num_real.max(0) as usize).div_ceil(VALUES_PER_LINE);
+        let int_lines: usize = (num_int.max(0) as usize).div_ceil(VALUES_PER_LINE);
+        let char_lines: usize = (num_char.max(0) as usize).div_ceil(VALUES_PER_LINE);
+        let expected_lines: usize = 2 + real_lines + int_lines + char_lines;
+
+        let rec2: Record = ctx.record_line(lines, i, 1, expected_lines, PSSEDatatype::GneDevice)?;
+        let block: DataBlock = DataBlock { lines, start: i, expected_lines, ctx };
+        let real_data: Vec<f64> = block.values(2, num_real, |rec, column| rec.required(column, "REALi", 0.0))?;
+        let int_data: Vec<i32> = block.values(2 + real_lines, num_int, |rec, column| rec.required(column, "INTGi", 0))?;
+        let char_data: Vec<String> = block.values(2 + real_lines + int_lines, num_char, |rec, column| rec.required_str(column, "CHARi"))?;
+
+        let gne_device: GneDevice = GneDevice {
+            name: rec1.required_str(0, "NAME")?,
+            model: rec1.required_str(1, "MODEL")?,
+            num_terminals,
+            bus_ids,
+            num_real,
+            num_int,
+            num_char,
+            status: rec2.required(0, "STATUS", 1)?,
+            owner: rec2.optional(1, "OWNER", 1)?,
+            metered_end: rec2.optional(2, "NMETR", num_terminals)?,
+            real_data,
+            int_data,
+            char_data,
+        };
+        gne_devices.push(gne_device);
+
+        i = i.saturating_add(expected_lines);
+    }
+    Ok(gne_devices)
+}
+
+/// The lines of a GNE device record holding its real, integer and character data
+struct DataBlock<'a, 'b> {
+    lines: &'b [&'a [u8]],
+    start: usize,
+    expected_lines: usize,
+    ctx: &'a SectionContext,
+}
+
+impl<'a> DataBlock<'a, '_> {
+    /// Reads `count` values written 10 to a line from the record line `offset` on, splitting each line once
+    ///
+    /// Only the lines left in the section are read, so a count larger than the section holds
+    /// reports the record as ended early instead of reading past it.
+    fn values<T>(&self, offset: usize, count: i32, read: impl Fn(&Record<'a>, usize) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+        let count: usize = count.max(0) as usize;
+        let first: usize = self.start + offset;
+        let available: usize = self.lines.len().saturating_sub(first).saturating_mul(VALUES_PER_LINE);
+        let mut values: Vec<T> = Vec::with_capacity(count.min(available));
+        for line in 0..count.div_ceil(VALUES_PER_LINE) {
+            let rec: Record = self.ctx.record_line(self.lines, self.start, offset + line, self.expected_lines, PSSEDatatype::GneDevice)?;
+            if first + line >= self.lines.len() {
+                break;
+            }
+            for column in 0..(count - line * VALUES_PER_LINE).min(VALUES_PER_LINE) {
+                values.push(read(&rec, column)?);
+            }
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::psse::{error::{Diagnostic, DiagnosticKind}, options::ParseOptions};
+
+    use super::*;
+
+    #[test]
+    fn parse_gne_2_terminal() {
+        let gne_str: &'static str = "'GNE-TEST    ', 'SERIESCAP', 2, 1001, 1002, 12, 2, 1
+1, 4, 2
+ 0.01000, 0.25000, 1.5, 2.5, 3.5, 4.5, 5.5, 6.5, 7.5, 8.5
+ 9.5, 10.5
+ 1, 0
+'BYPASS'";
+        let lines: Vec<&[u8]> = gne_str.lines().map(|line| line.as_bytes()).collect();
+        let binding: Vec<GneDevice> = parse_gne_devices(&lines, &SectionContext::default()).unwrap();
+        let gne: Option<&GneDevice> = binding.first();
+        //To avoid checking everything, check bits and pieces to make sure things are in the right place
+        assert_eq!(binding.len(), 1);
+        assert_eq!(gne.unwrap().name, "GNE-TEST".to_string());
+        assert_eq!(gne.unwrap().model, "SERIESCAP".to_string());
+        assert_eq!(gne.unwrap().bus_ids, vec![1001, 1002]);
+        assert_eq!(gne.unwrap().owner, 4);
+        assert_eq!(gne.unwrap().metered_end, 2);
+        assert_eq!(gne.unwrap().real_data.len(), 12);
+        assert_eq!(gne.unwrap().real_data[11], 10.5);
+        assert_eq!(gne.unwrap().int_data, vec![1, 0]);
+        assert_eq!(gne.unwrap().char_data, vec!["BYPASS".to_string()]);
+    }
+
+    #[test]
+    fn parse_gne_truncated() {
+        let gne_str: &'static str = "'GNE-TEST    ', 'SERIESCAP', 1, 1001, 2, 0, 0
+1, 4, 1";
+        let lines: Vec<&[u8]> = gne_str.lines().map(|line| line.as_bytes()).collect();
+        let err: ParseError = parse_gne_devices(&lines, &SectionContext::default()).unwrap_err();
+        assert!(matches!(err, ParseError::TruncatedRecord { line: 1, expected_lines: 3, .. }));
+    }
+
+    #[test]
+    fn parse_gne_oversized_counts() {
+        //A count larger than the section holds is read up to the end of the section
+        let gne_str: &'static str = "'GNE-TEST    ', 'SERIESCAP', 1, 1001, 2000000000, 0, 0
+1, 4, 1
+ 0.01000, 0.25000, 1.5, 2.5, 3.5, 4.5, 5.5, 6.5, 7.5, 8.5";
+        let lines: Vec<&[u8]> = gne_str.lines().map(|line| line.as_bytes()).collect();
+        let err: ParseError = parse_gne_devices(&lines, &SectionContext::default()).unwrap_err();
+        assert!(matches!(err, ParseError::TruncatedRecord { line: 1, .. }), "{}", err);
+        let ctx: SectionContext = SectionContext::new(1, &ParseOptions::lenient());
+        let binding: Vec<GneDevice> = parse_gne_devices(&lines, &ctx).unwrap();
+        assert_eq!(binding[0].num_real, 2000000000);
+        assert_eq!(binding[0].real_data.len(), 10);
+        assert_eq!(binding[0].real_data[9], 8.5);
+        let diagnostics: Vec<Diagnostic> = ctx.into_diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert!(matches!(diagnostics[0].kind, DiagnosticKind::TruncatedRecord { .. }));
+
+        //More terminals than the line holds is an invalid count
+        let gne_str: &'static str = "'GNE-TEST    ', 'SERIESCAP', 2000000000, 1001, 0, 0, 0
+1, 4, 1";
+        let lines: Vec<&[u8]> = gne_str.lines().map(|line| line.as_bytes()).collect();
+        let err: ParseError = parse_gne_devices(&lines, &SectionContext::default()).unwrap_err();
+        assert!(matches!(err, ParseError::InvalidField { field: "NTERM", .. }), "{}", err);
+    }
+}
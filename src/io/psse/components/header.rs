@@ -43,7 +43,7 @@ impl From<Vec<String>> for HeaderInfo {
     fn from(values: Vec<String>) -> Self {
 
         HeaderInfo {
-            ic: values.get(0).and_then(|s| s.parse().ok()).unwrap_or(0),
+            ic: values.first().and_then(|s| s.parse().ok()).unwrap_or(0),
             sbase: values.get(1).and_then(|s| s.parse().ok()).unwrap_or(100.0),
             revision: values.get(2).and_then(|s| s.parse().ok()).unwrap_or(34),
             transformer_rating_code: values.get(3).and_then(|s| s.parse().ok()).unwrap_or(0),
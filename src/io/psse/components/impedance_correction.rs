@@ -1,4 +1,4 @@
-use std::str::from_utf8;
+use crate::io::psse::{error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -7,10 +7,10 @@ pub struct ImpedanceCorrectionEntry {
     /// ## Ti: Off nominal turns ratio or the phase shift angle in degrees
     /// ### Type: f64
     pub tap: f64,
-    /// ## Rei: Complex scaling factor (Real)
+    /// ## Rei: Complex scaling factor (Real), or the real scaling factor Fi in V33 and earlier
     /// ### Type: f64
     pub real_complex_scaling_factor: f64,
-    /// ## Imi: Complex scaling factor (Imaginary)
+    /// ## Imi: Complex scaling factor (Imaginary), always 0 in V33 and earlier
     /// ### Type: f64
     pub imag_complex_scaling_factor: f64,
 }
@@ -35,9 +35,9 @@ impl ImpedanceCorrectionEntry {
     }
 }
 
-pub fn parse_impedance_correction_table(lines: &[&[u8]], psse_version: i8) -> Vec<ImpedanceCorrectionTable> {
+pub fn parse_impedance_correction_table(lines: &[&[u8]], ctx: &SectionContext, psse_version: i8) -> Result<Vec<ImpedanceCorrectionTable>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
+    if lines.is_empty() {return Ok(Vec::new());}
     //Create a new vec of correction tables to push into
     let mut correction_tables: Vec<ImpedanceCorrectionTable> = Vec::new();
     //Define while loop variables
@@ -47,67 +47,75 @@ pub fn parse_impedance_correction_table(lines: &[&[u8]], psse_version: i8) -> Ve
     let mut begin_entry: bool = true;
     //Define a new correction table
     let mut correction_table: ImpedanceCorrectionTable = ImpedanceCorrectionTable::default();
+    //V33 and earlier entries are (T, F) pairs with a real factor, V34+ entries are (T, Re(F), Im(F)) triplets
+    let entry_length: usize = if psse_version <= 33 { 2 } else { 3 };
 
     //Loop over each line of the correction table
     while line_position < lines.len() {
-        //Read the entry line
-        if let Ok(line1_str) = from_utf8(lines[line_position]) {
-            //Split the entry line by commas and collect into a vec
-            let parts1: Vec<&str> = line1_str.split(',').map(|s| s.trim()).collect();
-            //Checks if this is the begining of a new table entry
-            if begin_entry {
-                //Creates a new correction table
-                correction_table = ImpedanceCorrectionTable {
-                    //Sets the correction index from the first value of the entry
-                    index: parts1[0].parse::<i32>().unwrap_or(0),
-                    //Creates an empty vec to store the table entries
-                    correction_table_entries: Vec::new(),
-                };
-                //Increase the entry position by one to move past the index
-                entry_position += 1;
-                //Change the entry flag
-                begin_entry = false;
-            }
+        //Read the entry line and split it into its fields
+        let rec1: Record = ctx.record(lines[line_position], line_position, PSSEDatatype::ImpedanceCorrection)?;
+        //Checks if this is the begining of a new table entry
+        if begin_entry {
+            //Creates a new correction table
+            correction_table = ImpedanceCorrectionTable {
+                //Sets the correction index from the first value of the entry
+                index: rec1.required(0, "I", 0)?,
+                //Creates an empty vec to store the table entries
+                correction_table_entries: Vec::new(),
+            };
+            //Increase the entry position by one to move past the index
+            entry_position += 1;
+            //Change the entry flag
+            begin_entry = false;
+        }
 
-            //Loop over each entry position
-            while entry_position < parts1.len() {
-                //Create a new table entry, reading 3 parts at a time since each entry will always be 3 long
-                let table_entry: ImpedanceCorrectionEntry = ImpedanceCorrectionEntry {
-                    tap: parts1[entry_position].parse::<f64>().unwrap_or(0.0),
-                    real_complex_scaling_factor: parts1.get(entry_position + 1).map_or("0.0", |v| v).parse::<f64>().unwrap_or(0.0),
-                    imag_complex_scaling_factor: parts1.get(entry_position + 2).map_or("0.0", |v| v).parse::<f64>().unwrap_or(0.0),
-                };
-                //Checks if the entry is all 0.0 indicating the end of this table
-                if table_entry.is_end_of_table() {
-                    //Push the correction table
-                    //Don't push the blank table entry
-                    correction_tables.push(correction_table.clone());
-                    //Set the entry flag to read the index
-                    begin_entry = true;
-                    //Break out from reading the entries and start a new line
-                    break;
-                } else {
-                    //If there are still more entries to read, keep going
-                    //Push the table entry to the main table
-                    correction_table.correction_table_entries.push(table_entry);
-                    //Increase the position to read the next 3
-                    entry_position += 3;                    
+        //Loop over each entry position
+        while entry_position < rec1.len() {
+            //Create a new table entry, reading one pair or triplet at a time
+            let table_entry: ImpedanceCorrectionEntry = if entry_length == 2 {
+                ImpedanceCorrectionEntry {
+                    tap: rec1.required(entry_position, "Ti", 0.0)?,
+                    real_complex_scaling_factor: rec1.optional(entry_position + 1, "Fi", 0.0)?,
+                    imag_complex_scaling_factor: 0.0,
                 }
-            }
-            if psse_version <= 33 {
+            } else {
+                ImpedanceCorrectionEntry {
+                    tap: rec1.required(entry_position, "Ti", 0.0)?,
+                    real_complex_scaling_factor: rec1.optional(entry_position + 1, "REi", 0.0)?,
+                    imag_complex_scaling_factor: rec1.optional(entry_position + 2, "IMi", 0.0)?,
+                }
+            };
+            //Checks if the entry is all 0.0 indicating the end of this table
+            if table_entry.is_end_of_table() {
                 //Push the correction table
+                //Don't push the blank table entry
                 correction_tables.push(correction_table.clone());
                 //Set the entry flag to read the index
                 begin_entry = true;
+                //Break out from reading the entries and start a new line
+                break;
+            } else {
+                //If there are still more entries to read, keep going
+                //Push the table entry to the main table
+                correction_table.correction_table_entries.push(table_entry);
+                //Increase the position to read the next entry
+                entry_position += entry_length;
             }
-            //Change the entry position back to 0
-            entry_position = 0;
-            //Increase the line position to read the next line
-            line_position += 1;
-        }        
+        }
+        //V33 tables end with their line, unless the zero entry already ended them
+        if psse_version <= 33 && !begin_entry {
+            //Push the correction table
+            correction_tables.push(correction_table.clone());
+            //Set the entry flag to read the index
+            begin_entry = true;
+        }
+        //Change the entry position back to 0
+        entry_position = 0;
+        //Increase the line position to read the next line
+        line_position += 1;
     }
     //Return the vec of collection tables
-    correction_tables
+    Ok(correction_tables)
 }
 
 #[cfg(test)]
@@ -118,8 +126,8 @@ mod tests {
     fn parse_correction_table_1_line() {
         let correction_table_str: &'static str = " 1,  80.00000,  1.00000,  0.00000,  -36.00000,  0.53800,  0.00000,  -54.40000,  0.49200,  0.00000,  -15.80000,  0.02400,  0.00000,   -20.30000,  0.09400,  0.00000,    0.00000,  0.00000,  0.00000";
         let lines: Vec<&[u8]> = correction_table_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<ImpedanceCorrectionTable> = parse_impedance_correction_table(&lines, 35);
-        let correction_table: Option<&ImpedanceCorrectionTable> = binding.get(0);
+        let binding: Vec<ImpedanceCorrectionTable> = parse_impedance_correction_table(&lines, &SectionContext::default(), 35).unwrap();
+        let correction_table: Option<&ImpedanceCorrectionTable> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(correction_table.unwrap().index, 1);
@@ -139,8 +147,8 @@ mod tests {
         let correction_table_str: &'static str = " 1,  80.00000,  1.00000,  0.00000,  -36.00000,  0.53800,  0.00000,  -54.40000,  0.49200,  0.00000,  -15.80000,  0.02400,  0.00000,   -20.30000,  0.09400,  0.00000,    0.00000,  0.11000,  0.00000
        10.30000,  0.02300,  0.00000,   15.40000,  0.01400,  0.00000,   84.40000,  0.29200,  0.00000,   66.00000,  0.46800,  0.00000,   20.00000,  1.00000,  0.00000,    0.00000,  0.00000,  0.00000";
         let lines: Vec<&[u8]> = correction_table_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<ImpedanceCorrectionTable> = parse_impedance_correction_table(&lines, 35);
-        let correction_table: Option<&ImpedanceCorrectionTable> = binding.get(0);
+        let binding: Vec<ImpedanceCorrectionTable> = parse_impedance_correction_table(&lines, &SectionContext::default(), 35).unwrap();
+        let correction_table: Option<&ImpedanceCorrectionTable> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(correction_table.unwrap().index, 1);
@@ -161,8 +169,8 @@ mod tests {
         10.30000,  0.02300,  0.00000,   15.40000,  0.01400,  0.00000,   84.40000,  0.29200,  0.00000,   66.00000,  0.46800,  0.00000,   20.00000,  1.00000,  0.00000,    -15.80000,  0.02400,  0.00000
        10.30000,  0.02300,  0.00000,   15.40000,  0.01400,  0.00000,   84.40000,  0.29200,  0.00000,   66.00000,  0.46800,  0.00000,   98.12300,  5.92800,  2.10000,    0.00000,  0.00000,  0.00000";
         let lines: Vec<&[u8]> = correction_table_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<ImpedanceCorrectionTable> = parse_impedance_correction_table(&lines, 35);
-        let correction_table: Option<&ImpedanceCorrectionTable> = binding.get(0);
+        let binding: Vec<ImpedanceCorrectionTable> = parse_impedance_correction_table(&lines, &SectionContext::default(), 35).unwrap();
+        let correction_table: Option<&ImpedanceCorrectionTable> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(correction_table.unwrap().index, 1);
@@ -176,4 +184,20 @@ mod tests {
         assert_eq!(correction_table.unwrap().correction_table_entries.last().unwrap().real_complex_scaling_factor, 5.928);
         assert_eq!(correction_table.unwrap().correction_table_entries.last().unwrap().imag_complex_scaling_factor, 2.1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn parse_correction_table_v33() {
+        let correction_table_str: &'static str = " 2,  -78.90123,  1.23456,  -45.67890,  0.45678,  -34.56789,  0.23456,   0.00000,  0.00000,   0.00000,  0.00000
+ 3,  -30.00000,  1.10000,   30.00000,  1.20000";
+        let lines: Vec<&[u8]> = correction_table_str.lines().map(|line| line.as_bytes()).collect();
+        let binding: Vec<ImpedanceCorrectionTable> = parse_impedance_correction_table(&lines, &SectionContext::default(), 33).unwrap();
+        //Each line is one table of (T, F) pairs, the trailing zero pairs being unused entries
+        assert_eq!(binding.len(), 2);
+        assert_eq!(binding[0].index, 2);
+        assert_eq!(binding[0].correction_table_entries.len(), 3);
+        assert_eq!(binding[0].correction_table_entries[1], ImpedanceCorrectionEntry { tap: -45.6789, real_complex_scaling_factor: 0.45678, imag_complex_scaling_factor: 0.0 });
+        assert_eq!(binding[1].index, 3);
+        assert_eq!(binding[1].correction_table_entries.last().unwrap().tap, 30.0);
+        assert_eq!(binding[1].correction_table_entries.last().unwrap().real_complex_scaling_factor, 1.2);
+    }
+}
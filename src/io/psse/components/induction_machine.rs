@@ -1,3 +1,7 @@
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+use crate::io::psse::{error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
+
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -42,7 +46,7 @@ impl From<Vec<String>> for InductionMachine {
     fn from(values: Vec<String>) -> Self {
 
         InductionMachine {
-            bus_id: values.get(0).and_then(|s| s.parse().ok()).unwrap_or(0),
+            bus_id: values.first().and_then(|s| s.parse().ok()).unwrap_or(0),
             id: values.get(1).and_then(|s| s.parse().ok()).unwrap_or("1".to_string()),
             status: values.get(2).and_then(|s| s.parse().ok()).unwrap_or(1),
             standard_code: values.get(3).and_then(|s| s.parse().ok()).unwrap_or(1),
@@ -79,4 +83,86 @@ impl From<Vec<String>> for InductionMachine {
             saturation_mult: values.get(33).and_then(|s| s.parse().ok()).unwrap_or(1.0),
         }
     }
-}
\ No newline at end of file
+}
+
+pub fn parse_induction_machines(lines: &[&[u8]], ctx: &SectionContext) -> Result<Vec<InductionMachine>, ParseError> {
+    //Check if there is even data before proceeding
+    if lines.is_empty() {return Ok(Vec::new());}
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::InductionMachine)?;
+        //Everything after the bus number may be left off the end of the line
+        Ok(InductionMachine{
+            bus_id: rec.required(0, "I", 0)?,
+            id: rec.optional_str(1, "ID", "1")?,
+            status: rec.optional(2, "STAT", 1)?,
+            standard_code: rec.optional(3, "SCODE", 1)?,
+            design_code: rec.optional(4, "DCODE", 2)?,
+            area: rec.optional(5, "AREA", 0)?,
+            zone: rec.optional(6, "ZONE", 0)?,
+            owner: rec.optional(7, "OWNER", 0)?,
+            torque_code: rec.optional(8, "TCODE", 1)?,
+            base_power_code: rec.optional(9, "BCODE", 1)?,
+            sbase: rec.optional(10, "MBASE", 100.0)?,
+            rated_voltage: rec.optional(11, "RATEKV", 0.0)?,
+            power_code: rec.optional(12, "PCODE", 1)?,
+            sched_active_power: rec.optional(13, "PSET", 0.0)?,
+            inertia: rec.optional(14, "H", 1.0)?,
+            torque_params: vec![
+                rec.optional(15, "A", 1.0)?,
+                rec.optional(16, "B", 1.0)?,
+                rec.optional(17, "D", 1.0)?,
+                rec.optional(18, "E", 1.0)?,
+            ],
+            arm_resistance: rec.optional(19, "RA", 0.0)?,
+            arm_reactance: rec.optional(20, "XA", 0.0)?,
+            mag_reactance: rec.optional(21, "XM", 2.5)?,
+            windng1_resist: rec.optional(22, "R1", 999.0)?,
+            windng1_react: rec.optional(23, "X1", 999.0)?,
+            windng2_resist: rec.optional(24, "R2", 999.0)?,
+            windng2_react: rec.optional(25, "X2", 999.0)?,
+            windng3_react: rec.optional(26, "X3", 0.0)?,
+            volt_point_1: rec.optional(27, "E1", 1.0)?,
+            v_sat1: rec.optional(28, "SE1", 0.0)?,
+            volt_point_2: rec.optional(29, "E2", 1.2)?,
+            v_sat2: rec.optional(30, "SE2", 0.0)?,
+            stator_current_1: rec.optional(31, "IA1", 0.0)?,
+            stator_current_2: rec.optional(32, "IA2", 0.0)?,
+            saturation_mult: rec.optional(33, "XAMULT", 1.0)?,
+        })
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_induction_machine_full() {
+        let im_str: &'static str = "  3005,'M1',1,2,1,  1,  1,  1,1,1,   120.000,   13.8000,1,    -85.000,  0.5000,  1.0000,  0.0000,  0.0000,  1.0000,0.00300,  0.12000,  3.50000,  0.02000,  0.09000, 999.0000, 999.0000, 0.00000,  1.00000,  0.05000,  1.20000,  0.20000,  0.00000,  0.00000,  1.00000";
+        let lines: Vec<&[u8]> = im_str.lines().map(|line| line.as_bytes()).collect();
+        let binding: Vec<InductionMachine> = parse_induction_machines(&lines, &SectionContext::default()).unwrap();
+        let machine: Option<&InductionMachine> = binding.first();
+        //To avoid checking everything, check bits and pieces to make sure things are in the right place
+        assert_eq!(machine.unwrap().bus_id, 3005);
+        assert_eq!(machine.unwrap().id, "M1".to_string());
+        assert_eq!(machine.unwrap().standard_code, 2);
+        assert_eq!(machine.unwrap().sbase, 120.0);
+        assert_eq!(machine.unwrap().sched_active_power, -85.0);
+        assert_eq!(machine.unwrap().torque_params, vec![1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(machine.unwrap().mag_reactance, 3.5);
+        assert_eq!(machine.unwrap().v_sat2, 0.2);
+        assert_eq!(machine.unwrap().saturation_mult, 1.0);
+    }
+
+    #[test]
+    fn parse_induction_machine_defaults() {
+        let im_str: &'static str = "  3005,'M1',0";
+        let lines: Vec<&[u8]> = im_str.lines().map(|line| line.as_bytes()).collect();
+        let binding: Vec<InductionMachine> = parse_induction_machines(&lines, &SectionContext::default()).unwrap();
+        let machine: Option<&InductionMachine> = binding.first();
+        assert_eq!(machine.unwrap().status, 0);
+        assert_eq!(machine.unwrap().design_code, 2);
+        assert_eq!(machine.unwrap().windng1_resist, 999.0);
+        assert_eq!(machine.unwrap().volt_point_2, 1.2);
+    }
+}
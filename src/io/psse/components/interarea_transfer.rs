@@ -1,6 +1,6 @@
-use std::str::from_utf8;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::io::psse::{error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -29,18 +29,16 @@ pub struct InterAreaTransfer {
     pub power_transfer: f64,
 }
 
-pub fn parse_area_transfers(lines: &[&[u8]]) -> Vec<InterAreaTransfer> {
+pub fn parse_area_transfers(lines: &[&[u8]], ctx: &SectionContext) -> Result<Vec<InterAreaTransfer>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
-    lines.par_iter().filter_map(|line_bytes| {
-        from_utf8(line_bytes).ok().and_then(|line| {
-            let parts: Vec<&str> = line.split(",").map(|s| s.trim()).collect();
-            Some(InterAreaTransfer{
-                from_area: parts[0].parse().unwrap_or(0),
-                to_area: parts[1].parse().unwrap_or(0),
-                transfer_id: parts[2].replace("'", "").trim().to_string(),
-                power_transfer: parts[3].parse().unwrap_or(0.0),
-            })
+    if lines.is_empty() {return Ok(Vec::new());}
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::InterAreaTransfer)?;
+        Ok(InterAreaTransfer{
+            from_area: rec.required(0, "ARFROM", 0)?,
+            to_area: rec.required(1, "ARTO", 0)?,
+            transfer_id: rec.required_str(2, "TRID")?,
+            power_transfer: rec.required(3, "PTRAN", 0.0)?,
         })
     }).collect()
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
-use std::str::from_utf8;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::io::psse::{components::codes::Status, error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -23,6 +23,10 @@ pub struct Load {
     /// * 0 : Load Offline
     /// * 1 : Load Online
     pub status: i8,
+    /// ## PRIORITY: Load priority, from V36
+    /// ### Type: i8
+    /// ### Default: 0
+    pub priority: i8,
     /// ## AREA: Load area number
     /// ### Type: i16
     /// ### Default: 0
@@ -108,32 +112,40 @@ pub struct Load {
     pub load_type: String,
 }
 
-pub fn parse_loads(lines: &[&[u8]]) -> Vec<Load> {
+impl Load {
+    /// Status of the STATUS code
+    pub fn service_status(&self) -> Status {
+        Status::from(self.status)
+    }
+}
+
+pub fn parse_loads(lines: &[&[u8]], ctx: &SectionContext, psse_version: i8) -> Result<Vec<Load>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
-    lines.par_iter().filter_map(|line_bytes| {
-        from_utf8(line_bytes).ok().and_then(|line| {
-            let parts: Vec<&str> = line.split(",").map(|s| s.trim()).collect();
-            Some(Load{
-                bus_id: parts[0].parse().unwrap_or(0),
-                id: parts[1].replace("'", "").trim().to_string(),
-                status: parts[2].parse().unwrap_or(1),
-                area: parts[3].parse().unwrap_or(0),
-                zone: parts[4].parse().unwrap_or(0),
-                pl_mw: parts[5].parse().unwrap_or(99.99),
-                ql_mvar: parts[6].parse().unwrap_or(99.99),
-                ip_mw: parts[7].parse().unwrap_or(0.0),
-                iq_mvar: parts[8].parse().unwrap_or(0.0),
-                yp_mw: parts[9].parse().unwrap_or(0.0),
-                yq_mvar: parts[10].parse().unwrap_or(0.0),
-                owner: parts[11].parse().unwrap_or(1),
-                scale: parts[12].parse().unwrap_or(1),
-                interruptable: parts.get(13).and_then(|s| s.parse().ok()).unwrap_or(0),
-                dgen_mw: parts.get(14).and_then(|s| s.parse().ok()).unwrap_or(0.0),
-                dgen_mvar: parts.get(15).and_then(|s| s.parse().ok()).unwrap_or(0.0),
-                dgen_mode: parts.get(16).and_then(|s| s.parse().ok()).unwrap_or(0),
-                load_type: parts.get(15).and_then(|s| s.parse().ok()).unwrap_or("".to_string()),
-            })
+    if lines.is_empty() {return Ok(Vec::new());}
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::Load)?;
+        Ok(Load{
+            bus_id: rec.required(0, "I", 0)?,
+            id: rec.required_str(1, "ID")?,
+            status: rec.required(2, "STATUS", 1)?,
+            // PRIORITY follows STATUS from V36
+            priority: if psse_version >= 36 { rec.optional(18, "PRIORITY", 0)? } else { 0 },
+            area: rec.required(3, "AREA", 0)?,
+            zone: rec.required(4, "ZONE", 0)?,
+            pl_mw: rec.required(5, "PL", 99.99)?,
+            ql_mvar: rec.required(6, "QL", 99.99)?,
+            ip_mw: rec.required(7, "IP", 0.0)?,
+            iq_mvar: rec.required(8, "IQ", 0.0)?,
+            yp_mw: rec.required(9, "YP", 0.0)?,
+            yq_mvar: rec.required(10, "YQ", 0.0)?,
+            owner: rec.required(11, "OWNER", 1)?,
+            // SCALE was added in V32
+            scale: if psse_version >= 32 { rec.required(12, "SCALE", 1)? } else { 1 },
+            interruptable: rec.optional(13, "INTRPT", 0)?,
+            dgen_mw: rec.optional(14, "DGENP", 0.0)?,
+            dgen_mvar: rec.optional(15, "DGENQ", 0.0)?,
+            dgen_mode: rec.optional(16, "DGENM", 0)?,
+            load_type: rec.optional_str(17, "LOADTYPE", "")?,
         })
     }).collect()
 }
@@ -146,8 +158,8 @@ mod tests {
     fn parse_load_v33() {
         let load_str: &'static str = "   84,'1 ',1,   1,   1,    11.000,     7.000,     0.000,     0.000,     0.000,    -0.000,   1,1";
         let loads: Vec<&[u8]> = load_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<Load> = parse_loads(&loads);
-        let load: Option<&Load> = binding.get(0);
+        let binding: Vec<Load> = parse_loads(&loads, &SectionContext::default(), 33).unwrap();
+        let load: Option<&Load> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(load.unwrap().bus_id, 84);
@@ -159,8 +171,8 @@ mod tests {
     fn parse_load_v35() {
         let load_str: &'static str = "110001,'1 ',1,   7,   1,     0.353,     0.145,     0.000,     0.000,     0.000,     0.000,   1,1, 0,     0.000,     0.000, 1,'            '";
         let loads: Vec<&[u8]> = load_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<Load> = parse_loads(&loads);
-        let load: Option<&Load> = binding.get(0);
+        let binding: Vec<Load> = parse_loads(&loads, &SectionContext::default(), 35).unwrap();
+        let load: Option<&Load> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(load.unwrap().bus_id, 110001);
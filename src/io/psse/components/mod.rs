@@ -1,3 +1,4 @@
+pub mod codes;
 pub mod header;
 pub mod bus;
 pub mod load;
@@ -17,7 +18,9 @@ pub mod interarea_transfer;
 pub mod owner;
 pub mod facts;
 pub mod switched_shunt;
+pub mod gne_device;
 pub mod induction_machine;
+pub mod substation;
 
 pub mod structs {
     use crate::io::psse::components;
@@ -41,5 +44,7 @@ pub mod structs {
     pub use components::owner::Owner;
     pub use components::facts::Facts;
     pub use components::switched_shunt::SwitchedShunt;
+    pub use components::gne_device::GneDevice;
     pub use components::induction_machine::InductionMachine;
+    pub use components::substation::{Substation, SubstationNode, SubstationSwitchingDevice, SubstationTerminal};
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
-use std::str::from_utf8;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::io::psse::{error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -37,29 +37,27 @@ pub struct MultiSectionLine {
     pub dummy_bus_ids: Vec<i32>,
 }
 
-pub fn parse_multisection_lines(lines: &[&[u8]]) -> Vec<MultiSectionLine> {
+pub fn parse_multisection_lines(lines: &[&[u8]], ctx: &SectionContext) -> Result<Vec<MultiSectionLine>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
-    lines.par_iter().filter_map(|line_bytes| {
-        from_utf8(line_bytes).ok().and_then(|line| {
-            let parts: Vec<&str> = line.split(",").map(|s| s.trim()).collect();
-            Some(MultiSectionLine{
-                from_bus: parts[0].parse().unwrap_or(0),
-                to_bus: parts[1].parse().unwrap_or(0),
-                circuit: parts[2].replace("'", "").trim().to_string(),
-                meter_end: parts[3].parse().unwrap_or(1),
-                dummy_bus_ids: vec![
-                    parts.get(4).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(5).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(6).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(7).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(8).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(9).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(10).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(11).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(12).and_then(|s| s.parse().ok()).unwrap_or(0),
-                ]
-            })
+    if lines.is_empty() {return Ok(Vec::new());}
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::MultiSectionLine)?;
+        Ok(MultiSectionLine{
+            from_bus: rec.required(0, "I", 0)?,
+            to_bus: rec.required(1, "J", 0)?,
+            circuit: rec.required_str(2, "CKT")?,
+            meter_end: rec.required(3, "MET", 1)?,
+            dummy_bus_ids: vec![
+                rec.optional(4, "DUM1", 0)?,
+                rec.optional(5, "DUM2", 0)?,
+                rec.optional(6, "DUM3", 0)?,
+                rec.optional(7, "DUM4", 0)?,
+                rec.optional(8, "DUM5", 0)?,
+                rec.optional(9, "DUM6", 0)?,
+                rec.optional(10, "DUM7", 0)?,
+                rec.optional(11, "DUM8", 0)?,
+                rec.optional(12, "DUM9", 0)?,
+            ]
         })
     }).collect()
 }
@@ -72,8 +70,8 @@ mod tests {
     fn parse_multiterminal_0_dummies() {
         let multiterminal_str: &'static str = "1997,  1898, '&1', 1,";
         let lines: Vec<&[u8]> = multiterminal_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<MultiSectionLine> = parse_multisection_lines(&lines);
-        let multiterminal: Option<&MultiSectionLine> = binding.get(0);
+        let binding: Vec<MultiSectionLine> = parse_multisection_lines(&lines, &SectionContext::default()).unwrap();
+        let multiterminal: Option<&MultiSectionLine> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(multiterminal.unwrap().from_bus, 1997);
@@ -87,8 +85,8 @@ mod tests {
     fn parse_multiterminal_2_dummies() {
         let multiterminal_str: &'static str = "1997,  1898, '&1', 1, 1111,  2222,";
         let lines: Vec<&[u8]> = multiterminal_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<MultiSectionLine> = parse_multisection_lines(&lines);
-        let multiterminal: Option<&MultiSectionLine> = binding.get(0);
+        let binding: Vec<MultiSectionLine> = parse_multisection_lines(&lines, &SectionContext::default()).unwrap();
+        let multiterminal: Option<&MultiSectionLine> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(multiterminal.unwrap().from_bus, 1997);
@@ -102,8 +100,8 @@ mod tests {
     fn parse_multiterminal_9_dummies() {
         let multiterminal_str: &'static str = "1997,  1898, '&1', 1, 1111,  2222, 3333, 4444, 5555, 66, 7, 88888, 9909";
         let lines: Vec<&[u8]> = multiterminal_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<MultiSectionLine> = parse_multisection_lines(&lines);
-        let multiterminal: Option<&MultiSectionLine> = binding.get(0);
+        let binding: Vec<MultiSectionLine> = parse_multisection_lines(&lines, &SectionContext::default()).unwrap();
+        let multiterminal: Option<&MultiSectionLine> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(multiterminal.unwrap().from_bus, 1997);
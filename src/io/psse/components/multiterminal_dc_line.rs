@@ -1,4 +1,4 @@
-use std::str::from_utf8;
+use crate::io::psse::{error::ParseError, pssedata::PSSEDatatype, record::SectionContext};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -169,62 +169,56 @@ pub struct MultiTermDCLine {
     pub owner: i16,
 }
 
-pub fn parse_multiterminal_dc_line(lines: &[&[u8]]) -> Vec<MultiTermDCLine> {
+pub fn parse_multiterminal_dc_line(lines: &[&[u8]], ctx: &SectionContext) -> Result<Vec<MultiTermDCLine>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
+    if lines.is_empty() {return Ok(Vec::new());}
     let mut mt_dc_lines: Vec<MultiTermDCLine> = Vec::new();
     let mut i: usize = 0;
     while i < lines.len() {
-        if let (Ok(line1_str), Ok(line2_str), Ok(line3_str)) = (
-            from_utf8(lines[i]), from_utf8(lines[i + 1]), from_utf8(lines[i + 2])
-        ) {
-            let parts1: Vec<&str> = line1_str.split(',').map(|s| s.trim()).collect();
-            let parts2: Vec<&str> = line2_str.split(',').map(|s| s.trim()).collect();
-            let parts3: Vec<&str> = line3_str.split(',').map(|s| s.trim()).collect();
+        let [rec1, rec2, rec3] = ctx.records(lines, i, PSSEDatatype::MultiTerminalDc)?;
 
-            let mt_dc_line: MultiTermDCLine = MultiTermDCLine {
-                name: parts1[0].replace("'", "").trim().to_string(),
-                num_converters: parts1[1].parse().unwrap_or(0),
-                num_dc_buses: parts1[2].parse().unwrap_or(0),
-                num_dc_links: parts1[3].parse().unwrap_or(0),
-                control_mode: parts1[4].parse().unwrap_or(0),
-                positive_ac_conv_bus_id: parts1[5].parse().unwrap_or(0),
-                mode_switch_dc_volt: parts1[6].parse().unwrap_or(0.0),
-                negative_ac_conv_bus_id: parts1[7].parse().unwrap_or(0),
+        let mt_dc_line: MultiTermDCLine = MultiTermDCLine {
+            name: rec1.required_str(0, "NAME")?,
+            num_converters: rec1.required(1, "NCONV", 0)?,
+            num_dc_buses: rec1.required(2, "NDCBS", 0)?,
+            num_dc_links: rec1.required(3, "NDCLN", 0)?,
+            control_mode: rec1.required(4, "MDC", 0)?,
+            positive_ac_conv_bus_id: rec1.required(5, "VCONV", 0)?,
+            mode_switch_dc_volt: rec1.required(6, "VCMOD", 0.0)?,
+            negative_ac_conv_bus_id: rec1.required(7, "VCONVN", 0)?,
 
-                // Line 2
+            // Line 2
 
-                ac_conv_bus_1_id: parts2[0].parse().unwrap_or(0),
-                series_bridges: parts2[1].parse().unwrap_or(0),
-                max_alpha_gamma: parts2[2].parse().unwrap_or(0.0),
-                min_alpha_gamma: parts2[3].parse().unwrap_or(0.0),
-                bridge_r: parts2[4].parse().unwrap_or(0.0),
-                bridge_x: parts2[5].parse().unwrap_or(0.0),
-                base_ac_volt: parts2[6].parse().unwrap_or(0.0),
-                xfmr_ratio: parts2[7].parse().unwrap_or(1.0),
-                xfmr_tap: parts2[8].parse().unwrap_or(1.0),
-                xfmr_max_tap: parts2[9].parse().unwrap_or(1.5),
-                xfmr_min_tap: parts2[10].parse().unwrap_or(0.00625),
-                conv_setpoint: parts2[11].parse().unwrap_or(0.0),
-                conv_participation_factor: parts2[12].parse().unwrap_or(1.0),
-                rec_margin: parts2[13].parse().unwrap_or(0.0),
-                conv_code: parts2[14].parse().unwrap_or(1),
+            ac_conv_bus_1_id: rec2.required(0, "IB", 0)?,
+            series_bridges: rec2.required(1, "N", 0)?,
+            max_alpha_gamma: rec2.required(2, "ANGMX", 0.0)?,
+            min_alpha_gamma: rec2.required(3, "ANGMN", 0.0)?,
+            bridge_r: rec2.required(4, "RC", 0.0)?,
+            bridge_x: rec2.required(5, "XC", 0.0)?,
+            base_ac_volt: rec2.required(6, "EBAS", 0.0)?,
+            xfmr_ratio: rec2.required(7, "TR", 1.0)?,
+            xfmr_tap: rec2.required(8, "TAP", 1.0)?,
+            xfmr_max_tap: rec2.required(9, "TPMX", 1.5)?,
+            xfmr_min_tap: rec2.required(10, "TPMN", 0.00625)?,
+            conv_setpoint: rec2.required(11, "SETVL", 0.0)?,
+            conv_participation_factor: rec2.required(12, "DCPF", 1.0)?,
+            rec_margin: rec2.required(13, "MARG", 0.0)?,
+            conv_code: rec2.required(14, "CNVCOD", 1)?,
 
-                // Line 3
-                dc_bus_num: parts3[0].parse().unwrap_or(1),
-                ac_conv_bus_2_id: parts3[1].parse().unwrap_or(0),
-                area: parts3[2].parse().unwrap_or(1),
-                zone: parts3[3].parse().unwrap_or(1),
-                dc_bus_name: parts3[4].replace("'", "").trim().to_string(),
-                dc_bus_num_2: parts3[5].parse().unwrap_or(1),
-                resist_to_ground: parts3[6].parse().unwrap_or(0.0),
-                owner: parts3[7].parse().unwrap_or(1),
-            };
-            
-            mt_dc_lines.push(mt_dc_line);
+            // Line 3
+            dc_bus_num: rec3.required(0, "IDC", 1)?,
+            ac_conv_bus_2_id: rec3.required(1, "IB", 0)?,
+            area: rec3.required(2, "AREA", 1)?,
+            zone: rec3.required(3, "ZONE", 1)?,
+            dc_bus_name: rec3.required_str(4, "DCNAME")?,
+            dc_bus_num_2: rec3.required(5, "IDC2", 1)?,
+            resist_to_ground: rec3.required(6, "RGRND", 0.0)?,
+            owner: rec3.required(7, "OWNER", 1)?,
+        };
+        
+        mt_dc_lines.push(mt_dc_line);
 
-            i += 3;
-        }        
+        i += 3;
     }
-    mt_dc_lines
+    Ok(mt_dc_lines)
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
-use std::str::from_utf8;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::io::psse::{error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -18,16 +18,14 @@ pub struct Owner {
     pub owner_name: String,
 }
 
-pub fn parse_owners(lines: &[&[u8]]) -> Vec<Owner> {
+pub fn parse_owners(lines: &[&[u8]], ctx: &SectionContext) -> Result<Vec<Owner>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
-    lines.par_iter().filter_map(|line_bytes| {
-        from_utf8(line_bytes).ok().and_then(|line| {
-            let parts: Vec<&str> = line.split(",").map(|s| s.trim()).collect();
-            Some(Owner{
-                owner_id: parts[0].parse().unwrap_or(0),
-                owner_name: parts[1].replace("'", "").trim().to_string(),
-            })
+    if lines.is_empty() {return Ok(Vec::new());}
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::Owner)?;
+        Ok(Owner{
+            owner_id: rec.required(0, "I", 0)?,
+            owner_name: rec.required_str(1, "OWNAME")?,
         })
     }).collect()
 }
\ No newline at end of file
@@ -0,0 +1,333 @@
+use std::str::from_utf8;
+
+use crate::io::psse::{error::ParseError, parsers::sections::is_terminator, pssedata::PSSEDatatype, record::{Record, SectionContext}};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+/// A struct containing the information for each substation in the PSS/E .RAW case (V35+)
+///
+/// Each substation holds the node-breaker model of its buses: the nodes, the switching devices
+/// between them and the terminals connecting equipment to the nodes.
+pub struct Substation {
+    /// ## IS: Substation number
+    /// ### Type: i32
+    /// ### Default: 0
+    /// ## Range
+    /// * 1 - 99999
+    pub id: i32,
+    /// ## NAME: Substation name
+    /// ### Type: String
+    /// ### Default: " "
+    pub name: String,
+    /// ## LATI: Substation latitude
+    /// ### Type: f64
+    /// ### Units: degrees
+    /// ### Default: 0.0
+    pub latitude: f64,
+    /// ## LONG: Substation longitude
+    /// ### Type: f64
+    /// ### Units: degrees
+    /// ### Default: 0.0
+    pub longitude: f64,
+    /// ## SRG: Substation grounding DC resistance
+    /// ### Type: f64
+    /// ### Units: ohms
+    /// ### Default: 0.1
+    pub ground_resistance: f64,
+    /// ## Nodes of the substation
+    pub nodes: Vec<SubstationNode>,
+    /// ## Switching devices connecting the nodes of the substation
+    pub switching_devices: Vec<SubstationSwitchingDevice>,
+    /// ## Terminals connecting equipment to the nodes of the substation
+    pub terminals: Vec<SubstationTerminal>,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+/// A node of a substation, belonging to one of the substation's buses
+pub struct SubstationNode {
+    /// ## NI: Node number, unique within the substation
+    /// ### Type: i32
+    /// ### Default: 0
+    /// ## Range
+    /// * 1 - 999
+    pub node: i32,
+    /// ## NAME: Node name
+    /// ### Type: String
+    /// ### Default: " "
+    pub name: String,
+    /// ## I: Bus number the node belongs to
+    /// ### Type: i32
+    /// ### Default: 0
+    pub bus_id: i32,
+    /// ## STATUS: Node status
+    /// ### Type: i8
+    /// ### Default: 1
+    /// ## Parameters
+    /// * 0 : Out-of-service
+    /// * 1 : In-service
+    pub status: i8,
+    /// ## VM: Node voltage magnitude
+    /// ### Type: f64
+    /// ### Units: p.u.
+    /// ### Default: 1.0
+    pub vm_pu: f64,
+    /// ## VA: Node voltage phase angle
+    /// ### Type: f64
+    /// ### Units: degrees
+    /// ### Default: 0.0
+    pub va_deg: f64,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+/// A switching device (breaker, disconnect, ...) between two nodes of a substation
+pub struct SubstationSwitchingDevice {
+    /// ## NI: From node number
+    /// ### Type: i32
+    /// ### Default: 0
+    pub from_node: i32,
+    /// ## NJ: To node number
+    /// ### Type: i32
+    /// ### Default: 0
+    pub to_node: i32,
+    /// ## CKT: Switching device circuit identifier
+    /// ### Type: String
+    /// ### Default: "1"
+    pub circuit: String,
+    /// ## NAME: Switching device name
+    /// ### Type: String
+    /// ### Default: " "
+    pub name: String,
+    /// ## TYPE: Switching device type
+    /// ### Type: i8
+    /// ### Default: 1
+    /// ## Parameters
+    /// * 1 : Generic connector
+    /// * 2 : Circuit breaker
+    /// * 3 : Disconnect switch
+    pub device_type: i8,
+    /// ## STATUS: Switching device status
+    /// ### Type: i8
+    /// ### Default: 1
+    /// ## Parameters
+    /// * 0 : Open
+    /// * 1 : Closed
+    pub status: i8,
+    /// ## NSTAT: Switching device normal status
+    /// ### Type: i8
+    /// ### Default: 1
+    /// ## Parameters
+    /// * 0 : Normally open
+    /// * 1 : Normally closed
+    pub normal_status: i8,
+    /// ## X: Switching device reactance
+    /// ### Type: f64
+    /// ### Units: p.u.
+    /// ### Default: 0.0001
+    pub x: f64,
+    /// ## RATE1: First rating
+    /// ### Type: f64
+    /// ### Units: MVA
+    /// ### Default: 0.0
+    pub rate1: f64,
+    /// ## RATE2: Second rating
+    /// ### Type: f64
+    /// ### Units: MVA
+    /// ### Default: 0.0
+    pub rate2: f64,
+    /// ## RATE3: Third rating
+    /// ### Type: f64
+    /// ### Units: MVA
+    /// ### Default: 0.0
+    pub rate3: f64,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+/// The connection of one end of a piece of equipment to a substation node
+pub struct SubstationTerminal {
+    /// ## I: Bus number of the equipment end connected to the node
+    /// ### Type: i32
+    /// ### Default: 0
+    pub bus_id: i32,
+    /// ## NI: Node number the equipment is connected to
+    /// ### Type: i32
+    /// ### Default: 0
+    pub node: i32,
+    /// ## TYPE: Type of equipment connected
+    /// ### Type: String
+    /// ### Default: " "
+    /// ## Parameters
+    /// * L : Load
+    /// * F : Fixed shunt
+    /// * M : Machine (generator)
+    /// * B : Non-transformer branch
+    /// * 2 : Two winding transformer
+    /// * 3 : Three winding transformer
+    /// * S : Switched shunt
+    /// * I : Induction machine
+    /// * D : Two-terminal DC line
+    /// * V : VSC DC line
+    /// * N : Multi-terminal DC line
+    /// * A : FACTS device
+    pub equipment_type: String,
+    /// ## J: Other bus of two and three terminal equipment
+    /// ### Type: i32
+    /// ### Default: 0
+    pub other_bus_id: i32,
+    /// ## K: Third bus of three winding transformers
+    /// ### Type: i32
+    /// ### Default: 0
+    pub third_bus_id: i32,
+    /// ## ID: Equipment identifier (or circuit identifier for branches and transformers)
+    /// ### Type: String
+    /// ### Default: "1"
+    pub id: String,
+}
+
+pub fn parse_substations(lines: &[&[u8]], ctx: &SectionContext) -> Result<Vec<Substation>, ParseError> {
+    //Check if there is even data before proceeding
+    if lines.is_empty() {return Ok(Vec::new());}
+    let mut substations: Vec<Substation> = Vec::new();
+    let mut i: usize = 0;
+
+    //Each substation record is followed by its node, switching device and terminal data, each block closed by a terminator
+    while let Some(index) = next_record(lines, i) {
+        if is_terminator(text(lines[index])) {
+            i = index + 1;
+            continue;
+        }
+        let rec: Record = ctx.record(lines[index], index, PSSEDatatype::Substation)?;
+        let mut substation: Substation = Substation {
+            id: rec.required(0, "IS", 0)?,
+            name: rec.required_str(1, "NAME")?,
+            latitude: rec.optional(2, "LATI", 0.0)?,
+            longitude: rec.optional(3, "LONG", 0.0)?,
+            ground_resistance: rec.optional(4, "SRG", 0.1)?,
+            ..Default::default()
+        };
+        //The substation record itself ends with the terminator opening the node data
+        i = skip_block(lines, index + 1);
+        let (node_lines, end) = block(lines, i);
+        for &index in &node_lines {
+            let rec: Record = ctx.record(lines[index], index, PSSEDatatype::Substation)?;
+            substation.nodes.push(SubstationNode {
+                node: rec.required(0, "NI", 0)?,
+                name: rec.required_str(1, "NAME")?,
+                bus_id: rec.required(2, "I", 0)?,
+                status: rec.required(3, "STATUS", 1)?,
+                vm_pu: rec.optional(4, "VM", 1.0)?,
+                va_deg: rec.optional(5, "VA", 0.0)?,
+            });
+        }
+        let (device_lines, end) = block(lines, end);
+        for &index in &device_lines {
+            let rec: Record = ctx.record(lines[index], index, PSSEDatatype::Substation)?;
+            substation.switching_devices.push(SubstationSwitchingDevice {
+                from_node: rec.required(0, "NI", 0)?,
+                to_node: rec.required(1, "NJ", 0)?,
+                circuit: rec.required_str(2, "CKT")?,
+                name: rec.required_str(3, "NAME")?,
+                device_type: rec.required(4, "TYPE", 1)?,
+                status: rec.required(5, "STATUS", 1)?,
+                normal_status: rec.required(6, "NSTAT", 1)?,
+                x: rec.required(7, "X", 0.0001)?,
+                rate1: rec.optional(8, "RATE1", 0.0)?,
+                rate2: rec.optional(9, "RATE2", 0.0)?,
+                rate3: rec.optional(10, "RATE3", 0.0)?,
+            });
+        }
+        let (terminal_lines, end) = block(lines, end);
+        for &index in &terminal_lines {
+            let rec: Record = ctx.record(lines[index], index, PSSEDatatype::Substation)?;
+            //The equipment ID is always last, after the one to three buses of the equipment
+            let id_index: usize = rec.len().clamp(4, 6) - 1;
+            substation.terminals.push(SubstationTerminal {
+                bus_id: rec.required(0, "I", 0)?,
+                node: rec.required(1, "NI", 0)?,
+                equipment_type: rec.required_str(2, "TYPE")?,
+                other_bus_id: if id_index > 3 { rec.required(3, "J", 0)? } else { 0 },
+                third_bus_id: if id_index > 4 { rec.required(4, "K", 0)? } else { 0 },
+                id: rec.required_str(id_index, "ID")?,
+            });
+        }
+        substations.push(substation);
+        i = end;
+    }
+    Ok(substations)
+}
+
+fn text(line_bytes: &[u8]) -> &str {
+    from_utf8(line_bytes).unwrap_or("").trim()
+}
+
+//Index of the next line holding a record or terminator, skipping blank lines and @! comments
+fn next_record(lines: &[&[u8]], start: usize) -> Option<usize> {
+    (start..lines.len()).find(|&index| {
+        let line: &str = text(lines[index]);
+        !line.is_empty() && !line.starts_with('@')
+    })
+}
+
+//Returns the record lines of a block and the index following its terminator
+fn block(lines: &[&[u8]], start: usize) -> (Vec<usize>, usize) {
+    let mut records: Vec<usize> = Vec::new();
+    let mut index: usize = start;
+    while let Some(record) = next_record(lines, index) {
+        if is_terminator(text(lines[record])) {
+            return (records, record + 1);
+        }
+        records.push(record);
+        index = record + 1;
+    }
+    (records, lines.len())
+}
+
+//Moves past the terminator of a block without reading its records
+fn skip_block(lines: &[&[u8]], start: usize) -> usize {
+    block(lines, start).1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_substation_v35() {
+        let substation_str: &'static str = "@! IS,'NAME', LATI, LONG, SRG
+    1,'STATION A', 45.5000, -73.6000, 0.1000
+0 / BEGIN SUBSTATION NODE DATA
+@! NI,'NAME', I, STATUS, VM, VA
+    1,'NB1', 151, 1, 1.0100, -6.2000
+    2,'NB2', 151, 1, 1.0100, -6.2000
+0 / END OF SUBSTATION NODE DATA, BEGIN SUBSTATION SWITCHING DEVICE DATA
+    1,    2,'1 ','BKR 1-2', 2, 1, 1, 0.0001, 100.0, 110.0, 120.0
+0 / END OF SUBSTATION SWITCHING DEVICE DATA, BEGIN SUBSTATION TERMINAL DATA
+  151,    1,'M','1 '
+  151,    2,'B',  152,'1 '
+  151,    2,'3',  152,  153,'T3'
+0 / END OF SUBSTATION TERMINAL DATA
+    2,'STATION B'
+0 / BEGIN SUBSTATION NODE DATA
+0 / END OF SUBSTATION NODE DATA, BEGIN SUBSTATION SWITCHING DEVICE DATA
+0 / END OF SUBSTATION SWITCHING DEVICE DATA, BEGIN SUBSTATION TERMINAL DATA
+0 / END OF SUBSTATION TERMINAL DATA";
+        let lines: Vec<&[u8]> = substation_str.lines().map(|line| line.as_bytes()).collect();
+        let substations: Vec<Substation> = parse_substations(&lines, &SectionContext::default()).unwrap();
+        //To avoid checking everything, check bits and pieces to make sure things are in the right place
+        assert_eq!(substations.len(), 2);
+        assert_eq!(substations[0].name, "STATION A".to_string());
+        assert_eq!(substations[0].longitude, -73.6);
+        assert_eq!(substations[0].nodes[1].node, 2);
+        assert_eq!(substations[0].nodes[1].bus_id, 151);
+        assert_eq!(substations[0].switching_devices[0].name, "BKR 1-2".to_string());
+        assert_eq!(substations[0].switching_devices[0].rate3, 120.0);
+        assert_eq!(substations[0].terminals[0], SubstationTerminal { bus_id: 151, node: 1, equipment_type: "M".to_string(), other_bus_id: 0, third_bus_id: 0, id: "1".to_string() });
+        assert_eq!(substations[0].terminals[1].other_bus_id, 152);
+        assert_eq!(substations[0].terminals[2].third_bus_id, 153);
+        assert_eq!(substations[0].terminals[2].id, "T3".to_string());
+        assert_eq!(substations[1].ground_resistance, 0.1);
+        assert!(substations[1].nodes.is_empty());
+    }
+}
@@ -1,6 +1,6 @@
-use std::str::from_utf8;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::io::psse::{components::codes::{Status, SwitchedShuntControlMode}, error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -91,62 +91,64 @@ pub struct SwitchedShunt {
     pub b_increment: Vec<f64>   //Increment for each step for each block
 }
 
-pub fn parse_switched_shunts(lines: &[&[u8]], psse_version: i8) -> Vec<SwitchedShunt> {
+const BLOCK_STATUS: [&str; 8] = ["S1", "S2", "S3", "S4", "S5", "S6", "S7", "S8"];
+const BLOCK_STEPS: [&str; 8] = ["N1", "N2", "N3", "N4", "N5", "N6", "N7", "N8"];
+const BLOCK_INCREMENT: [&str; 8] = ["B1", "B2", "B3", "B4", "B5", "B6", "B7", "B8"];
+
+impl SwitchedShunt {
+    /// Control mode of the MODSW code
+    pub fn shunt_control_mode(&self) -> SwitchedShuntControlMode {
+        SwitchedShuntControlMode::from(self.control_mode)
+    }
+
+    /// Status of the STAT code
+    pub fn service_status(&self) -> Status {
+        Status::from(self.status)
+    }
+}
+
+pub fn parse_switched_shunts(lines: &[&[u8]], ctx: &SectionContext, psse_version: i8) -> Result<Vec<SwitchedShunt>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
-    let parse_adder_1: usize = (psse_version >= 34) as usize;
-    let parse_adder_2: usize = parse_adder_1 * 2;
-    
-    let v33_status: Vec<i8> = vec![1, 1, 1, 1, 1, 1, 1, 1];
-    lines.par_iter().filter_map(|line_bytes| {
-        from_utf8(line_bytes).ok().and_then(|line| {
-            let parts: Vec<&str> = line.split(",").map(|s| s.trim()).collect();
-            Some(SwitchedShunt{
-                bus_id: parts[0].parse().unwrap_or(0),
-                id: parts[1].replace("'", "").trim().to_string(),
-                control_mode: parts[1 + parse_adder_1].parse().unwrap_or(1),
-                adjust_method: parts[2 + parse_adder_1].parse().unwrap_or(0),
-                status: parts[3 + parse_adder_1].parse().unwrap_or(1),
-                upper_limit: parts[4 + parse_adder_1].parse().unwrap_or(1.0),
-                x_lower_limit: parts[5 + parse_adder_1].parse().unwrap_or(1.0),
-                controlled_bus_id: parts[6 + parse_adder_1].parse().unwrap_or(0),
-                controlled_bus_node: parts[7 + parse_adder_1].parse().unwrap_or(0),
-                mvar_contribution_perc: parts[7 + parse_adder_2].parse().unwrap_or(100.0),
-                controlled_bus_name: parts[8 + parse_adder_2].replace("'", "").trim().to_string(),
-                b_init: parts[9 + parse_adder_2].parse().unwrap_or(0.0),
-                block_status: (psse_version >= 34).then_some(
-                    vec![
-                    parts.get(12).and_then(|s| s.parse().ok()).unwrap_or(1),
-                    parts.get(15).and_then(|s| s.parse().ok()).unwrap_or(1),
-                    parts.get(18).and_then(|s| s.parse().ok()).unwrap_or(1),
-                    parts.get(21).and_then(|s| s.parse().ok()).unwrap_or(1),
-                    parts.get(24).and_then(|s| s.parse().ok()).unwrap_or(1),
-                    parts.get(27).and_then(|s| s.parse().ok()).unwrap_or(1),
-                    parts.get(30).and_then(|s| s.parse().ok()).unwrap_or(1),
-                    parts.get(33).and_then(|s| s.parse().ok()).unwrap_or(1),
-                    ]
-                ).unwrap_or(v33_status.clone()),
-                steps: vec![
-                    parts.get(10 + (parse_adder_1 * 3)).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(12 + (parse_adder_1 * 4)).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(14 + (parse_adder_1 * 5)).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(16 + (parse_adder_1 * 6)).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(18 + (parse_adder_1 * 7)).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(20 + (parse_adder_1 * 8)).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(22 + (parse_adder_1 * 9)).and_then(|s| s.parse().ok()).unwrap_or(0),
-                    parts.get(24 + (parse_adder_1 * 10)).and_then(|s| s.parse().ok()).unwrap_or(0),
-                ],
-                b_increment: vec![
-                    parts.get(11 + (parse_adder_1 * 3)).and_then(|s| s.parse().ok()).unwrap_or(0.0),
-                    parts.get(13 + (parse_adder_1 * 4)).and_then(|s| s.parse().ok()).unwrap_or(0.0),
-                    parts.get(15 + (parse_adder_1 * 5)).and_then(|s| s.parse().ok()).unwrap_or(0.0),
-                    parts.get(17 + (parse_adder_1 * 6)).and_then(|s| s.parse().ok()).unwrap_or(0.0),
-                    parts.get(19 + (parse_adder_1 * 7)).and_then(|s| s.parse().ok()).unwrap_or(0.0),
-                    parts.get(21 + (parse_adder_1 * 8)).and_then(|s| s.parse().ok()).unwrap_or(0.0),
-                    parts.get(23 + (parse_adder_1 * 9)).and_then(|s| s.parse().ok()).unwrap_or(0.0),
-                    parts.get(25 + (parse_adder_1 * 10)).and_then(|s| s.parse().ok()).unwrap_or(0.0),
-                ],
-            })
+    if lines.is_empty() {return Ok(Vec::new());}
+    // V35 added the switched shunt ID after the bus number
+    let id_adder: usize = (psse_version >= 35) as usize;
+    // V34 added NREG after SWREG and a status in front of each block
+    let node_adder: usize = (psse_version >= 34) as usize;
+    // ADJM and STAT were added in V32
+    let adjust_adder: usize = ((psse_version >= 32) as usize) * 2;
+    let block_start: usize = 8 + id_adder + adjust_adder + node_adder;
+    let block_width: usize = 2 + node_adder;
+
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::SwitchedShunt)?;
+        let mut block_status: Vec<i8> = vec![1; 8];
+        let mut steps: Vec<i32> = vec![0; 8];
+        let mut b_increment: Vec<f64> = vec![0.0; 8];
+        for block in 0..8 {
+            let position: usize = block_start + block * block_width;
+            if psse_version >= 34 {
+                block_status[block] = rec.optional(position, BLOCK_STATUS[block], 1)?;
+            }
+            steps[block] = rec.optional(position + node_adder, BLOCK_STEPS[block], 0)?;
+            b_increment[block] = rec.optional(position + node_adder + 1, BLOCK_INCREMENT[block], 0.0)?;
+        }
+        Ok(SwitchedShunt{
+            bus_id: rec.required(0, "I", 0)?,
+            // Before V35 there is no switched shunt identifier, so every shunt takes the default
+            id: if psse_version >= 35 { rec.required_str(1, "ID")? } else { "1".to_string() },
+            control_mode: rec.required(1 + id_adder, "MODSW", 1)?,
+            adjust_method: if psse_version >= 32 { rec.required(2 + id_adder, "ADJM", 0)? } else { 0 },
+            status: if psse_version >= 32 { rec.required(3 + id_adder, "STAT", 1)? } else { 1 },
+            upper_limit: rec.required(2 + id_adder + adjust_adder, "VSWHI", 1.0)?,
+            x_lower_limit: rec.required(3 + id_adder + adjust_adder, "VSWLO", 1.0)?,
+            controlled_bus_id: rec.required(4 + id_adder + adjust_adder, "SWREG", 0)?,
+            controlled_bus_node: if psse_version >= 34 { rec.required(5 + id_adder + adjust_adder, "NREG", 0)? } else { 0 },
+            mvar_contribution_perc: rec.required(5 + id_adder + adjust_adder + node_adder, "RMPCT", 100.0)?,
+            controlled_bus_name: rec.required_str(6 + id_adder + adjust_adder + node_adder, "RMIDNT")?,
+            b_init: rec.required(7 + id_adder + adjust_adder + node_adder, "BINIT", 0.0)?,
+            block_status,
+            steps,
+            b_increment,
         })
     }).collect()
 }
@@ -159,8 +161,8 @@ mod tests {
     fn parse_ss_1_entry_v35() {
         let ss_str: &'static str = "  55555, '8 ',  1,0,1,    1.04000,   1.00000,      1907,   1708,  90.0,'SSTEST       ',  225.000,  0,  4,   75.000,";
         let lines: Vec<&[u8]> = ss_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<SwitchedShunt> = parse_switched_shunts(&lines, 35);
-        let switched_shunt: Option<&SwitchedShunt> = binding.get(0);
+        let binding: Vec<SwitchedShunt> = parse_switched_shunts(&lines, &SectionContext::default(), 35).unwrap();
+        let switched_shunt: Option<&SwitchedShunt> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(switched_shunt.unwrap().bus_id, 55555);
@@ -184,8 +186,8 @@ mod tests {
     fn parse_ss_7_entry_v35() {
         let ss_str: &'static str = "  55555, '8 ',  1,0,1,    1.04000,   1.00000,      1907,   1708,  90.0,'SSTEST       ',  225.000,  1,  2,   75.000, 2,  3,   80.000, 3,  4,   85.000, 4,  5,   90.000, 5,  6,   95.000, 6,  7,   100.000, 7,  8,   105.000,";
         let lines: Vec<&[u8]> = ss_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<SwitchedShunt> = parse_switched_shunts(&lines, 35);
-        let switched_shunt: Option<&SwitchedShunt> = binding.get(0);
+        let binding: Vec<SwitchedShunt> = parse_switched_shunts(&lines, &SectionContext::default(), 35).unwrap();
+        let switched_shunt: Option<&SwitchedShunt> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(switched_shunt.unwrap().bus_id, 55555);
@@ -209,8 +211,8 @@ mod tests {
     fn parse_ss_1_entry_v33() {
         let ss_str: &'static str = "  55555,  1,0,1,    1.04000,   1.00000,      1907,  90.0,'SSTEST       ',  225.000,  4,   75.000,";
         let lines: Vec<&[u8]> = ss_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<SwitchedShunt> = parse_switched_shunts(&lines, 33);
-        let switched_shunt: Option<&SwitchedShunt> = binding.get(0);
+        let binding: Vec<SwitchedShunt> = parse_switched_shunts(&lines, &SectionContext::default(), 33).unwrap();
+        let switched_shunt: Option<&SwitchedShunt> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(switched_shunt.unwrap().bus_id, 55555);
@@ -234,8 +236,8 @@ mod tests {
     fn parse_ss_7_entry_v33() {
         let ss_str: &'static str = "  55555,  1,0,1,    1.04000,   1.00000,      1907,  90.0,'SSTEST       ',  225.000,  2,   75.000,  3,   80.000,  4,   85.000,  5,   90.000,  6,   95.000,  7,   100.000,  8,   105.000,";
         let lines: Vec<&[u8]> = ss_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<SwitchedShunt> = parse_switched_shunts(&lines, 33);
-        let switched_shunt: Option<&SwitchedShunt> = binding.get(0);
+        let binding: Vec<SwitchedShunt> = parse_switched_shunts(&lines, &SectionContext::default(), 33).unwrap();
+        let switched_shunt: Option<&SwitchedShunt> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(switched_shunt.unwrap().bus_id, 55555);
@@ -1,6 +1,6 @@
-use std::str::from_utf8;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::io::psse::{error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -124,35 +124,33 @@ pub struct SystemSwitchingDevice {
     pub name: String,
 }
 
-pub fn parse_system_switching_device(lines: &[&[u8]]) -> Vec<SystemSwitchingDevice> {
+pub fn parse_system_switching_device(lines: &[&[u8]], ctx: &SectionContext) -> Result<Vec<SystemSwitchingDevice>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
-    lines.par_iter().filter_map(|line_bytes| {
-        from_utf8(line_bytes).ok().and_then(|line| {
-            let parts: Vec<&str> = line.split(",").map(|s| s.trim()).collect();
-            Some(SystemSwitchingDevice{
-                from_bus: parts[0].parse().unwrap_or(0),
-                to_bus: parts[1].parse().unwrap_or(0),
-                circuit: parts[2].replace("'", "").trim().to_string(),
-                x: parts[3].parse().unwrap_or(0.0001),
-                rate1: parts[4].parse().unwrap_or(0.0),
-                rate2: parts[5].parse().unwrap_or(0.0),
-                rate3: parts[6].parse().unwrap_or(0.0),
-                rate4: parts[7].parse().unwrap_or(0.0),
-                rate5: parts[8].parse().unwrap_or(0.0),
-                rate6: parts[9].parse().unwrap_or(0.0),
-                rate7: parts[10].parse().unwrap_or(0.0),
-                rate8: parts[11].parse().unwrap_or(0.0),
-                rate9: parts[12].parse().unwrap_or(0.0),
-                rate10: parts[13].parse().unwrap_or(0.0),
-                rate11: parts[14].parse().unwrap_or(0.0),
-                rate12: parts[15].parse().unwrap_or(0.0),
-                status: parts[16].parse().unwrap_or(1),
-                normal_status: parts[17].parse().unwrap_or(1),
-                meter_end: parts[18].parse().unwrap_or(1),
-                device_type: parts[19].parse().unwrap_or(1),
-                name: parts[20].replace("'", "").trim().to_string(),
-            })
+    if lines.is_empty() {return Ok(Vec::new());}
+    lines.par_iter().enumerate().map(|(i, line_bytes)| {
+        let rec: Record = ctx.record(line_bytes, i, PSSEDatatype::SystemSwitchingDevice)?;
+        Ok(SystemSwitchingDevice{
+            from_bus: rec.required(0, "I", 0)?,
+            to_bus: rec.required(1, "J", 0)?,
+            circuit: rec.required_str(2, "CKT")?,
+            x: rec.required(3, "X", 0.0001)?,
+            rate1: rec.required(4, "RATE1", 0.0)?,
+            rate2: rec.required(5, "RATE2", 0.0)?,
+            rate3: rec.required(6, "RATE3", 0.0)?,
+            rate4: rec.required(7, "RATE4", 0.0)?,
+            rate5: rec.required(8, "RATE5", 0.0)?,
+            rate6: rec.required(9, "RATE6", 0.0)?,
+            rate7: rec.required(10, "RATE7", 0.0)?,
+            rate8: rec.required(11, "RATE8", 0.0)?,
+            rate9: rec.required(12, "RATE9", 0.0)?,
+            rate10: rec.required(13, "RATE10", 0.0)?,
+            rate11: rec.required(14, "RATE11", 0.0)?,
+            rate12: rec.required(15, "RATE12", 0.0)?,
+            status: rec.required(16, "STATUS", 1)?,
+            normal_status: rec.required(17, "NSTATUS", 1)?,
+            meter_end: rec.required(18, "METERD", 1)?,
+            device_type: rec.required(19, "STYPE", 1)?,
+            name: rec.required_str(20, "NAME")?,
         })
     }).collect()
 }
@@ -1,4 +1,4 @@
-use std::str::from_utf8;
+use crate::io::psse::{components::codes::{ImpedanceIoCode, MagnetizingIoCode, TapControlMode, TransformerStatus, WindingIoCode}, error::ParseError, pssedata::PSSEDatatype, record::{Record, SectionContext}};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -72,6 +72,10 @@ pub struct Transformer {
     /// ### Type: String
     /// ### Default: " "
     pub name: String,
+    /// ## DESC: Transformer description, from V36
+    /// ### Type: String
+    /// ### Default: ""
+    pub description: String,
     /// ## ST: Transformer Status
     /// ### Type: i8
     /// ### Default: 1
@@ -301,6 +305,10 @@ pub struct Transformer {
     /// ## Range
     /// * 2 - 9999
     pub tap_positions_1: i32,
+    /// ## TAPSTEP1: Tap step of winding 1, from V36
+    /// ### Type: f64
+    /// ### Default: 0.0
+    pub tap_step_1: f64,
     /// ## TAB1: The impedance table correction number to use for winding 1
     /// ### Type: i32
     /// ### Default: 0
@@ -442,6 +450,10 @@ pub struct Transformer {
     /// ## Range
     /// * 2 - 9999
     pub tap_positions_2: i32,
+    /// ## TAPSTEP2: Tap step of winding 2, from V36
+    /// ### Type: f64
+    /// ### Default: 0.0
+    pub tap_step_2: f64,
     /// ## TAB2: The impedance table correction number to use for winding 2
     /// ### Type: i32
     /// ### Default: 0
@@ -583,6 +595,10 @@ pub struct Transformer {
     /// ## Range
     /// * 2 - 9999
     pub tap_positions_3: i32,
+    /// ## TAPSTEP3: Tap step of winding 3, from V36
+    /// ### Type: f64
+    /// ### Default: 0.0
+    pub tap_step_3: f64,
     /// ## TAB3: The impedance table correction number to use for winding 3
     /// ### Type: i32
     /// ### Default: 0
@@ -607,70 +623,93 @@ pub struct Transformer {
     pub connection_ang_3: f64,
 }
 
-pub fn parse_transformers(lines: &[&[u8]], psse_version: i8) -> Vec<Transformer> {
+impl Transformer {
+    /// Units of the turns ratios given by the CW code
+    pub fn winding_io_code(&self) -> WindingIoCode {
+        WindingIoCode::from(self.cw)
+    }
+
+    /// Units of the winding impedances given by the CZ code
+    pub fn impedance_io_code(&self) -> ImpedanceIoCode {
+        ImpedanceIoCode::from(self.cz)
+    }
+
+    /// Units of MAG1 and MAG2 given by the CM code
+    pub fn magnetizing_io_code(&self) -> MagnetizingIoCode {
+        MagnetizingIoCode::from(self.cm)
+    }
+
+    /// Status of the STAT code
+    pub fn transformer_status(&self) -> TransformerStatus {
+        TransformerStatus::from(self.status)
+    }
+
+    /// Tap control modes of the COD1, COD2 and COD3 codes, winding 3 is [`TapControlMode::Fixed`] on two-winding transformers
+    pub fn tap_control_modes(&self) -> [TapControlMode; 3] {
+        [self.control_mode_1, self.control_mode_2, self.control_mode_3].map(TapControlMode::from)
+    }
+}
+
+pub fn parse_transformers(lines: &[&[u8]], ctx: &SectionContext, psse_version: i8) -> Result<Vec<Transformer>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
+    if lines.is_empty() {return Ok(Vec::new());}
     let mut transformers: Vec<Transformer> = Vec::new();
     let mut i = 0;
     while i < lines.len() {
-        if let (Ok(line1_str), Ok(line2_str), Ok(line3_str), Ok(line4_str)) = (
-            from_utf8(lines[i]), from_utf8(lines[i + 1]), from_utf8(lines[i + 2]), from_utf8(lines[i + 3])
-        ) {
-            let parts1: Vec<&str> = line1_str.split(',').map(|s| s.trim()).collect();
-            let two_winding: bool = parts1[2].parse::<i32>().unwrap_or(0) == 0;
-            if two_winding {
-                // Two winding transformer (4 lines)
-                let two_wnd_xfmr: Transformer = parse_two_winding_transformer(parts1, line2_str, line3_str, line4_str, psse_version);
-                transformers.push(two_wnd_xfmr);
-                i += 4;
-            } else {
-                // Three winding transformer (5 lines)
-                let line5_str = from_utf8(lines[i + 4]).unwrap();
-                let three_wnd_xfmr: Transformer = parse_three_winding_transformer(parts1, line2_str, line3_str, line4_str, line5_str, psse_version);
-                transformers.push(three_wnd_xfmr);
-                i += 5;
-            }
-        }        
+        let rec1: Record = ctx.record(lines[i], i, PSSEDatatype::Transformer)?;
+        let two_winding: bool = rec1.required::<i32>(2, "K", 0)? == 0;
+        if two_winding {
+            // Two winding transformer (4 lines)
+            let [rec1, rec2, rec3, rec4] = ctx.records(lines, i, PSSEDatatype::Transformer)?;
+            let two_wnd_xfmr: Transformer = parse_two_winding_transformer(&rec1, &rec2, &rec3, &rec4, psse_version)?;
+            transformers.push(two_wnd_xfmr);
+            i += 4;
+        } else {
+            // Three winding transformer (5 lines)
+            let [rec1, rec2, rec3, rec4, rec5] = ctx.records(lines, i, PSSEDatatype::Transformer)?;
+            let three_wnd_xfmr: Transformer = parse_three_winding_transformer(&rec1, &rec2, &rec3, &rec4, &rec5, psse_version)?;
+            transformers.push(three_wnd_xfmr);
+            i += 5;
+        }
     }
-    transformers
+    Ok(transformers)
 }
 
-fn parse_two_winding_transformer(parts1: Vec<&str>, line2_str: &str, line3_str: &str, line4_str: &str, psse_version: i8) -> Transformer {
-    // let parts1: Vec<&str> = line1_str.split(',').map(|s| s.trim()).collect();
-    let parts2: Vec<&str> = line2_str.split(',').map(|s| s.trim()).collect();
-    let parts3: Vec<&str> = line3_str.split(',').map(|s| s.trim()).collect();
-    let parts4: Vec<&str> = line4_str.split(',').map(|s| s.trim()).collect();
-
+fn parse_two_winding_transformer(rec1: &Record, rec2: &Record, rec3: &Record, rec4: &Record, psse_version: i8) -> Result<Transformer, ParseError> {
     let parse_adder: usize = ((psse_version >= 34) as usize) * 9;
-    Transformer {
-        from_bus: parts1[0].parse().unwrap_or(0),
-        to_bus: parts1[1].parse().unwrap_or(0),
+    // Version 34 also added NODEk after CONTk
+    let node_adder: usize = (psse_version >= 34) as usize;
+    Ok(Transformer {
+        from_bus: rec1.required(0, "I", 0)?,
+        to_bus: rec1.required(1, "J", 0)?,
         tertiary_bus: 0,
-        circuit: parts1[3].replace("'", "").trim().to_string(),
-        cw: parts1[4].parse().unwrap_or(1),
-        cz: parts1[5].parse().unwrap_or(1),
-        cm: parts1[6].parse().unwrap_or(1),
-        mag1: parts1[7].parse().unwrap_or(0.0),
-        mag2: parts1[8].parse().unwrap_or(0.0),
-        metered_end: parts1[9].parse().unwrap_or(1),
-        name: parts1[10].replace("'", "").trim().to_string(),
-        status: parts1[11].parse().unwrap_or(1),
-        owner1: parts1[12].parse().unwrap_or(1),
-        owner1_percent: parts1[13].parse().unwrap_or(1.0),
-        owner2: parts1[14].parse().unwrap_or(0),
-        owner2_percent: parts1[15].parse().unwrap_or(1.0),
-        owner3: parts1[16].parse().unwrap_or(0),
-        owner3_percent: parts1[17].parse().unwrap_or(1.0),
-        owner4: parts1[18].parse().unwrap_or(0),
-        owner4_percent: parts1[19].parse().unwrap_or(1.0),
-        vector_group: parts1.get(20).map_or("", |v| v).replace("'", "").trim().to_string(),
+        circuit: rec1.required_str(3, "CKT")?,
+        cw: rec1.required(4, "CW", 1)?,
+        cz: rec1.required(5, "CZ", 1)?,
+        cm: rec1.required(6, "CM", 1)?,
+        mag1: rec1.required(7, "MAG1", 0.0)?,
+        mag2: rec1.required(8, "MAG2", 0.0)?,
+        metered_end: rec1.required(9, "NMETER", 1)?,
+        name: rec1.required_str(10, "NAME")?,
+        // V36 inserted DESC after NAME and TAPSTEPk after NTPk
+        description: if psse_version >= 36 { rec1.optional_str(22, "DESC", "")? } else { String::new() },
+        status: rec1.required(11, "ST", 1)?,
+        owner1: rec1.required(12, "O1", 1)?,
+        owner1_percent: rec1.required(13, "F1", 1.0)?,
+        owner2: rec1.required(14, "O2", 0)?,
+        owner2_percent: rec1.required(15, "F2", 1.0)?,
+        owner3: rec1.required(16, "O3", 0)?,
+        owner3_percent: rec1.required(17, "F3", 1.0)?,
+        owner4: rec1.required(18, "O4", 0)?,
+        owner4_percent: rec1.required(19, "F4", 1.0)?,
+        vector_group: rec1.optional_str(20, "VECGRP", "")?,
         zcod: 0,
 
         // Line 2
 
-        r12: parts2[0].parse().unwrap_or(0.0),
-        x12: parts2[1].parse().unwrap_or(0.0),
-        sbase12: parts2[2].parse().unwrap_or(100.0),
+        r12: rec2.required(0, "R12", 0.0)?,
+        x12: rec2.required(1, "X12", 0.0)?,
+        sbase12: rec2.required(2, "SBASE12", 100.0)?,
         r23: 0.0,
         x23: 0.0,
         sbase23: 0.0,
@@ -682,37 +721,38 @@ fn parse_two_winding_transformer(parts1: Vec<&str>, line2_str: &str, line3_str:
 
         // Line 3
 
-        winding_1_volt: parts3[0].parse().unwrap_or(1.0),
-        nominal_volt1: parts3[1].parse().unwrap_or(0.0),
-        angle1: parts3[2].parse().unwrap_or(0.0),
-        w1_rate1: parts3[3].parse().unwrap_or(0.0),
-        w1_rate2: parts3[4].parse().unwrap_or(0.0),
-        w1_rate3: parts3[5].parse().unwrap_or(0.0),
-        w1_rate4: (psse_version >= 34).then_some(parts3[6].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate5: (psse_version >= 34).then_some(parts3[7].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate6: (psse_version >= 34).then_some(parts3[8].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate7: (psse_version >= 34).then_some(parts3[9].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate8: (psse_version >= 34).then_some(parts3[10].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate9: (psse_version >= 34).then_some(parts3[11].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate10: (psse_version >= 34).then_some(parts3[12].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate11: (psse_version >= 34).then_some(parts3[13].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate12: (psse_version >= 34).then_some(parts3[14].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        control_mode_1: parts3[6 + parse_adder].parse().unwrap_or(0),
-        controlled_bus_id_1: parts3[7 + parse_adder].parse().unwrap_or(0),
-        rma1: parts3[8 + parse_adder].parse().unwrap_or(1.1),
-        rmi1: parts3[9 + parse_adder].parse().unwrap_or(0.9),
-        vma1: parts3[10 + parse_adder].parse().unwrap_or(1.1),
-        vmi1: parts3[11 + parse_adder].parse().unwrap_or(0.9),
-        tap_positions_1: parts3[12 + parse_adder].parse().unwrap_or(33),
-        impedance_correction_table_1: parts3[13 + parse_adder].parse().unwrap_or(0),
-        load_drop_comp_r1: parts3[14 + parse_adder].parse().unwrap_or(0.0),
-        load_drop_comp_x1: parts3[15 + parse_adder].parse().unwrap_or(0.0),
-        connection_ang_1: parts3.get(16 + parse_adder).and_then(|s| s.parse().ok()).unwrap_or(0.0),
+        winding_1_volt: rec3.required(0, "WINDV1", 1.0)?,
+        nominal_volt1: rec3.required(1, "NOMV1", 0.0)?,
+        angle1: rec3.required(2, "ANG1", 0.0)?,
+        w1_rate1: rec3.required(3, "RATE1", 0.0)?,
+        w1_rate2: rec3.required(4, "RATE2", 0.0)?,
+        w1_rate3: rec3.required(5, "RATE3", 0.0)?,
+        w1_rate4: if psse_version >= 34 { rec3.required(6, "RATE4", 0.0)? } else { 0.0 },
+        w1_rate5: if psse_version >= 34 { rec3.required(7, "RATE5", 0.0)? } else { 0.0 },
+        w1_rate6: if psse_version >= 34 { rec3.required(8, "RATE6", 0.0)? } else { 0.0 },
+        w1_rate7: if psse_version >= 34 { rec3.required(9, "RATE7", 0.0)? } else { 0.0 },
+        w1_rate8: if psse_version >= 34 { rec3.required(10, "RATE8", 0.0)? } else { 0.0 },
+        w1_rate9: if psse_version >= 34 { rec3.required(11, "RATE9", 0.0)? } else { 0.0 },
+        w1_rate10: if psse_version >= 34 { rec3.required(12, "RATE10", 0.0)? } else { 0.0 },
+        w1_rate11: if psse_version >= 34 { rec3.required(13, "RATE11", 0.0)? } else { 0.0 },
+        w1_rate12: if psse_version >= 34 { rec3.required(14, "RATE12", 0.0)? } else { 0.0 },
+        control_mode_1: rec3.required(6 + parse_adder, "COD1", 0)?,
+        controlled_bus_id_1: rec3.required(7 + parse_adder, "CONT1", 0)?,
+        rma1: rec3.required(8 + parse_adder + node_adder, "RMA1", 1.1)?,
+        rmi1: rec3.required(9 + parse_adder + node_adder, "RMI1", 0.9)?,
+        vma1: rec3.required(10 + parse_adder + node_adder, "VMA1", 1.1)?,
+        vmi1: rec3.required(11 + parse_adder + node_adder, "VMI1", 0.9)?,
+        tap_positions_1: rec3.required(12 + parse_adder + node_adder, "NTP1", 33)?,
+        tap_step_1: if psse_version >= 36 { rec3.optional(27, "TAPSTEP1", 0.0)? } else { 0.0 },
+        impedance_correction_table_1: rec3.required(13 + parse_adder + node_adder, "TAB1", 0)?,
+        load_drop_comp_r1: rec3.required(14 + parse_adder + node_adder, "CR1", 0.0)?,
+        load_drop_comp_x1: rec3.required(15 + parse_adder + node_adder, "CX1", 0.0)?,
+        connection_ang_1: rec3.optional(16 + parse_adder + node_adder, "CNXA1", 0.0)?,
 
         // Line 4
 
-        winding_2_volt: parts4[0].parse().unwrap_or(1.0),
-        nominal_volt2: parts4[0].parse().unwrap_or(1.0),
+        winding_2_volt: rec4.required(0, "WINDV2", 1.0)?,
+        nominal_volt2: rec4.optional(1, "NOMV2", 0.0)?,
         angle2: 0.0,
         w2_rate1: 0.0,
         w2_rate2: 0.0,
@@ -733,6 +773,7 @@ fn parse_two_winding_transformer(parts1: Vec<&str>, line2_str: &str, line3_str:
         vma2: 0.0,
         vmi2: 0.0,
         tap_positions_2: 0,
+        tap_step_2: 0.0,
         impedance_correction_table_2: 0,
         load_drop_comp_r2: 0.0,
         load_drop_comp_x2: 0.0,
@@ -762,147 +803,149 @@ fn parse_two_winding_transformer(parts1: Vec<&str>, line2_str: &str, line3_str:
         vma3: 0.0,
         vmi3: 0.0,
         tap_positions_3: 0,
+        tap_step_3: 0.0,
         impedance_correction_table_3: 0,
         load_drop_comp_r3: 0.0,
         load_drop_comp_x3: 0.0,
         connection_ang_3: 0.0,
-    }
+    })
 }
 
-fn parse_three_winding_transformer(parts1: Vec<&str>, line2_str: &str, line3_str: &str, line4_str: &str, line5_str: &str, psse_version: i8) -> Transformer {
-    // let parts1: Vec<&str> = line1_str.split(',').map(|s| s.trim()).collect();
-    let parts2: Vec<&str> = line2_str.split(',').map(|s| s.trim()).collect();
-    let parts3: Vec<&str> = line3_str.split(',').map(|s| s.trim()).collect();
-    let parts4: Vec<&str> = line4_str.split(',').map(|s| s.trim()).collect();
-    let parts5: Vec<&str> = line5_str.split(',').map(|s| s.trim()).collect();
-
+fn parse_three_winding_transformer(rec1: &Record, rec2: &Record, rec3: &Record, rec4: &Record, rec5: &Record, psse_version: i8) -> Result<Transformer, ParseError> {
     let parse_adder: usize = ((psse_version >= 34) as usize) * 9;
+    // Version 34 also added NODEk after CONTk
+    let node_adder: usize = (psse_version >= 34) as usize;
 
-    Transformer {
-        from_bus: parts1[0].parse().unwrap_or(0),
-        to_bus: parts1[1].parse().unwrap_or(0),
-        tertiary_bus: parts1[2].parse().unwrap_or(0),
-        circuit: parts1[3].replace("'", "").trim().to_string(),
-        cw: parts1[4].parse().unwrap_or(1),
-        cz: parts1[5].parse().unwrap_or(1),
-        cm: parts1[6].parse().unwrap_or(1),
-        mag1: parts1[7].parse().unwrap_or(0.0),
-        mag2: parts1[8].parse().unwrap_or(0.0),
-        metered_end: parts1[9].parse().unwrap_or(1),
-        name: parts1[10].replace("'", "").trim().to_string(),
-        status: parts1[11].parse().unwrap_or(1),
-        owner1: parts1[12].parse().unwrap_or(1),
-        owner1_percent: parts1[13].parse().unwrap_or(1.0),
-        owner2: parts1[14].parse().unwrap_or(0),
-        owner2_percent: parts1[15].parse().unwrap_or(1.0),
-        owner3: parts1[16].parse().unwrap_or(0),
-        owner3_percent: parts1[17].parse().unwrap_or(1.0),
-        owner4: parts1[18].parse().unwrap_or(0),
-        owner4_percent: parts1[19].parse().unwrap_or(1.0),
-        vector_group: parts1[20].replace("'", "").trim().to_string(),
-        zcod: parts1.get(21).and_then(|s| s.parse().ok()).unwrap_or(0),
+    Ok(Transformer {
+        from_bus: rec1.required(0, "I", 0)?,
+        to_bus: rec1.required(1, "J", 0)?,
+        tertiary_bus: rec1.required(2, "K", 0)?,
+        circuit: rec1.required_str(3, "CKT")?,
+        cw: rec1.required(4, "CW", 1)?,
+        cz: rec1.required(5, "CZ", 1)?,
+        cm: rec1.required(6, "CM", 1)?,
+        mag1: rec1.required(7, "MAG1", 0.0)?,
+        mag2: rec1.required(8, "MAG2", 0.0)?,
+        metered_end: rec1.required(9, "NMETER", 1)?,
+        name: rec1.required_str(10, "NAME")?,
+        description: if psse_version >= 36 { rec1.optional_str(22, "DESC", "")? } else { String::new() },
+        status: rec1.required(11, "ST", 1)?,
+        owner1: rec1.required(12, "O1", 1)?,
+        owner1_percent: rec1.required(13, "F1", 1.0)?,
+        owner2: rec1.required(14, "O2", 0)?,
+        owner2_percent: rec1.required(15, "F2", 1.0)?,
+        owner3: rec1.required(16, "O3", 0)?,
+        owner3_percent: rec1.required(17, "F3", 1.0)?,
+        owner4: rec1.required(18, "O4", 0)?,
+        owner4_percent: rec1.required(19, "F4", 1.0)?,
+        // VECGRP and the winding connection angles were added in V33
+        vector_group: if psse_version >= 33 { rec1.required_str(20, "VECGRP")? } else { String::new() },
+        zcod: rec1.optional(21, "ZCOD", 0)?,
         
         // Line 2
 
-        r12: parts2[0].parse().unwrap_or(0.0),
-        x12: parts2[1].parse().unwrap_or(0.0),
-        sbase12: parts2[2].parse().unwrap_or(100.0),
-        r23: parts2[3].parse().unwrap_or(0.0),
-        x23: parts2[4].parse().unwrap_or(0.0),
-        sbase23: parts2[5].parse().unwrap_or(100.0),
-        r31: parts2[6].parse().unwrap_or(0.0),
-        x31: parts2[7].parse().unwrap_or(0.0),
-        sbase31: parts2[8].parse().unwrap_or(100.0),
-        star_vm: parts2[9].parse().unwrap_or(1.0),
-        star_ang: parts2[10].parse().unwrap_or(0.0),
+        r12: rec2.required(0, "R12", 0.0)?,
+        x12: rec2.required(1, "X12", 0.0)?,
+        sbase12: rec2.required(2, "SBASE12", 100.0)?,
+        r23: rec2.required(3, "R23", 0.0)?,
+        x23: rec2.required(4, "X23", 0.0)?,
+        sbase23: rec2.required(5, "SBASE23", 100.0)?,
+        r31: rec2.required(6, "R31", 0.0)?,
+        x31: rec2.required(7, "X31", 0.0)?,
+        sbase31: rec2.required(8, "SBASE31", 100.0)?,
+        star_vm: rec2.required(9, "VMSTAR", 1.0)?,
+        star_ang: rec2.required(10, "ANSTAR", 0.0)?,
 
         // Line 3
 
-        winding_1_volt: parts3[0].parse().unwrap_or(1.0),
-        nominal_volt1: parts3[1].parse().unwrap_or(0.0),
-        angle1: parts3[2].parse().unwrap_or(0.0),
-        w1_rate1: parts3[3].parse().unwrap_or(0.0),
-        w1_rate2: parts3[4].parse().unwrap_or(0.0),
-        w1_rate3: parts3[5].parse().unwrap_or(0.0),
-        w1_rate4: (psse_version >= 34).then_some(parts3[6].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate5: (psse_version >= 34).then_some(parts3[7].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate6: (psse_version >= 34).then_some(parts3[8].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate7: (psse_version >= 34).then_some(parts3[9].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate8: (psse_version >= 34).then_some(parts3[10].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate9: (psse_version >= 34).then_some(parts3[11].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate10: (psse_version >= 34).then_some(parts3[12].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate11: (psse_version >= 34).then_some(parts3[13].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w1_rate12: (psse_version >= 34).then_some(parts3[14].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        control_mode_1: parts3[6 + parse_adder].parse().unwrap_or(0),
-        controlled_bus_id_1: parts3[7 + parse_adder].parse().unwrap_or(0),
-        rma1: parts3[8 + parse_adder].parse().unwrap_or(1.1),
-        rmi1: parts3[9 + parse_adder].parse().unwrap_or(0.9),
-        vma1: parts3[10 + parse_adder].parse().unwrap_or(1.1),
-        vmi1: parts3[11 + parse_adder].parse().unwrap_or(0.9),
-        tap_positions_1: parts3[12 + parse_adder].parse().unwrap_or(33),
-        impedance_correction_table_1: parts3[13 + parse_adder].parse().unwrap_or(0),
-        load_drop_comp_r1: parts3[14 + parse_adder].parse().unwrap_or(0.0),
-        load_drop_comp_x1: parts3[15 + parse_adder].parse().unwrap_or(0.0),
-        connection_ang_1: parts3[16 + parse_adder].parse().unwrap_or(0.0),
+        winding_1_volt: rec3.required(0, "WINDV1", 1.0)?,
+        nominal_volt1: rec3.required(1, "NOMV1", 0.0)?,
+        angle1: rec3.required(2, "ANG1", 0.0)?,
+        w1_rate1: rec3.required(3, "RATE1", 0.0)?,
+        w1_rate2: rec3.required(4, "RATE2", 0.0)?,
+        w1_rate3: rec3.required(5, "RATE3", 0.0)?,
+        w1_rate4: if psse_version >= 34 { rec3.required(6, "RATE4", 0.0)? } else { 0.0 },
+        w1_rate5: if psse_version >= 34 { rec3.required(7, "RATE5", 0.0)? } else { 0.0 },
+        w1_rate6: if psse_version >= 34 { rec3.required(8, "RATE6", 0.0)? } else { 0.0 },
+        w1_rate7: if psse_version >= 34 { rec3.required(9, "RATE7", 0.0)? } else { 0.0 },
+        w1_rate8: if psse_version >= 34 { rec3.required(10, "RATE8", 0.0)? } else { 0.0 },
+        w1_rate9: if psse_version >= 34 { rec3.required(11, "RATE9", 0.0)? } else { 0.0 },
+        w1_rate10: if psse_version >= 34 { rec3.required(12, "RATE10", 0.0)? } else { 0.0 },
+        w1_rate11: if psse_version >= 34 { rec3.required(13, "RATE11", 0.0)? } else { 0.0 },
+        w1_rate12: if psse_version >= 34 { rec3.required(14, "RATE12", 0.0)? } else { 0.0 },
+        control_mode_1: rec3.required(6 + parse_adder, "COD1", 0)?,
+        controlled_bus_id_1: rec3.required(7 + parse_adder, "CONT1", 0)?,
+        rma1: rec3.required(8 + parse_adder + node_adder, "RMA1", 1.1)?,
+        rmi1: rec3.required(9 + parse_adder + node_adder, "RMI1", 0.9)?,
+        vma1: rec3.required(10 + parse_adder + node_adder, "VMA1", 1.1)?,
+        vmi1: rec3.required(11 + parse_adder + node_adder, "VMI1", 0.9)?,
+        tap_positions_1: rec3.required(12 + parse_adder + node_adder, "NTP1", 33)?,
+        tap_step_1: if psse_version >= 36 { rec3.optional(27, "TAPSTEP1", 0.0)? } else { 0.0 },
+        impedance_correction_table_1: rec3.required(13 + parse_adder + node_adder, "TAB1", 0)?,
+        load_drop_comp_r1: rec3.required(14 + parse_adder + node_adder, "CR1", 0.0)?,
+        load_drop_comp_x1: rec3.required(15 + parse_adder + node_adder, "CX1", 0.0)?,
+        connection_ang_1: if psse_version >= 33 { rec3.required(16 + parse_adder + node_adder, "CNXA1", 0.0)? } else { 0.0 },
 
         // Line 4
 
-        winding_2_volt: parts4[0].parse().unwrap_or(1.0),
-        nominal_volt2: parts4[1].parse().unwrap_or(0.0),
-        angle2: parts4[2].parse().unwrap_or(0.0),
-        w2_rate1: parts4[3].parse().unwrap_or(0.0),
-        w2_rate2: parts4[4].parse().unwrap_or(0.0),
-        w2_rate3: parts4[5].parse().unwrap_or(0.0),
-        w2_rate4: (psse_version >= 34).then_some(parts4[6].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w2_rate5: (psse_version >= 34).then_some(parts4[7].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w2_rate6: (psse_version >= 34).then_some(parts4[8].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w2_rate7: (psse_version >= 34).then_some(parts4[9].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w2_rate8: (psse_version >= 34).then_some(parts4[10].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w2_rate9: (psse_version >= 34).then_some(parts4[11].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w2_rate10: (psse_version >= 34).then_some(parts4[12].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w2_rate11: (psse_version >= 34).then_some(parts4[13].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w2_rate12: (psse_version >= 34).then_some(parts4[14].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        control_mode_2: parts4[6 + parse_adder].parse().unwrap_or(0),
-        controlled_bus_id_2: parts4[7 + parse_adder].parse().unwrap_or(0),
-        rma2: parts4[8 + parse_adder].parse().unwrap_or(1.1),
-        rmi2: parts4[9 + parse_adder].parse().unwrap_or(0.9),
-        vma2: parts4[10 + parse_adder].parse().unwrap_or(1.1),
-        vmi2: parts4[11 + parse_adder].parse().unwrap_or(0.9),
-        tap_positions_2: parts4[12 + parse_adder].parse().unwrap_or(33),
-        impedance_correction_table_2: parts4[13 + parse_adder].parse().unwrap_or(0),
-        load_drop_comp_r2: parts4[14 + parse_adder].parse().unwrap_or(0.0),
-        load_drop_comp_x2: parts4[15 + parse_adder].parse().unwrap_or(0.0),
-        connection_ang_2: parts4[16 + parse_adder].parse().unwrap_or(0.0),
+        winding_2_volt: rec4.required(0, "WINDV2", 1.0)?,
+        nominal_volt2: rec4.required(1, "NOMV2", 0.0)?,
+        angle2: rec4.required(2, "ANG2", 0.0)?,
+        w2_rate1: rec4.required(3, "RATE1", 0.0)?,
+        w2_rate2: rec4.required(4, "RATE2", 0.0)?,
+        w2_rate3: rec4.required(5, "RATE3", 0.0)?,
+        w2_rate4: if psse_version >= 34 { rec4.required(6, "RATE4", 0.0)? } else { 0.0 },
+        w2_rate5: if psse_version >= 34 { rec4.required(7, "RATE5", 0.0)? } else { 0.0 },
+        w2_rate6: if psse_version >= 34 { rec4.required(8, "RATE6", 0.0)? } else { 0.0 },
+        w2_rate7: if psse_version >= 34 { rec4.required(9, "RATE7", 0.0)? } else { 0.0 },
+        w2_rate8: if psse_version >= 34 { rec4.required(10, "RATE8", 0.0)? } else { 0.0 },
+        w2_rate9: if psse_version >= 34 { rec4.required(11, "RATE9", 0.0)? } else { 0.0 },
+        w2_rate10: if psse_version >= 34 { rec4.required(12, "RATE10", 0.0)? } else { 0.0 },
+        w2_rate11: if psse_version >= 34 { rec4.required(13, "RATE11", 0.0)? } else { 0.0 },
+        w2_rate12: if psse_version >= 34 { rec4.required(14, "RATE12", 0.0)? } else { 0.0 },
+        control_mode_2: rec4.required(6 + parse_adder, "COD2", 0)?,
+        controlled_bus_id_2: rec4.required(7 + parse_adder, "CONT2", 0)?,
+        rma2: rec4.required(8 + parse_adder + node_adder, "RMA2", 1.1)?,
+        rmi2: rec4.required(9 + parse_adder + node_adder, "RMI2", 0.9)?,
+        vma2: rec4.required(10 + parse_adder + node_adder, "VMA2", 1.1)?,
+        vmi2: rec4.required(11 + parse_adder + node_adder, "VMI2", 0.9)?,
+        tap_positions_2: rec4.required(12 + parse_adder + node_adder, "NTP2", 33)?,
+        tap_step_2: if psse_version >= 36 { rec4.optional(27, "TAPSTEP2", 0.0)? } else { 0.0 },
+        impedance_correction_table_2: rec4.required(13 + parse_adder + node_adder, "TAB2", 0)?,
+        load_drop_comp_r2: rec4.required(14 + parse_adder + node_adder, "CR2", 0.0)?,
+        load_drop_comp_x2: rec4.required(15 + parse_adder + node_adder, "CX2", 0.0)?,
+        connection_ang_2: if psse_version >= 33 { rec4.required(16 + parse_adder + node_adder, "CNXA2", 0.0)? } else { 0.0 },
 
         // Line 5
         
-        winding_3_volt: parts5[0].parse().unwrap_or(1.0),
-        nominal_volt3: parts5[1].parse().unwrap_or(0.0),
-        angle3: parts5[2].parse().unwrap_or(0.0),
-        w3_rate1: parts5[3].parse().unwrap_or(0.0),
-        w3_rate2: parts5[4].parse().unwrap_or(0.0),
-        w3_rate3: parts5[5].parse().unwrap_or(0.0),
-        w3_rate4: (psse_version >= 34).then_some(parts5[6].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w3_rate5: (psse_version >= 34).then_some(parts5[7].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w3_rate6: (psse_version >= 34).then_some(parts5[8].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w3_rate7: (psse_version >= 34).then_some(parts5[9].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w3_rate8: (psse_version >= 34).then_some(parts5[10].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w3_rate9: (psse_version >= 34).then_some(parts5[11].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w3_rate10: (psse_version >= 34).then_some(parts5[12].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w3_rate11: (psse_version >= 34).then_some(parts5[13].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        w3_rate12: (psse_version >= 34).then_some(parts5[14].parse().unwrap_or(0.0)).unwrap_or(0.0),
-        control_mode_3: parts5[6 + parse_adder].parse().unwrap_or(0),
-        controlled_bus_id_3: parts5[7 + parse_adder].parse().unwrap_or(0),
-        rma3: parts5[8 + parse_adder].parse().unwrap_or(1.1),
-        rmi3: parts5[9 + parse_adder].parse().unwrap_or(0.9),
-        vma3: parts5[10 + parse_adder].parse().unwrap_or(1.1),
-        vmi3: parts5[11 + parse_adder].parse().unwrap_or(0.9),
-        tap_positions_3: parts5[12 + parse_adder].parse().unwrap_or(33),
-        impedance_correction_table_3: parts5[13 + parse_adder].parse().unwrap_or(0),
-        load_drop_comp_r3: parts5[14 + parse_adder].parse().unwrap_or(0.0),
-        load_drop_comp_x3: parts5[15 + parse_adder].parse().unwrap_or(0.0),
-        connection_ang_3: parts5[16 + parse_adder].parse().unwrap_or(0.0),
-    }
+        winding_3_volt: rec5.required(0, "WINDV3", 1.0)?,
+        nominal_volt3: rec5.required(1, "NOMV3", 0.0)?,
+        angle3: rec5.required(2, "ANG3", 0.0)?,
+        w3_rate1: rec5.required(3, "RATE1", 0.0)?,
+        w3_rate2: rec5.required(4, "RATE2", 0.0)?,
+        w3_rate3: rec5.required(5, "RATE3", 0.0)?,
+        w3_rate4: if psse_version >= 34 { rec5.required(6, "RATE4", 0.0)? } else { 0.0 },
+        w3_rate5: if psse_version >= 34 { rec5.required(7, "RATE5", 0.0)? } else { 0.0 },
+        w3_rate6: if psse_version >= 34 { rec5.required(8, "RATE6", 0.0)? } else { 0.0 },
+        w3_rate7: if psse_version >= 34 { rec5.required(9, "RATE7", 0.0)? } else { 0.0 },
+        w3_rate8: if psse_version >= 34 { rec5.required(10, "RATE8", 0.0)? } else { 0.0 },
+        w3_rate9: if psse_version >= 34 { rec5.required(11, "RATE9", 0.0)? } else { 0.0 },
+        w3_rate10: if psse_version >= 34 { rec5.required(12, "RATE10", 0.0)? } else { 0.0 },
+        w3_rate11: if psse_version >= 34 { rec5.required(13, "RATE11", 0.0)? } else { 0.0 },
+        w3_rate12: if psse_version >= 34 { rec5.required(14, "RATE12", 0.0)? } else { 0.0 },
+        control_mode_3: rec5.required(6 + parse_adder, "COD3", 0)?,
+        controlled_bus_id_3: rec5.required(7 + parse_adder, "CONT3", 0)?,
+        rma3: rec5.required(8 + parse_adder + node_adder, "RMA3", 1.1)?,
+        rmi3: rec5.required(9 + parse_adder + node_adder, "RMI3", 0.9)?,
+        vma3: rec5.required(10 + parse_adder + node_adder, "VMA3", 1.1)?,
+        vmi3: rec5.required(11 + parse_adder + node_adder, "VMI3", 0.9)?,
+        tap_positions_3: rec5.required(12 + parse_adder + node_adder, "NTP3", 33)?,
+        tap_step_3: if psse_version >= 36 { rec5.optional(27, "TAPSTEP3", 0.0)? } else { 0.0 },
+        impedance_correction_table_3: rec5.required(13 + parse_adder + node_adder, "TAB3", 0)?,
+        load_drop_comp_r3: rec5.required(14 + parse_adder + node_adder, "CR3", 0.0)?,
+        load_drop_comp_x3: rec5.required(15 + parse_adder + node_adder, "CX3", 0.0)?,
+        connection_ang_3: if psse_version >= 33 { rec5.required(16 + parse_adder + node_adder, "CNXA3", 0.0)? } else { 0.0 },
+    })
 }
 
 #[cfg(test)]
@@ -917,8 +960,8 @@ mod tests {
     1.00000,    0.000,    0.000,   288.00,   288.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,  0,        0,   0,    1.10000,    0.90000,    1.10000,    0.90000,   33,    0,    0.00000,    0.00000,      0.000
     1.00000,    0.000,  -30.000,    10.00,    10.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,  0,        0,   0,    1.10000,    0.90000,    1.10000,    0.90000,   33,    0,    0.00000,    0.00000,      0.000";
         let lines: Vec<&[u8]> = three_winding_v35_str.lines().map(|line| line.as_bytes()).collect();
-        let binding = parse_transformers(&lines, 35);
-        let three_winding_v35: Option<&Transformer> = binding.get(0);
+        let binding = parse_transformers(&lines, &SectionContext::default(), 35).unwrap();
+        let three_winding_v35: Option<&Transformer> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(three_winding_v35.unwrap().from_bus, 1234);
@@ -926,6 +969,7 @@ mod tests {
         assert_eq!(three_winding_v35.unwrap().tertiary_bus, 91011);
         assert_eq!(three_winding_v35.unwrap().circuit, "2".to_string());
         assert_eq!(three_winding_v35.unwrap().control_mode_1, 1);
+        assert_eq!(three_winding_v35.unwrap().tap_control_modes(), [TapControlMode::Voltage { enabled: true }, TapControlMode::Fixed, TapControlMode::Fixed]);
         assert_eq!(three_winding_v35.unwrap().r12, 0.00109);
         assert_eq!(three_winding_v35.unwrap().r23, 0.02112);
         assert_eq!(three_winding_v35.unwrap().r31, 0.00898);
@@ -941,8 +985,8 @@ mod tests {
     0.98040,    0.000,    0.000,   420.00,   480.00,   530.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,     0.00,  1,        0,   0,    1.50000,    0.51000,    1.50000,    0.51000,  159,    0,    0.00000,    0.00000,      0.000
     1.00000,    0.000,";
         let lines: Vec<&[u8]> = two_winding_v35_str.lines().map(|line| line.as_bytes()).collect();
-        let binding = parse_transformers(&lines, 35);
-        let two_winding_v35: Option<&Transformer> = binding.get(0);
+        let binding = parse_transformers(&lines, &SectionContext::default(), 35).unwrap();
+        let two_winding_v35: Option<&Transformer> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(two_winding_v35.unwrap().from_bus, 1234);
@@ -962,12 +1006,12 @@ mod tests {
     fn parse_3_winding_v33() {
         let three_winding_v33_str: &'static str = "1234, 5678, 91011, '2 ', 1, 1, 1, 0.00000E+00, 0.00000E+00, 2, 'TESTXFMR                                ', 1,  190, 1.0000,    0, 1.0000,    0, 1.0000,    0, 1.0000, '                                        '
  1.09000E-03, 5.12700E-02,   100.00, 2.11200E-02, 2.05040E-01,   100.00, 0.89800E-02, 2.03950E-01,   100.00,   1.010001894, 105.342819992,
-    0.95967,    0.000,    0.000,   288.00,   288.00,     0.00,  1,        0,   0,    1.10000,    0.90000,    1.10000,    0.90000,   33,    0,    0.00000,    0.00000,      0.000
-    1.00000,    0.000,    0.000,   288.00,   288.00,     0.00,  0,        0,   0,    1.10000,    0.90000,    1.10000,    0.90000,   33,    0,    0.00000,    0.00000,      0.000
-    1.00000,    0.000,  -30.000,    10.00,    10.00,     0.00,  0,        0,   0,    1.10000,    0.90000,    1.10000,    0.90000,   33,    0,    0.00000,    0.00000,      0.000";
+    0.95967,    0.000,    0.000,   288.00,   288.00,     0.00,  1,        0,    1.10000,    0.90000,    1.10000,    0.90000,   33,    0,    0.00000,    0.00000,      0.000
+    1.00000,    0.000,    0.000,   288.00,   288.00,     0.00,  0,        0,    1.10000,    0.90000,    1.10000,    0.90000,   33,    0,    0.00000,    0.00000,      0.000
+    1.00000,    0.000,  -30.000,    10.00,    10.00,     0.00,  0,        0,    1.10000,    0.90000,    1.10000,    0.90000,   33,    0,    0.00000,    0.00000,      0.000";
         let lines: Vec<&[u8]> = three_winding_v33_str.lines().map(|line| line.as_bytes()).collect();
-        let binding = parse_transformers(&lines, 33);
-        let three_winding_v35: Option<&Transformer> = binding.get(0);
+        let binding = parse_transformers(&lines, &SectionContext::default(), 33).unwrap();
+        let three_winding_v35: Option<&Transformer> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(three_winding_v35.unwrap().from_bus, 1234);
@@ -987,11 +1031,11 @@ mod tests {
     fn parse_2_winding_v33() {
         let two_winding_v33_str: &'static str = "1234, 5678,      0, '1 ', 1, 1, 1, 0.00000E+00, 0.00000E+00, 2, 'TESTXFMR                             ', 1,    1, 1.0000,    0, 1.0000,    0, 1.0000,    0, 1.0000, '                                        '
  2.60000E-04, 2.36000E-02,   100.00,
-    0.98040,    0.000,    0.000,   420.00,   480.00,   530.00,  1,        0,   0,    1.50000,    0.51000,    1.50000,    0.51000,  159,    0,    0.00000,    0.00000,      0.000
+    0.98040,    0.000,    0.000,   420.00,   480.00,   530.00,  1,        0,    1.50000,    0.51000,    1.50000,    0.51000,  159,    0,    0.00000,    0.00000,      0.000
     1.00000,    0.000,";
         let lines: Vec<&[u8]> = two_winding_v33_str.lines().map(|line| line.as_bytes()).collect();
-        let binding = parse_transformers(&lines, 33);
-        let two_winding_v35: Option<&Transformer> = binding.get(0);
+        let binding = parse_transformers(&lines, &SectionContext::default(), 33).unwrap();
+        let two_winding_v35: Option<&Transformer> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(two_winding_v35.unwrap().from_bus, 1234);
@@ -1006,4 +1050,28 @@ mod tests {
         assert_eq!(two_winding_v35.unwrap().w2_rate1, 0.0);
         assert_eq!(two_winding_v35.unwrap().w3_rate1, 0.0);
     }
+
+    #[test]
+    fn winding_field_names() {
+        let record: &'static str = "1234, 5678, 91011, '2 ', 1, 1, 1, 0.00000E+00, 0.00000E+00, 2, 'TESTXFMR', 1,  190, 1.0000,    0, 1.0000,    0, 1.0000,    0, 1.0000, ''
+ 1.09000E-03, 5.12700E-02,   100.00, 2.11200E-02, 2.05040E-01,   100.00, 0.89800E-02, 2.03950E-01,   100.00,   1.010001894, 105.342819992,
+    0.95967,    0.000,    0.000,   288.00,   288.00,     0.00,  1,        0,    1.10000,    0.90000,    1.10000,    0.90000,   33,    0,    0.00000,    0.00000,      0.000
+    1.00000,    0.000,    0.000,   288.00,   288.00,     0.00,  0,        0,    1.10000,    0.90000,    1.10000,    0.90000,   33,    0,    0.00000,    0.00000,      0.000
+    1.00000,    0.000,  -30.000,    10.00,    10.00,     0.00,  0,        0,    1.10000,    0.90000,    1.10000,    0.90000,   33,    0,    0.00000,    0.00000,      0.000";
+        //Each winding line names its own fields, the first rating of every winding being RATE1
+        for (winding, line) in [(1, 2), (2, 3), (3, 4)] {
+            let mut record_lines: Vec<String> = record.lines().map(str::to_string).collect();
+            record_lines[line] = record_lines[line].replacen("288.00", "BAD", 1).replacen("10.00", "BAD", 1);
+            let lines: Vec<&[u8]> = record_lines.iter().map(|line| line.as_bytes()).collect();
+            let err: ParseError = parse_transformers(&lines, &SectionContext::default(), 33).unwrap_err();
+            assert!(matches!(err, ParseError::InvalidField { field: "RATE1", .. }), "winding {}: {}", winding, err);
+
+            let mut record_lines: Vec<String> = record.lines().map(str::to_string).collect();
+            record_lines[line] = record_lines[line].replacen("   33,", "  BAD,", 1);
+            let lines: Vec<&[u8]> = record_lines.iter().map(|line| line.as_bytes()).collect();
+            let err: ParseError = parse_transformers(&lines, &SectionContext::default(), 33).unwrap_err();
+            let expected: &str = ["NTP1", "NTP2", "NTP3"][winding - 1];
+            assert!(matches!(err, ParseError::InvalidField { field, .. } if field == expected), "winding {}: {}", winding, err);
+        }
+    }
 }
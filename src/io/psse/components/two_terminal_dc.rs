@@ -1,4 +1,4 @@
-use std::str::from_utf8;
+use crate::io::psse::{components::codes::MeteredEnd, error::ParseError, pssedata::PSSEDatatype, record::SectionContext};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -245,84 +245,85 @@ pub struct TwoTerminalDc {
     pub inv_comm_cap_x: f64,
 }
 
-pub fn parse_two_terminal_dc_line(lines: &[&[u8]], psse_version: i8) -> Vec<TwoTerminalDc> {
+impl TwoTerminalDc {
+    /// Metered end of the METER code
+    pub fn meter(&self) -> MeteredEnd {
+        MeteredEnd::from(self.metered_end.as_str())
+    }
+}
+
+pub fn parse_two_terminal_dc_line(lines: &[&[u8]], ctx: &SectionContext, psse_version: i8) -> Result<Vec<TwoTerminalDc>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
+    if lines.is_empty() {return Ok(Vec::new());}
     let mut two_terminal_dc_lines: Vec<TwoTerminalDc> = Vec::new();
     let mut i: usize = 0;
     // Version 34 added NDI and NDR
     let parse_adder: usize = (psse_version >= 34) as usize;
 
-    while i < lines.len() - 1 {
-        if let (Ok(line1_str), Ok(line2_str), Ok(line3_str)) = (
-            from_utf8(lines[i]), from_utf8(lines[i + 1]), from_utf8(lines[i + 2])
-        ) {
-            let parts1: Vec<&str> = line1_str.split(',').map(|s| s.trim()).collect();
-            let parts2: Vec<&str> = line2_str.split(',').map(|s| s.trim()).collect();
-            let parts3: Vec<&str> = line3_str.split(',').map(|s| s.trim()).collect();
+    while i < lines.len() {
+        let [rec1, rec2, rec3] = ctx.records(lines, i, PSSEDatatype::TwoTerminalDc)?;
 
-            let two_terminal_dc: TwoTerminalDc = TwoTerminalDc {
-                name: parts1[0].replace("'", "").replace("\"", "").trim().to_string(),
-                power_ctrl_mode: parts1[1].parse().unwrap_or(0),
-                resistance: parts1[2].parse().unwrap_or(0.0),
-                sending_pow: parts1[3].parse().unwrap_or(0.0),
-                voltage: parts1[4].parse().unwrap_or(0.0),
-                voltage_ctrl_mode: parts1[5].parse().unwrap_or(0.0),
-                r_comp: parts1[6].parse().unwrap_or(0.0),
-                dc_pow_margin: parts1[7].parse().unwrap_or(0.0),
-                metered_end: parts1[8].replace("'", "").trim().to_string(),
-                min_volt: parts1[9].parse().unwrap_or(0.0),
-                iteration_limit: parts1[10].parse().unwrap_or(20),
-                acceleration_fac: parts1[11].parse().unwrap_or(1.0),
+        let two_terminal_dc: TwoTerminalDc = TwoTerminalDc {
+            name: rec1.required_str(0, "NAME")?,
+            power_ctrl_mode: rec1.required(1, "MDC", 0)?,
+            resistance: rec1.required(2, "RDC", 0.0)?,
+            sending_pow: rec1.required(3, "SETVL", 0.0)?,
+            voltage: rec1.required(4, "VSCHD", 0.0)?,
+            voltage_ctrl_mode: rec1.required(5, "VCMOD", 0.0)?,
+            r_comp: rec1.required(6, "RCOMP", 0.0)?,
+            dc_pow_margin: rec1.required(7, "DELTI", 0.0)?,
+            metered_end: rec1.required_str(8, "METER")?,
+            min_volt: rec1.required(9, "DCVMIN", 0.0)?,
+            iteration_limit: rec1.required(10, "CCCITMX", 20)?,
+            acceleration_fac: rec1.required(11, "CCCACC", 1.0)?,
 
-                // Line 2
+            // Line 2
 
-                rec_bus_id: parts2[0].parse().unwrap_or(0),
-                rec_num_bridges: parts2[1].parse().unwrap_or(0),
-                rec_max_firing_angle: parts2[2].parse().unwrap_or(0.0),
-                rec_min_firing_angle: parts2[3].parse().unwrap_or(0.0),
-                rec_comm_xfmr_r: parts2[4].parse().unwrap_or(0.0),
-                rec_comm_xfmr_x: parts2[5].parse().unwrap_or(0.0),
-                rec_base_volt_ac: parts2[6].parse().unwrap_or(0.0),
-                rec_xfmr_ratio: parts2[7].parse().unwrap_or(1.0),
-                rec_tap_setting: parts2[8].parse().unwrap_or(1.0),
-                rec_max_tap_set: parts2[9].parse().unwrap_or(1.5),
-                rec_min_tap_set: parts2[10].parse().unwrap_or(0.51),
-                rec_tap_step: parts2[11].parse().unwrap_or(0.00625),
-                rec_comm_bus_id: parts2[12].parse().unwrap_or(0),
-                rec_comm_bus_node: parts2[13].parse().unwrap_or(0) * (parse_adder as i32),
-                rec_from_bus: parts2[13 + parse_adder].parse().unwrap_or(0),
-                rec_to_bus: parts2[14 + parse_adder].parse().unwrap_or(0),
-                rec_ckt_id: parts2[15 + parse_adder].replace("'", "").trim().to_string(),
-                rec_comm_cap_x: parts2[16 + parse_adder].parse().unwrap_or(0.0),
+            rec_bus_id: rec2.required(0, "IPR", 0)?,
+            rec_num_bridges: rec2.required(1, "NBR", 0)?,
+            rec_max_firing_angle: rec2.required(2, "ANMXR", 0.0)?,
+            rec_min_firing_angle: rec2.required(3, "ANMNR", 0.0)?,
+            rec_comm_xfmr_r: rec2.required(4, "RCR", 0.0)?,
+            rec_comm_xfmr_x: rec2.required(5, "XCR", 0.0)?,
+            rec_base_volt_ac: rec2.required(6, "EBASR", 0.0)?,
+            rec_xfmr_ratio: rec2.required(7, "TRR", 1.0)?,
+            rec_tap_setting: rec2.required(8, "TAPR", 1.0)?,
+            rec_max_tap_set: rec2.required(9, "TMXR", 1.5)?,
+            rec_min_tap_set: rec2.required(10, "TMNR", 0.51)?,
+            rec_tap_step: rec2.required(11, "STPR", 0.00625)?,
+            rec_comm_bus_id: rec2.required(12, "ICR", 0)?,
+            rec_comm_bus_node: if psse_version >= 34 { rec2.required(13, "NDR", 0)? } else { 0 },
+            rec_from_bus: rec2.required(13 + parse_adder, "IFR", 0)?,
+            rec_to_bus: rec2.required(14 + parse_adder, "ITR", 0)?,
+            rec_ckt_id: rec2.required_str(15 + parse_adder, "IDR")?,
+            rec_comm_cap_x: rec2.required(16 + parse_adder, "XCAPR", 0.0)?,
 
-                // Line 3
+            // Line 3
 
-                inv_bus_id: parts3[0].parse().unwrap_or(0),
-                inv_num_bridges: parts3[1].parse().unwrap_or(0),
-                inv_max_firing_angle: parts3[2].parse().unwrap_or(0.0),
-                inv_min_firing_angle: parts3[3].parse().unwrap_or(0.0),
-                inv_comm_xfmr_r: parts3[4].parse().unwrap_or(0.0),
-                inv_comm_xfmr_x: parts3[5].parse().unwrap_or(0.0),
-                inv_base_volt_ac: parts3[6].parse().unwrap_or(0.0),
-                inv_xfmr_ratio: parts3[7].parse().unwrap_or(1.0),
-                inv_tap_setting: parts3[8].parse().unwrap_or(1.0),
-                inv_max_tap_set: parts3[9].parse().unwrap_or(1.5),
-                inv_min_tap_set: parts3[10].parse().unwrap_or(0.51),
-                inv_tap_step: parts3[11].parse().unwrap_or(0.00625),
-                inv_comm_bus_id: parts3[12].parse().unwrap_or(0),
-                inv_comm_bus_node: parts3[13].parse().unwrap_or(0) * (parse_adder as i32),
-                inv_from_bus: parts3[13 + parse_adder].parse().unwrap_or(0),
-                inv_to_bus: parts3[14 + parse_adder].parse().unwrap_or(0),
-                inv_ckt_id: parts3[15 + parse_adder].replace("'", "").trim().to_string(),
-                inv_comm_cap_x: parts3[16 + parse_adder].parse().unwrap_or(0.0),
-            };
-            two_terminal_dc_lines.push(two_terminal_dc);
+            inv_bus_id: rec3.required(0, "IPI", 0)?,
+            inv_num_bridges: rec3.required(1, "NBI", 0)?,
+            inv_max_firing_angle: rec3.required(2, "ANMXI", 0.0)?,
+            inv_min_firing_angle: rec3.required(3, "ANMNI", 0.0)?,
+            inv_comm_xfmr_r: rec3.required(4, "RCI", 0.0)?,
+            inv_comm_xfmr_x: rec3.required(5, "XCI", 0.0)?,
+            inv_base_volt_ac: rec3.required(6, "EBASI", 0.0)?,
+            inv_xfmr_ratio: rec3.required(7, "TRI", 1.0)?,
+            inv_tap_setting: rec3.required(8, "TAPI", 1.0)?,
+            inv_max_tap_set: rec3.required(9, "TMXI", 1.5)?,
+            inv_min_tap_set: rec3.required(10, "TMNI", 0.51)?,
+            inv_tap_step: rec3.required(11, "STPI", 0.00625)?,
+            inv_comm_bus_id: rec3.required(12, "ICI", 0)?,
+            inv_comm_bus_node: if psse_version >= 34 { rec3.required(13, "NDI", 0)? } else { 0 },
+            inv_from_bus: rec3.required(13 + parse_adder, "IFI", 0)?,
+            inv_to_bus: rec3.required(14 + parse_adder, "ITI", 0)?,
+            inv_ckt_id: rec3.required_str(15 + parse_adder, "IDI")?,
+            inv_comm_cap_x: rec3.required(16 + parse_adder, "XCAPI", 0.0)?,
+        };
+        two_terminal_dc_lines.push(two_terminal_dc);
 
-            i += 3;
-        }        
+        i += 3;
     }
-    two_terminal_dc_lines
+    Ok(two_terminal_dc_lines)
 }
 
 #[cfg(test)]
@@ -335,8 +336,8 @@ mod tests {
 715600, 3,  27.500,  22.500,   1.31800,  32.45000,  330.00,  0.84260,  0.85470,  1.30000,  0.80000,  0.02250,      1121,    1,      7777,      8888, '2 ',   1.00000 
 715353, 3,  27.000,  27.000,   1.32700,  29.22000,  445.00,  0.57140,  0.86250,  1.31250,  0.81250,  0.02250,      1234,    1,      5555,      9999, '2 ',   1.00000 ";
         let lines: Vec<&[u8]> = ttdc_v35_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<TwoTerminalDc> = parse_two_terminal_dc_line(&lines, 35);
-        let ttdc_v35: Option<&TwoTerminalDc> = binding.get(0);
+        let binding: Vec<TwoTerminalDc> = parse_two_terminal_dc_line(&lines, &SectionContext::default(), 35).unwrap();
+        let ttdc_v35: Option<&TwoTerminalDc> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(ttdc_v35.unwrap().rec_from_bus, 7777);
@@ -357,8 +358,8 @@ mod tests {
 715600, 3,  27.500,  22.500,   1.31800,  32.45000,  330.00,  0.84260,  0.85470,  1.30000,  0.80000,  0.02250,      1121,      7777,      8888, '2 ',   1.00000 
 715353, 3,  27.000,  27.000,   1.32700,  29.22000,  445.00,  0.57140,  0.86250,  1.31250,  0.81250,  0.02250,      1234,      5555,      9999, '2 ',   1.00000 ";
         let lines: Vec<&[u8]> = ttdc_v33_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<TwoTerminalDc> = parse_two_terminal_dc_line(&lines, 33);
-        let ttdc_v33: Option<&TwoTerminalDc> = binding.get(0);
+        let binding: Vec<TwoTerminalDc> = parse_two_terminal_dc_line(&lines, &SectionContext::default(), 33).unwrap();
+        let ttdc_v33: Option<&TwoTerminalDc> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(ttdc_v33.unwrap().rec_from_bus, 7777);
@@ -1,4 +1,4 @@
-use std::str::from_utf8;
+use crate::io::psse::{error::ParseError, pssedata::PSSEDatatype, record::SectionContext};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default, PartialEq)]
@@ -240,80 +240,74 @@ pub struct VSCDc {
     pub control_bus_percent_mvar_2: f64,
 }
 
-pub fn parse_vsc_dc_line(lines: &[&[u8]], psse_version: i8) -> Vec<VSCDc> {
+pub fn parse_vsc_dc_line(lines: &[&[u8]], ctx: &SectionContext, psse_version: i8) -> Result<Vec<VSCDc>, ParseError> {
     //Check if there is even data before proceeding
-    if lines.len() == 0 {return Vec::new();}
+    if lines.is_empty() {return Ok(Vec::new());}
     let mut vsc_dc_lines: Vec<VSCDc> = Vec::new();
     let mut i: usize = 0;
     // Version 34 added NREG
     let parse_adder: usize = (psse_version >= 34) as usize;
 
-    while i < lines.len() - 1 {
-        if let (Ok(line1_str), Ok(line2_str), Ok(line3_str)) = (
-            from_utf8(lines[i]), from_utf8(lines[i + 1]), from_utf8(lines[i + 2])
-        ) {
-            let parts1: Vec<&str> = line1_str.split(',').map(|s| s.trim()).collect();
-            let parts2: Vec<&str> = line2_str.split(',').map(|s| s.trim()).collect();
-            let parts3: Vec<&str> = line3_str.split(',').map(|s| s.trim()).collect();
+    while i < lines.len() {
+        let [rec1, rec2, rec3] = ctx.records(lines, i, PSSEDatatype::VscDc)?;
 
-            let vsc_dc: VSCDc = VSCDc {
-                name: parts1[0].replace("'", "").trim().to_string(),
-                status: parts1[1].parse().unwrap_or(1),
-                resistance: parts1[2].parse().unwrap_or(0.0),
-                owner1: parts1[3].parse().unwrap_or(1),
-                owner1_percent: parts1[4].parse().unwrap_or(1.0),
-                // Sometimes they won't list multiple owners and just leave the fields blank, get solves this
-                owner2: parts1.get(5).and_then(|s| s.parse().ok()).unwrap_or(0),
-                owner2_percent: parts1.get(6).and_then(|s| s.parse().ok()).unwrap_or(1.0),
-                owner3: parts1.get(7).and_then(|s| s.parse().ok()).unwrap_or(0),
-                owner3_percent: parts1.get(8).and_then(|s| s.parse().ok()).unwrap_or(1.0),
-                owner4: parts1.get(9).and_then(|s| s.parse().ok()).unwrap_or(0),
-                owner4_percent: parts1.get(10).and_then(|s| s.parse().ok()).unwrap_or(1.0),
+        let vsc_dc: VSCDc = VSCDc {
+            name: rec1.required_str(0, "NAME")?,
+            status: rec1.required(1, "MDC", 1)?,
+            resistance: rec1.required(2, "RDC", 0.0)?,
+            owner1: rec1.required(3, "O1", 1)?,
+            owner1_percent: rec1.required(4, "F1", 1.0)?,
+            // Sometimes they won't list multiple owners and just leave the fields blank, get solves this
+            owner2: rec1.optional(5, "O2", 0)?,
+            owner2_percent: rec1.optional(6, "F2", 1.0)?,
+            owner3: rec1.optional(7, "O3", 0)?,
+            owner3_percent: rec1.optional(8, "F3", 1.0)?,
+            owner4: rec1.optional(9, "O4", 0)?,
+            owner4_percent: rec1.optional(10, "F4", 1.0)?,
 
-                // Line 2
+            // Line 2
 
-                converter_1_bus_id: parts2[0].parse().unwrap_or(0),
-                converter_1_dc_control: parts2[1].parse().unwrap_or(0),
-                converter_1_ac_control: parts2[2].parse().unwrap_or(1),
-                dc_setpoint_1: parts2[3].parse().unwrap_or(0.0),
-                ac_setpoint_1: parts2[4].parse().unwrap_or(0.0),
-                cont_loss_coeff_1: parts2[5].parse().unwrap_or(0.0),
-                prop_loss_coeff_1: parts2[6].parse().unwrap_or(0.0),
-                min_converter_loss_1: parts2[7].parse().unwrap_or(0.0),
-                mva_rating_1: parts2[8].parse().unwrap_or(0.0),
-                ac_rating_1: parts2[9].parse().unwrap_or(0.0),
-                power_weighting_factor_1: parts2[10].parse().unwrap_or(1.0),
-                max_reac_1: parts2[11].parse().unwrap_or(9999.0),
-                min_reac_1: parts2[12].parse().unwrap_or(-9999.0),
-                control_bus_id_1: parts2[13].parse().unwrap_or(0),
-                control_node_1: parts2[14].parse().unwrap_or(0) * (parse_adder as i32),
-                control_bus_percent_mvar_1: parts2[14 + parse_adder].parse().unwrap_or(100.0),
+            converter_1_bus_id: rec2.required(0, "IBUS", 0)?,
+            converter_1_dc_control: rec2.required(1, "TYPE", 0)?,
+            converter_1_ac_control: rec2.required(2, "MODE", 1)?,
+            dc_setpoint_1: rec2.required(3, "DCSET", 0.0)?,
+            ac_setpoint_1: rec2.required(4, "ACSET", 0.0)?,
+            cont_loss_coeff_1: rec2.required(5, "ALOSS", 0.0)?,
+            prop_loss_coeff_1: rec2.required(6, "BLOSS", 0.0)?,
+            min_converter_loss_1: rec2.required(7, "MINLOSS", 0.0)?,
+            mva_rating_1: rec2.required(8, "SMAX", 0.0)?,
+            ac_rating_1: rec2.required(9, "IMAX", 0.0)?,
+            power_weighting_factor_1: rec2.required(10, "PWF", 1.0)?,
+            max_reac_1: rec2.required(11, "MAXQ", 9999.0)?,
+            min_reac_1: rec2.required(12, "MINQ", -9999.0)?,
+            control_bus_id_1: rec2.required(13, "VSREG", 0)?,
+            control_node_1: if psse_version >= 34 { rec2.required(14, "NREG", 0)? } else { 0 },
+            control_bus_percent_mvar_1: rec2.required(14 + parse_adder, "RMPCT", 100.0)?,
 
-                // Line 3
+            // Line 3
 
-                converter_2_bus_id: parts3[0].parse().unwrap_or(0),
-                converter_2_dc_control: parts3[1].parse().unwrap_or(0),
-                converter_2_ac_control: parts3[2].parse().unwrap_or(1),
-                dc_setpoint_2: parts3[3].parse().unwrap_or(0.0),
-                ac_setpoint_2: parts3[4].parse().unwrap_or(0.0),
-                cont_loss_coeff_2: parts3[5].parse().unwrap_or(0.0),
-                prop_loss_coeff_2: parts3[6].parse().unwrap_or(0.0),
-                min_converter_loss_2: parts3[7].parse().unwrap_or(0.0),
-                mva_rating_2: parts3[8].parse().unwrap_or(0.0),
-                ac_rating_2: parts3[9].parse().unwrap_or(0.0),
-                power_weighting_factor_2: parts3[10].parse().unwrap_or(1.0),
-                max_reac_2: parts3[11].parse().unwrap_or(9999.0),
-                min_reac_2: parts3[12].parse().unwrap_or(-9999.0),
-                control_bus_id_2: parts3[13].parse().unwrap_or(0),
-                control_node_2: parts3[14].parse().unwrap_or(0) * (parse_adder as i32),
-                control_bus_percent_mvar_2: parts3[14 + parse_adder].parse().unwrap_or(100.0),
-            };
-            vsc_dc_lines.push(vsc_dc);
+            converter_2_bus_id: rec3.required(0, "IBUS", 0)?,
+            converter_2_dc_control: rec3.required(1, "TYPE", 0)?,
+            converter_2_ac_control: rec3.required(2, "MODE", 1)?,
+            dc_setpoint_2: rec3.required(3, "DCSET", 0.0)?,
+            ac_setpoint_2: rec3.required(4, "ACSET", 0.0)?,
+            cont_loss_coeff_2: rec3.required(5, "ALOSS", 0.0)?,
+            prop_loss_coeff_2: rec3.required(6, "BLOSS", 0.0)?,
+            min_converter_loss_2: rec3.required(7, "MINLOSS", 0.0)?,
+            mva_rating_2: rec3.required(8, "SMAX", 0.0)?,
+            ac_rating_2: rec3.required(9, "IMAX", 0.0)?,
+            power_weighting_factor_2: rec3.required(10, "PWF", 1.0)?,
+            max_reac_2: rec3.required(11, "MAXQ", 9999.0)?,
+            min_reac_2: rec3.required(12, "MINQ", -9999.0)?,
+            control_bus_id_2: rec3.required(13, "VSREG", 0)?,
+            control_node_2: if psse_version >= 34 { rec3.required(14, "NREG", 0)? } else { 0 },
+            control_bus_percent_mvar_2: rec3.required(14 + parse_adder, "RMPCT", 100.0)?,
+        };
+        vsc_dc_lines.push(vsc_dc);
 
-            i += 3;
-        }        
+        i += 3;
     }
-    vsc_dc_lines
+    Ok(vsc_dc_lines)
 }
 
 #[cfg(test)]
@@ -326,8 +320,8 @@ mod tests {
 99890, 2, 1,      96.00, 1.00000,  1800.000,     3.400,  1110.000,    360.00,   1032.00,0.50000,    -20.00,    -70.00, 128880,   0,  100.0
 22050, 1, 1,     590.00, 1.08080,  2200.000,     3.400,  1110.000,    360.00,   1085.00,0.50000,    -11.00,    -70.00, 123636,   2,  90.0";
         let lines: Vec<&[u8]> = vsdc_v35_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<VSCDc> = parse_vsc_dc_line(&lines, 35);
-        let vsdc_v35_str: Option<&VSCDc> = binding.get(0);
+        let binding: Vec<VSCDc> = parse_vsc_dc_line(&lines, &SectionContext::default(), 35).unwrap();
+        let vsdc_v35_str: Option<&VSCDc> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(vsdc_v35_str.unwrap().name, "TST-VSC".to_string());
@@ -348,8 +342,8 @@ mod tests {
 99890, 2, 1,      96.00, 1.00000,  1800.000,     3.400,  1110.000,    360.00,   1032.00,0.50000,    -20.00,    -70.00, 128880,  100.0
 22050, 1, 1,     590.00, 1.08080,  2200.000,     3.400,  1110.000,    360.00,   1085.00,0.50000,    -11.00,    -70.00, 123636,  90.0";
         let lines: Vec<&[u8]> = vsdc_v35_str.lines().map(|line| line.as_bytes()).collect();
-        let binding: Vec<VSCDc> = parse_vsc_dc_line(&lines, 33);
-        let vsdc_v35_str: Option<&VSCDc> = binding.get(0);
+        let binding: Vec<VSCDc> = parse_vsc_dc_line(&lines, &SectionContext::default(), 33).unwrap();
+        let vsdc_v35_str: Option<&VSCDc> = binding.first();
         //To avoid checking everything, check bits and pieces to make sure things are in the right place
         //If any values were distorted on the lines from the adder, it would show here
         assert_eq!(vsdc_v35_str.unwrap().name, "TST-VSC".to_string());
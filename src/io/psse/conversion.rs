@@ -0,0 +1,404 @@
+use std::fmt::{self, Display};
+
+use super::{components::structs::*, pssedata::{PSSEData, PSSEDatatype}};
+
+const EXTRA_RATINGS: [&str; 9] = ["RATE4", "RATE5", "RATE6", "RATE7", "RATE8", "RATE9", "RATE10", "RATE11", "RATE12"];
+const BLOCK_STATUS: [&str; 8] = ["S1", "S2", "S3", "S4", "S5", "S6", "S7", "S8"];
+//Most entries a V33 impedance correction table can hold
+const V33_CORRECTION_ENTRIES: usize = 11;
+
+/// A value that could not be carried over when converting a case to another revision, or that had to be made up
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionLoss {
+    /// ## Section of the source record
+    pub section: PSSEDatatype,
+    /// ## Source record, identified by its buses and ID
+    pub record: String,
+    /// ## PSS/E mnemonic of the field
+    pub field: &'static str,
+    /// ## Value that was dropped or filled in
+    pub value: String,
+    /// ## Whether the value was dropped or filled in
+    pub kind: LossKind,
+}
+
+/// What a conversion did to the value of a [`ConversionLoss`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LossKind {
+    /// The target revision can't represent the value, so it was dropped
+    Dropped,
+    /// The record the source record became in the target revision needs a value the source record doesn't have
+    Filled,
+}
+
+impl Display for ConversionLoss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            LossKind::Dropped => write!(f, "{:?} data {}: {} = {} dropped", self.section, self.record, self.field, self.value),
+            LossKind::Filled => write!(f, "{:?} data {}: {} = {} filled in", self.section, self.record, self.field, self.value),
+        }
+    }
+}
+
+/// Converts a case to the data layout of another PSS/E revision (V33, V34, V35 or V36)
+///
+/// Fields are mapped between the revisions, so the converted case can be written with [`write_raw`](super::writers::raw_writer::write_raw)
+/// for the target revision and read back as the same converted case. What a downgrade drops or changes is not restored
+/// by converting back up:
+/// * V33 branch and transformer ratings fill RATE1-RATE3 of the 12 ratings in V34+, and ratings 4-12 are dropped going down to V33
+/// * Switched shunt blocks that are out of service can't be represented before V34, so they are removed from the block list
+/// * System switching devices don't exist before V34 and become zero resistance branches with the device's reactance,
+///   owned by the owner of their from bus, and stay branches going back up
+/// * V33 impedance correction factors are real, the real part of a V34+ complex factor being kept going down to V33
+///   (with at most 11 entries per table) and the imaginary part set to 0 going up
+/// * Substations, terminal nodes, base loaded flags, distributed generation, load types and switched shunt IDs are dropped when not supported
+/// * The V36 long bus names, load priorities, generator and branch types, transformer descriptions and tap steps are dropped below V36
+///
+/// Every value that differs from its default and can't be represented in the target revision is returned as a [`ConversionLoss`],
+/// as is every value filled in for a field the source record doesn't have.
+pub fn convert_revision(data: &PSSEData, target: i8) -> (PSSEData, Vec<ConversionLoss>) {
+    let mut converted: PSSEData = data.clone();
+    let mut losses: Losses = Losses::default();
+    converted.header.revision = target;
+
+    if target < 36 {
+        drop_v36_columns(&mut converted, &mut losses);
+    }
+    if target < 35 {
+        for load in converted.loads.iter_mut() {
+            let record: String = format!("{} '{}'", load.bus_id, load.id);
+            losses.drop(PSSEDatatype::Load, &record, "LOADTYPE", &mut load.load_type, String::new());
+        }
+        for shunt in converted.switched_shunts.iter_mut() {
+            let record: String = format!("{} '{}'", shunt.bus_id, shunt.id);
+            losses.drop(PSSEDatatype::SwitchedShunt, &record, "ID", &mut shunt.id, "1".to_string());
+        }
+        //Node-breaker data only exists from V35
+        for substation in converted.substations.drain(..) {
+            losses.push(PSSEDatatype::Substation, &format!("'{}'", substation.name), "IS", substation.id);
+        }
+    }
+    if target < 34 {
+        downgrade_to_v33(&mut converted, &mut losses);
+    } else {
+        //V33 branches have no name, which is blank from V34
+        for branch in converted.branches.iter_mut().filter(|branch| branch.name.trim().is_empty()) {
+            branch.name = String::new();
+        }
+    }
+    (converted, losses.losses)
+}
+
+//The columns V36 inserted in the middle of the bus, load, generator, branch and transformer records
+fn drop_v36_columns(data: &mut PSSEData, losses: &mut Losses) {
+    for bus in data.buses.iter_mut() {
+        losses.drop(PSSEDatatype::Bus, &bus.id.to_string(), "LONGNAME", &mut bus.long_name, String::new());
+    }
+    for load in data.loads.iter_mut() {
+        let record: String = format!("{} '{}'", load.bus_id, load.id);
+        losses.drop(PSSEDatatype::Load, &record, "PRIORITY", &mut load.priority, 0);
+    }
+    for generator in data.generators.iter_mut() {
+        let record: String = format!("{} '{}'", generator.bus_id, generator.id);
+        losses.drop(PSSEDatatype::Generator, &record, "GENTYPE", &mut generator.gen_type, String::new());
+    }
+    for branch in data.branches.iter_mut() {
+        let record: String = format!("{}-{} '{}'", branch.from_bus, branch.to_bus, branch.circuit);
+        losses.drop(PSSEDatatype::Branch, &record, "BRTYPE", &mut branch.branch_type, String::new());
+    }
+    for transformer in data.transformers.iter_mut() {
+        let record: String = format!("{}-{}-{} '{}'", transformer.from_bus, transformer.to_bus, transformer.tertiary_bus, transformer.circuit);
+        losses.drop(PSSEDatatype::Transformer, &record, "DESC", &mut transformer.description, String::new());
+        losses.drop(PSSEDatatype::Transformer, &record, "TAPSTEP1", &mut transformer.tap_step_1, 0.0);
+        losses.drop(PSSEDatatype::Transformer, &record, "TAPSTEP2", &mut transformer.tap_step_2, 0.0);
+        losses.drop(PSSEDatatype::Transformer, &record, "TAPSTEP3", &mut transformer.tap_step_3, 0.0);
+    }
+}
+
+fn downgrade_to_v33(data: &mut PSSEData, losses: &mut Losses) {
+    for load in data.loads.iter_mut() {
+        let record: String = format!("{} '{}'", load.bus_id, load.id);
+        losses.drop(PSSEDatatype::Load, &record, "DGENP", &mut load.dgen_mw, 0.0);
+        losses.drop(PSSEDatatype::Load, &record, "DGENQ", &mut load.dgen_mvar, 0.0);
+        losses.drop(PSSEDatatype::Load, &record, "DGENM", &mut load.dgen_mode, 0);
+    }
+    for generator in data.generators.iter_mut() {
+        let record: String = format!("{} '{}'", generator.bus_id, generator.id);
+        losses.drop(PSSEDatatype::Generator, &record, "NREG", &mut generator.reg_bus_node, 0);
+        losses.drop(PSSEDatatype::Generator, &record, "BASLOD", &mut generator.base_loaded, 0);
+    }
+    for branch in data.branches.iter_mut() {
+        let record: String = format!("{}-{} '{}'", branch.from_bus, branch.to_bus, branch.circuit);
+        if !branch.name.trim().is_empty() {
+            losses.drop(PSSEDatatype::Branch, &record, "NAME", &mut branch.name, " ".to_string());
+        }
+        branch.name = " ".to_string();
+        let ratings: [&mut f64; 9] = [
+            &mut branch.rate4, &mut branch.rate5, &mut branch.rate6, &mut branch.rate7, &mut branch.rate8,
+            &mut branch.rate9, &mut branch.rate10, &mut branch.rate11, &mut branch.rate12,
+        ];
+        losses.drop_ratings(PSSEDatatype::Branch, &record, ratings);
+    }
+    //Switching devices are kept as zero resistance branches with the same buses, circuit, reactance and ratings
+    for device in data.switching_devices.drain(..) {
+        let record: String = format!("{}-{} '{}'", device.from_bus, device.to_bus, device.circuit);
+        let section: PSSEDatatype = PSSEDatatype::SystemSwitchingDevice;
+        losses.push(section, &record, "STYPE", device.device_type);
+        if device.normal_status != device.status {
+            losses.push(section, &record, "NSTATUS", device.normal_status);
+        }
+        if !device.name.trim().is_empty() {
+            losses.push(section, &record, "NAME", &device.name);
+        }
+        let mut ratings: [f64; 9] = [
+            device.rate4, device.rate5, device.rate6, device.rate7, device.rate8,
+            device.rate9, device.rate10, device.rate11, device.rate12,
+        ];
+        losses.drop_ratings(section, &record, ratings.each_mut());
+        //The device has no charging, length or owner, so the branch is owned by the owner of its from bus (or to bus)
+        let owner1: i16 = [device.from_bus, device.to_bus].iter()
+            .find_map(|&id| data.buses.iter().find(|bus| bus.id == id && bus.owner > 0).map(|bus| bus.owner))
+            .unwrap_or(1);
+        losses.fill(section, &record, "B", 0.0);
+        losses.fill(section, &record, "LEN", 0.0);
+        losses.fill(section, &record, "O1", owner1);
+        data.branches.push(Branch {
+            from_bus: device.from_bus,
+            to_bus: device.to_bus,
+            circuit: device.circuit,
+            x: device.x,
+            name: " ".to_string(),
+            rate1: device.rate1,
+            rate2: device.rate2,
+            rate3: device.rate3,
+            status: device.status,
+            meter_end: device.meter_end,
+            owner1,
+            owner1_percent: 1.0,
+            owner2_percent: 1.0,
+            owner3_percent: 1.0,
+            owner4_percent: 1.0,
+            ..Default::default()
+        });
+    }
+    for transformer in data.transformers.iter_mut() {
+        let record: String = format!("{}-{}-{} '{}'", transformer.from_bus, transformer.to_bus, transformer.tertiary_bus, transformer.circuit);
+        let winding_1: [&mut f64; 9] = [
+            &mut transformer.w1_rate4, &mut transformer.w1_rate5, &mut transformer.w1_rate6, &mut transformer.w1_rate7, &mut transformer.w1_rate8,
+            &mut transformer.w1_rate9, &mut transformer.w1_rate10, &mut transformer.w1_rate11, &mut transformer.w1_rate12,
+        ];
+        losses.drop_ratings(PSSEDatatype::Transformer, &format!("{} winding 1", record), winding_1);
+        let winding_2: [&mut f64; 9] = [
+            &mut transformer.w2_rate4, &mut transformer.w2_rate5, &mut transformer.w2_rate6, &mut transformer.w2_rate7, &mut transformer.w2_rate8,
+            &mut transformer.w2_rate9, &mut transformer.w2_rate10, &mut transformer.w2_rate11, &mut transformer.w2_rate12,
+        ];
+        losses.drop_ratings(PSSEDatatype::Transformer, &format!("{} winding 2", record), winding_2);
+        let winding_3: [&mut f64; 9] = [
+            &mut transformer.w3_rate4, &mut transformer.w3_rate5, &mut transformer.w3_rate6, &mut transformer.w3_rate7, &mut transformer.w3_rate8,
+            &mut transformer.w3_rate9, &mut transformer.w3_rate10, &mut transformer.w3_rate11, &mut transformer.w3_rate12,
+        ];
+        losses.drop_ratings(PSSEDatatype::Transformer, &format!("{} winding 3", record), winding_3);
+    }
+    //V33 tables hold up to 11 (T, F) pairs with a real factor, so the imaginary part of each factor is dropped
+    for table in data.impedance_correction.iter_mut() {
+        for (position, entry) in table.correction_table_entries.iter_mut().enumerate() {
+            let record: String = format!("{} entry {}", table.index, position + 1);
+            losses.drop(PSSEDatatype::ImpedanceCorrection, &record, "IMi", &mut entry.imag_complex_scaling_factor, 0.0);
+        }
+        if table.correction_table_entries.len() > V33_CORRECTION_ENTRIES {
+            for (position, entry) in table.correction_table_entries.drain(V33_CORRECTION_ENTRIES..).enumerate() {
+                let record: String = format!("{} entry {}", table.index, V33_CORRECTION_ENTRIES + position + 1);
+                losses.push(PSSEDatatype::ImpedanceCorrection, &record, "Ti", entry.tap);
+            }
+        }
+    }
+    for dc_line in data.two_terminal_dc.iter_mut() {
+        let record: String = format!("'{}'", dc_line.name);
+        losses.drop(PSSEDatatype::TwoTerminalDc, &record, "NDR", &mut dc_line.rec_comm_bus_node, 0);
+        losses.drop(PSSEDatatype::TwoTerminalDc, &record, "NDI", &mut dc_line.inv_comm_bus_node, 0);
+    }
+    for dc_line in data.vsc_dc.iter_mut() {
+        let record: String = format!("'{}'", dc_line.name);
+        losses.drop(PSSEDatatype::VscDc, &record, "NREG", &mut dc_line.control_node_1, 0);
+        losses.drop(PSSEDatatype::VscDc, &record, "NREG", &mut dc_line.control_node_2, 0);
+    }
+    for facts in data.facts.iter_mut() {
+        let record: String = format!("'{}'", facts.deivce_name);
+        losses.drop(PSSEDatatype::Facts, &record, "NREG", &mut facts.regulated_bus_node, 0);
+    }
+    for shunt in data.switched_shunts.iter_mut() {
+        let record: String = format!("{} '{}'", shunt.bus_id, shunt.id);
+        losses.drop(PSSEDatatype::SwitchedShunt, &record, "NREG", &mut shunt.controlled_bus_node, 0);
+        //Out of service blocks are removed, moving the blocks after them up
+        let blocks: usize = shunt.block_status.len().min(shunt.steps.len()).min(shunt.b_increment.len());
+        let mut steps: Vec<i32> = Vec::with_capacity(blocks);
+        let mut b_increment: Vec<f64> = Vec::with_capacity(blocks);
+        for block in 0..blocks {
+            if shunt.block_status[block] == 0 {
+                if shunt.steps[block] != 0 {
+                    losses.push(PSSEDatatype::SwitchedShunt, &record, BLOCK_STATUS[block.min(7)], 0);
+                }
+            } else {
+                steps.push(shunt.steps[block]);
+                b_increment.push(shunt.b_increment[block]);
+            }
+        }
+        steps.resize(blocks, 0);
+        b_increment.resize(blocks, 0.0);
+        shunt.block_status = vec![1; blocks];
+        shunt.steps = steps;
+        shunt.b_increment = b_increment;
+    }
+}
+
+/// Collects the values dropped during a conversion
+#[derive(Default)]
+struct Losses {
+    losses: Vec<ConversionLoss>,
+}
+
+impl Losses {
+    fn push<T: Display>(&mut self, section: PSSEDatatype, record: &str, field: &'static str, value: T) {
+        self.losses.push(ConversionLoss { section, record: record.to_string(), field, value: value.to_string(), kind: LossKind::Dropped });
+    }
+
+    fn fill<T: Display>(&mut self, section: PSSEDatatype, record: &str, field: &'static str, value: T) {
+        self.losses.push(ConversionLoss { section, record: record.to_string(), field, value: value.to_string(), kind: LossKind::Filled });
+    }
+
+    //Resets the value to its default, recording it if it was set
+    fn drop<T: PartialEq + Display>(&mut self, section: PSSEDatatype, record: &str, field: &'static str, value: &mut T, default: T) {
+        if *value != default {
+            self.push(section, record, field, &*value);
+            *value = default;
+        }
+    }
+
+    fn drop_ratings(&mut self, section: PSSEDatatype, record: &str, ratings: [&mut f64; 9]) {
+        for (rating, field) in ratings.into_iter().zip(EXTRA_RATINGS) {
+            self.drop(section, record, field, rating, 0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::psse::{components::impedance_correction::ImpedanceCorrectionEntry, error::Diagnostic, options::ParseOptions, parsers::fast_parser::parse_fast, pssedata::SourceMap, writers::raw_writer::write_raw};
+
+    use super::*;
+
+    fn read(fixture: &str) -> PSSEData {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        parse_fast(format!("{}/test-cases/{}", dir, fixture), &ParseOptions::strict()).unwrap().0
+    }
+
+    //Writes the case and reads it back, which should give the same case
+    fn rewrite(data: &PSSEData, name: &str) -> PSSEData {
+        let mut raw: Vec<u8> = Vec::new();
+        write_raw(data, &mut raw, data.header.revision).unwrap();
+        let filepath = std::env::temp_dir().join(format!("pf_gat_parser_convert_{}.RAW", name));
+        std::fs::write(&filepath, &raw).unwrap();
+        let (written, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::strict()).unwrap();
+        std::fs::remove_file(&filepath).unwrap();
+        assert!(diagnostics.is_empty());
+        written
+    }
+
+    #[test]
+    fn downgrade_v35_to_v33() {
+        let data: PSSEData = read("TEST_parse_psse_v35.RAW");
+        let (converted, losses): (PSSEData, Vec<ConversionLoss>) = convert_revision(&data, 33);
+        assert_eq!(converted.header.revision, 33);
+        //The switching device becomes a zero impedance branch
+        assert!(converted.switching_devices.is_empty());
+        assert_eq!(converted.branches.len(), 2);
+        assert_eq!(converted.branches[1].circuit, "@1".to_string());
+        assert_eq!(converted.branches[1].x, 0.0001);
+        assert_eq!(converted.branches[1].rate1, 2000.0);
+        //The out of service second block is removed
+        assert_eq!(converted.switched_shunts[0].steps, vec![2, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(converted.switched_shunts[0].block_status, vec![1; 8]);
+        let dropped: Vec<(PSSEDatatype, &str)> = losses.iter().map(|loss| (loss.section, loss.field)).collect();
+        assert!(dropped.contains(&(PSSEDatatype::Load, "LOADTYPE")));
+        assert!(dropped.contains(&(PSSEDatatype::Generator, "BASLOD")));
+        assert!(dropped.contains(&(PSSEDatatype::Branch, "NAME")));
+        assert!(dropped.contains(&(PSSEDatatype::SystemSwitchingDevice, "STYPE")));
+        assert!(dropped.contains(&(PSSEDatatype::SwitchedShunt, "S2")));
+        assert_eq!(losses[0].to_string(), "Load data 152 '1': LOADTYPE = INDUSTRIAL dropped".to_string());
+        assert_eq!(rewrite(&converted, "v35_to_v33"), converted);
+    }
+
+    #[test]
+    fn downgrade_and_upgrade_v35() {
+        let mut data: PSSEData = read("TEST_parse_psse_v35.RAW");
+        //The switching device leaves bus 151
+        data.buses[1].owner = 2;
+        let (v33, losses): (PSSEData, Vec<ConversionLoss>) = convert_revision(&data, 33);
+        let filled: Vec<String> = losses.iter().filter(|loss| loss.kind == LossKind::Filled).map(ToString::to_string).collect();
+        assert_eq!(filled, [
+            "SystemSwitchingDevice data 151-152 '@1': B = 0 filled in",
+            "SystemSwitchingDevice data 151-152 '@1': LEN = 0 filled in",
+            "SystemSwitchingDevice data 151-152 '@1': O1 = 2 filled in",
+        ]);
+        assert!(losses.iter().any(|loss| loss.section == PSSEDatatype::Substation && loss.kind == LossKind::Dropped));
+
+        let (v35, upgrade_losses): (PSSEData, Vec<ConversionLoss>) = convert_revision(&rewrite(&v33, "v35_to_v33_to_v35"), 35);
+        assert!(upgrade_losses.is_empty());
+        assert_eq!(rewrite(&v35, "v33_to_v35_again"), v35);
+        //What the downgrade dropped or changed does not come back
+        assert!(v35.substations.is_empty());
+        assert!(v35.switching_devices.is_empty());
+        assert_eq!((v35.branches[1].circuit.as_str(), v35.branches[1].x, v35.branches[1].owner1), ("@1", 0.0001, 2));
+        assert_eq!(v35.switched_shunts[0].block_status, vec![1; 8]);
+        assert_eq!(v35.switched_shunts[0].steps, vec![2, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(v35.loads[0].load_type, String::new());
+        //The rest of the case does
+        assert_eq!(v35.buses, data.buses);
+        assert_eq!(v35.branches[0].rate3, data.branches[0].rate3);
+        assert_eq!(v35.transformers.len(), data.transformers.len());
+        assert_eq!((v35.areas.clone(), v35.zones.clone(), v35.owners.clone()), (data.areas.clone(), data.zones.clone(), data.owners.clone()));
+    }
+
+    #[test]
+    fn downgrade_complex_correction_factors() {
+        let mut data: PSSEData = read("TEST_parse_psse_v35.RAW");
+        data.impedance_correction[0].correction_table_entries[1].imag_complex_scaling_factor = 0.05;
+        let (converted, losses): (PSSEData, Vec<ConversionLoss>) = convert_revision(&data, 33);
+        //The real part is kept as the V33 factor
+        let entry: &ImpedanceCorrectionEntry = &converted.impedance_correction[0].correction_table_entries[1];
+        assert_eq!((entry.tap, entry.real_complex_scaling_factor, entry.imag_complex_scaling_factor), (-36.0, 0.538, 0.0));
+        let loss: &ConversionLoss = losses.iter().find(|loss| loss.section == PSSEDatatype::ImpedanceCorrection).unwrap();
+        assert_eq!(loss.to_string(), "ImpedanceCorrection data 1 entry 2: IMi = 0.05 dropped".to_string());
+        assert_eq!(rewrite(&converted, "complex_factors"), converted);
+    }
+
+    #[test]
+    fn downgrade_v36_to_v35() {
+        let data: PSSEData = read("TEST_parse_psse_v36.RAW");
+        let (converted, losses): (PSSEData, Vec<ConversionLoss>) = convert_revision(&data, 35);
+        //The V36 case is the V35 one with the V36 columns filled in
+        let v35: PSSEData = read("TEST_parse_psse_v35.RAW");
+        assert_eq!(PSSEData { header: v35.header.clone(), ..converted.clone() }, v35);
+        let fields: Vec<&str> = losses.iter().map(|loss| loss.field).collect();
+        assert_eq!(fields, ["LONGNAME", "LONGNAME", "LONGNAME", "PRIORITY", "GENTYPE", "BRTYPE", "DESC", "TAPSTEP1"]);
+        assert_eq!(losses[6].to_string(), "Transformer data 101-151-0 '1': DESC = UNIT A STEP-UP dropped".to_string());
+        assert_eq!(rewrite(&converted, "v36_to_v35"), converted);
+    }
+
+    #[test]
+    fn upgrade_v33_to_v35() {
+        let data: PSSEData = read("TEST_parse_psse_v33.RAW");
+        let (converted, losses): (PSSEData, Vec<ConversionLoss>) = convert_revision(&data, 35);
+        assert!(losses.is_empty());
+        assert_eq!(converted.branches[0].rate2, 345.67);
+        assert_eq!(converted.branches[0].name, String::new());
+        //The real V33 correction factors become complex factors without an imaginary part
+        assert_eq!(converted.impedance_correction[0].correction_table_entries.len(), 11);
+        assert!(converted.impedance_correction[0].correction_table_entries.iter().all(|entry| entry.imag_complex_scaling_factor == 0.0));
+        assert_eq!(rewrite(&converted, "v33_to_v35"), converted);
+        //Converting back gives the original case
+        assert_eq!(convert_revision(&converted, 33), (data, Vec::new()));
+    }
+}
@@ -0,0 +1,80 @@
+use std::{borrow::Cow, str::from_utf8};
+
+use super::{options::ParseMode, parsers::sections::split_lines};
+
+/// Character encoding of the text (bus names, IDs, ...) in a .RAW file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8, reading any line that is not valid UTF-8 as Windows-1252
+    #[default]
+    Auto,
+    /// UTF-8 only, a line that is not valid UTF-8 fails in strict mode and has its invalid bytes replaced in lenient mode
+    Utf8,
+    /// ISO-8859-1, each byte is the character with the same code point
+    Latin1,
+    /// Windows-1252, Latin-1 with printable characters in place of the 0x80 - 0x9F control codes
+    Windows1252,
+}
+
+//Characters of the Windows-1252 bytes 0x80 - 0x9F, the five unassigned bytes keep their Latin-1 control code
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+impl Encoding {
+    /// Decodes a line of the file into text
+    ///
+    /// ```
+    /// use pf_gat_parser::io::psse::encoding::Encoding;
+    ///
+    /// assert_eq!(Encoding::Auto.decode(b"'M\xDCNCHEN'"), "'MÜNCHEN'");
+    /// assert_eq!(Encoding::Windows1252.decode(b"\x80 100"), "€ 100");
+    /// ```
+    pub fn decode(self, bytes: &[u8]) -> Cow<'_, str> {
+        if let Ok(text) = from_utf8(bytes) {
+            if bytes.is_ascii() || matches!(self, Encoding::Auto | Encoding::Utf8) {
+                return Cow::Borrowed(text);
+            }
+        }
+        match self {
+            Encoding::Utf8 => String::from_utf8_lossy(bytes),
+            Encoding::Latin1 => Cow::Owned(bytes.iter().map(|&byte| byte as char).collect()),
+            Encoding::Auto | Encoding::Windows1252 => Cow::Owned(bytes.iter().map(|&byte| windows_1252(byte)).collect()),
+        }
+    }
+}
+
+fn windows_1252(byte: u8) -> char {
+    match byte {
+        0x80..=0x9F => WINDOWS_1252_HIGH[(byte - 0x80) as usize],
+        byte => byte as char,
+    }
+}
+
+/// Converts the contents of a .RAW file to UTF-8, line by line
+///
+/// Returns the converted contents along with the (0-based) lines that were not valid UTF-8 and had their
+/// invalid bytes replaced, which only happens when reading as [`Encoding::Utf8`] in lenient mode.
+/// In strict mode those lines are left for the component parsers to fail on, so the error names the section.
+pub(crate) fn to_utf8(bytes: &[u8], encoding: Encoding, mode: ParseMode) -> (Cow<'_, [u8]>, Vec<usize>) {
+    //Nothing to convert in plain ASCII files, or valid UTF-8 read as UTF-8
+    let utf8: bool = matches!(encoding, Encoding::Auto | Encoding::Utf8) && from_utf8(bytes).is_ok();
+    if bytes.is_ascii() || utf8 || (encoding == Encoding::Utf8 && mode == ParseMode::Strict) {
+        return (Cow::Borrowed(bytes), Vec::new());
+    }
+    let mut text: String = String::with_capacity(bytes.len() + bytes.len() / 8);
+    let mut replaced: Vec<usize> = Vec::new();
+    for (index, line) in split_lines(bytes).into_iter().enumerate() {
+        if index > 0 {
+            text.push('\n');
+        }
+        if encoding == Encoding::Utf8 && from_utf8(line).is_err() {
+            replaced.push(index);
+        }
+        text.push_str(&encoding.decode(line));
+    }
+    (Cow::Owned(text.into_bytes()), replaced)
+}
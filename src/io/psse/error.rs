@@ -0,0 +1,201 @@
+use std::{error::Error, fmt, io};
+
+use super::pssedata::PSSEDatatype;
+
+/// Errors that can occur while reading a PSS/E .RAW case
+///
+/// Every variant that refers to the file contents carries the absolute (1-based) line number
+/// in the .RAW file along with the section being parsed, so errors point to the exact record.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The file could not be opened or read
+    Io(io::Error),
+    /// A field contained text that could not be converted to the expected type
+    InvalidField {
+        line: usize,
+        section: PSSEDatatype,
+        field: &'static str,
+        text: String,
+    },
+    /// A field required to build the record was not present on the line
+    MissingField {
+        line: usize,
+        section: PSSEDatatype,
+        field: &'static str,
+    },
+    /// A multi-line record (transformers, DC lines, ...) ended before all of its lines were read
+    TruncatedRecord {
+        line: usize,
+        section: PSSEDatatype,
+        expected_lines: usize,
+    },
+    /// A line could not be decoded as text
+    InvalidEncoding {
+        line: usize,
+        section: PSSEDatatype,
+    },
+    /// A .RAWX record refers to more rows of another table than the record can hold
+    UnreadRows {
+        line: usize,
+        section: PSSEDatatype,
+        table: &'static str,
+        rows: usize,
+    },
+    /// A .RAWX file is not valid JSON
+    Json(serde_json::Error),
+    /// A .RAWX table is not laid out as a `fields` list of names and a `data` list of rows
+    InvalidTable {
+        table: String,
+    },
+    /// A file given as a change case is a base case (IC = 0)
+    NotChangeFile {
+        ic: i8,
+    },
+}
+
+impl ParseError {
+    /// The absolute line number in the .RAW file the error refers to, if any
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            ParseError::Io(_) | ParseError::Json(_) | ParseError::InvalidTable { .. } | ParseError::NotChangeFile { .. } => None,
+            ParseError::InvalidField { line, .. }
+            | ParseError::MissingField { line, .. }
+            | ParseError::TruncatedRecord { line, .. }
+            | ParseError::InvalidEncoding { line, .. }
+            | ParseError::UnreadRows { line, .. } => Some(*line),
+        }
+    }
+
+    /// The section of the .RAW file the error refers to, if any
+    pub fn section(&self) -> Option<PSSEDatatype> {
+        match self {
+            ParseError::Io(_) | ParseError::Json(_) | ParseError::InvalidTable { .. } | ParseError::NotChangeFile { .. } => None,
+            ParseError::InvalidField { section, .. }
+            | ParseError::MissingField { section, .. }
+            | ParseError::TruncatedRecord { section, .. }
+            | ParseError::InvalidEncoding { section, .. }
+            | ParseError::UnreadRows { section, .. } => Some(*section),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(err) => write!(f, "{}", err),
+            ParseError::InvalidField { line, section, field, text } => {
+                write!(f, "line {} ({:?} data): invalid value '{}' for field {}", line, section, text, field)
+            }
+            ParseError::MissingField { line, section, field } => {
+                write!(f, "line {} ({:?} data): missing field {}", line, section, field)
+            }
+            ParseError::TruncatedRecord { line, section, expected_lines } => {
+                write!(f, "line {} ({:?} data): record ended early, expected {} lines", line, section, expected_lines)
+            }
+            ParseError::InvalidEncoding { line, section } => {
+                write!(f, "line {} ({:?} data): line is not valid UTF-8", line, section)
+            }
+            ParseError::UnreadRows { line, section, table, rows } => {
+                write!(f, "line {} ({:?} data): {} rows of table '{}' for the record, which holds only one", line, section, rows, table)
+            }
+            ParseError::Json(err) => write!(f, "invalid RAWX file: {}", err),
+            ParseError::InvalidTable { table } => write!(f, "RAWX table '{}' is not a list of fields and rows of data", table),
+            ParseError::NotChangeFile { ic } => write!(f, "not a change case, IC is {} instead of 1", ic),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::Io(err) => Some(err),
+            ParseError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ParseError {
+    fn from(err: io::Error) -> Self {
+        ParseError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(err: serde_json::Error) -> Self {
+        ParseError::Json(err)
+    }
+}
+
+/// A problem found while parsing
+///
+/// In lenient mode the parser keeps going with the PSS/E default for the affected field and
+/// records what was substituted so the caller can decide whether the case is usable.
+/// Section layout problems (a section missing from its expected place, an unrecognized section name)
+/// do not stop the parse and are recorded in either mode.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// ## Absolute (1-based) line number in the .RAW file
+    pub line: usize,
+    /// ## Section of the .RAW file being parsed
+    pub section: PSSEDatatype,
+    /// ## What was wrong with the record
+    pub kind: DiagnosticKind,
+}
+
+/// The kind of problem recorded by a [`Diagnostic`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticKind {
+    /// The field text could not be converted, so the default was used
+    InvalidField { field: &'static str, text: String },
+    /// The field was not present on the line, so the default was used
+    MissingField { field: &'static str },
+    /// The record ended early, the missing lines were treated as blank
+    TruncatedRecord { expected_lines: usize },
+    /// The line could not be decoded, so it was treated as blank
+    InvalidEncoding,
+    /// The line was not valid UTF-8, so its invalid bytes were replaced with U+FFFD
+    ReplacedEncoding,
+    /// The .RAWX record refers to more rows of another table than it can hold, so only the first was read
+    UnreadRows { table: &'static str, rows: usize },
+    /// The section named by the `begin ... data` comment is not the one expected by the revision's section order
+    SectionMismatch { expected: Option<PSSEDatatype> },
+    /// The `begin ... data` comment did not name a known section, so the section order was used
+    UnrecognizedSection { text: String },
+}
+
+impl Diagnostic {
+    /// Converts a parse error into the diagnostic recorded for it in lenient mode
+    ///
+    /// Returns the error back if it does not refer to a location in the file
+    pub(crate) fn from_error(err: ParseError) -> Result<Self, ParseError> {
+        let (line, section, kind) = match err {
+            ParseError::InvalidField { line, section, field, text } => (line, section, DiagnosticKind::InvalidField { field, text }),
+            ParseError::MissingField { line, section, field } => (line, section, DiagnosticKind::MissingField { field }),
+            ParseError::TruncatedRecord { line, section, expected_lines } => (line, section, DiagnosticKind::TruncatedRecord { expected_lines }),
+            ParseError::InvalidEncoding { line, section } => (line, section, DiagnosticKind::InvalidEncoding),
+            ParseError::UnreadRows { line, section, table, rows } => (line, section, DiagnosticKind::UnreadRows { table, rows }),
+            err => return Err(err),
+        };
+        Ok(Diagnostic { line, section, kind })
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {} ({:?} data): ", self.line, self.section)?;
+        match &self.kind {
+            DiagnosticKind::InvalidField { field, text } => write!(f, "invalid value '{}' for field {}, default used", text, field),
+            DiagnosticKind::MissingField { field } => write!(f, "missing field {}, default used", field),
+            DiagnosticKind::TruncatedRecord { expected_lines } => write!(f, "record ended early, expected {} lines", expected_lines),
+            DiagnosticKind::InvalidEncoding => write!(f, "line is not valid UTF-8, treated as blank"),
+            DiagnosticKind::ReplacedEncoding => write!(f, "line is not valid UTF-8, invalid bytes replaced"),
+            DiagnosticKind::UnreadRows { table, rows } => write!(f, "{} rows of table '{}' for the record, only the first read", rows, table),
+            DiagnosticKind::SectionMismatch { expected: Some(expected) } => write!(f, "section named in the comment, expected {:?} data", expected),
+            DiagnosticKind::SectionMismatch { expected: None } => write!(f, "section named in the comment, expected no more sections"),
+            DiagnosticKind::UnrecognizedSection { text } => write!(f, "unrecognized section '{}', assumed from the section order", text),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use super::{components::structs::*, pssedata::PSSEData};
+
+/// Natural-key lookups into a [`PSSEData`] case
+///
+/// Built once with [`PSSEData::index`], the index answers lookups by bus number, buses and circuit
+/// without scanning the component vectors. It borrows the case, so the case cannot be modified while it is in use.
+/// When a key appears more than once in the case the first record is returned.
+///
+/// ```no_run
+/// use pf_gat_parser::prelude::*;
+///
+/// let case: PSSEData = read_raw("case.raw").unwrap();
+/// let index: CaseIndex = case.index();
+/// let branch: Option<&Branch> = index.branch(876543, 347, "1");
+/// let load_mw: f64 = index.loads_at(347).map(|load| load.pl_mw).sum();
+/// ```
+#[derive(Debug, Clone)]
+pub struct CaseIndex<'a> {
+    data: &'a PSSEData,
+    buses: HashMap<i32, usize>,
+    //Keyed by the lower then the higher bus number
+    branches: HashMap<(i32, i32), Vec<usize>>,
+    transformers: HashMap<(i32, i32, i32), Vec<usize>>,
+    loads: HashMap<i32, Vec<usize>>,
+    generators: HashMap<i32, Vec<usize>>,
+    fixed_shunts: HashMap<i32, Vec<usize>>,
+    switched_shunts: HashMap<i32, Vec<usize>>,
+}
+
+impl PSSEData {
+    /// Builds a [`CaseIndex`] for looking up records by their natural keys
+    pub fn index(&self) -> CaseIndex<'_> {
+        CaseIndex::new(self)
+    }
+}
+
+impl<'a> CaseIndex<'a> {
+    /// Indexes the buses, branches, transformers, loads, generators and shunts of `data`
+    pub fn new(data: &'a PSSEData) -> Self {
+        let mut buses: HashMap<i32, usize> = HashMap::with_capacity(data.buses.len());
+        for (position, bus) in data.buses.iter().enumerate() {
+            buses.entry(bus.id).or_insert(position);
+        }
+        CaseIndex {
+            data,
+            buses,
+            branches: group(&data.branches, |branch| (branch.from_bus.min(branch.to_bus), branch.from_bus.max(branch.to_bus))),
+            transformers: group(&data.transformers, |transformer| (transformer.from_bus, transformer.to_bus, transformer.tertiary_bus)),
+            loads: group(&data.loads, |load| load.bus_id),
+            generators: group(&data.generators, |generator| generator.bus_id),
+            fixed_shunts: group(&data.fixed_shunts, |shunt| shunt.bus_id),
+            switched_shunts: group(&data.switched_shunts, |shunt| shunt.bus_id),
+        }
+    }
+
+    /// The bus numbered `id`
+    pub fn bus(&self, id: i32) -> Option<&'a Bus> {
+        self.bus_index(id).map(|position| &self.data.buses[position])
+    }
+
+    /// The position of bus `id` in [`PSSEData::buses`]
+    pub fn bus_index(&self, id: i32) -> Option<usize> {
+        self.buses.get(&id).copied()
+    }
+
+    /// The branch between buses `from` and `to` with circuit `ckt`, given from either end
+    pub fn branch(&self, from: i32, to: i32, ckt: &str) -> Option<&'a Branch> {
+        find(&self.branches, &(from.min(to), from.max(to)), &self.data.branches, |branch| branch.circuit == ckt.trim())
+    }
+
+    /// The transformer between buses `i`, `j` and `k` with circuit `ckt`, `k` being 0 for two-winding transformers
+    pub fn transformer(&self, i: i32, j: i32, k: i32, ckt: &str) -> Option<&'a Transformer> {
+        find(&self.transformers, &(i, j, k), &self.data.transformers, |transformer| transformer.circuit == ckt.trim())
+    }
+
+    /// The loads at bus `bus`
+    pub fn loads_at(&self, bus: i32) -> impl Iterator<Item = &'a Load> + '_ {
+        at(&self.loads, bus, &self.data.loads)
+    }
+
+    /// The generators at bus `bus`
+    pub fn generators_at(&self, bus: i32) -> impl Iterator<Item = &'a Generator> + '_ {
+        at(&self.generators, bus, &self.data.generators)
+    }
+
+    /// The fixed shunts at bus `bus`
+    pub fn shunts_at(&self, bus: i32) -> impl Iterator<Item = &'a FixedShunt> + '_ {
+        at(&self.fixed_shunts, bus, &self.data.fixed_shunts)
+    }
+
+    /// The switched shunts at bus `bus`
+    pub fn switched_shunts_at(&self, bus: i32) -> impl Iterator<Item = &'a SwitchedShunt> + '_ {
+        at(&self.switched_shunts, bus, &self.data.switched_shunts)
+    }
+}
+
+fn group<T, K: Eq + std::hash::Hash>(records: &[T], key: impl Fn(&T) -> K) -> HashMap<K, Vec<usize>> {
+    let mut groups: HashMap<K, Vec<usize>> = HashMap::new();
+    for (position, record) in records.iter().enumerate() {
+        groups.entry(key(record)).or_default().push(position);
+    }
+    groups
+}
+
+fn find<'a, T, K: Eq + std::hash::Hash>(groups: &HashMap<K, Vec<usize>>, key: &K, records: &'a [T], matches: impl Fn(&T) -> bool) -> Option<&'a T> {
+    groups.get(key)?.iter().map(|&position| &records[position]).find(|record| matches(record))
+}
+
+fn at<'a: 'b, 'b, T>(groups: &'b HashMap<i32, Vec<usize>>, bus: i32, records: &'a [T]) -> impl Iterator<Item = &'a T> + 'b {
+    groups.get(&bus).into_iter().flatten().map(move |&position| &records[position])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::psse::{error::Diagnostic, options::ParseOptions, parsers::fast_parser::parse_fast, pssedata::SourceMap};
+
+    use super::*;
+
+    #[test]
+    fn index_v33() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(format!("{}/test-cases/TEST_parse_psse_v33.RAW", dir), &ParseOptions::strict()).unwrap();
+        let index: CaseIndex = data.index();
+        assert_eq!(index.bus(347).unwrap().name, "TESTBUS".to_string());
+        assert_eq!(index.bus_index(347), Some(0));
+        assert!(index.bus(1).is_none());
+        //Branches are found from either end, with or without the padding of the circuit
+        assert_eq!(index.branch(347, 876543, "1"), data.branches.first());
+        assert_eq!(index.branch(876543, 347, "1 "), data.branches.first());
+        assert!(index.branch(347, 876543, "2").is_none());
+        assert_eq!(index.transformer(24568, 24569, 0, "1"), data.transformers.first());
+        assert!(index.transformer(24569, 24568, 0, "1").is_none());
+        assert_eq!(index.loads_at(347).count(), 1);
+        assert_eq!(index.generators_at(24568).next(), data.generators.first());
+        assert_eq!(index.shunts_at(20987).count(), 1);
+        assert_eq!(index.loads_at(1).count(), 0);
+    }
+}
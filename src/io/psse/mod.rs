@@ -1,3 +1,14 @@
+pub mod change;
+pub mod conversion;
+pub mod encoding;
+pub mod error;
+pub mod index;
+pub mod options;
 pub mod parsers;
+pub mod plausibility;
 pub mod pssedata;
+pub mod record;
+pub mod tokenizer;
+pub mod validation;
+pub mod writers;
 pub mod components;
\ No newline at end of file
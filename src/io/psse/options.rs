@@ -0,0 +1,34 @@
+use super::encoding::Encoding;
+
+/// How the parser reacts to malformed records
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Abort on the first invalid, missing or truncated field with a [`ParseError`](super::error::ParseError)
+    #[default]
+    Strict,
+    /// Substitute the PSS/E default for any invalid or missing field and record a [`Diagnostic`](super::error::Diagnostic)
+    Lenient,
+}
+
+/// Options controlling how a .RAW case is parsed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseOptions {
+    /// ## Strict or lenient handling of malformed records
+    /// ### Default: Strict
+    pub mode: ParseMode,
+    /// ## Character encoding of the file
+    /// ### Default: Auto (UTF-8, falling back to Windows-1252 line by line)
+    pub encoding: Encoding,
+}
+
+impl ParseOptions {
+    /// Options that abort on the first malformed record
+    pub fn strict() -> Self {
+        ParseOptions { mode: ParseMode::Strict, ..Default::default() }
+    }
+
+    /// Options that fall back to defaults and collect diagnostics instead of failing
+    pub fn lenient() -> Self {
+        ParseOptions { mode: ParseMode::Lenient, ..Default::default() }
+    }
+}
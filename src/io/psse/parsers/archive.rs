@@ -0,0 +1,107 @@
+use std::{fs::File, io::{self, BufReader, Read, Seek}, path::Path};
+
+use flate2::read::MultiGzDecoder;
+use ruzstd::decoding::StreamingDecoder;
+use zip::ZipArchive;
+
+use crate::io::psse::{error::{Diagnostic, ParseError}, options::ParseOptions, parsers::fast_parser::{parse_fast, parse_reader}, pssedata::{PSSEData, SourceMap}};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+const ZIP_MAGIC: [u8; 4] = [b'P', b'K', 0x03, 0x04];
+
+/// Parses a PSS/E .RAW case that may be stored compressed into a [`PSSEData`] struct
+///
+/// The compression is detected from the first bytes of the file rather than its extension:
+/// * gzip (`.raw.gz`) and zstd (`.raw.zst`) files are decompressed while being read
+/// * zip archives are searched for their first member ending in `.raw`, skipping the `.dyr`, `.seq`, ... files bundled with it
+/// * anything else is read as a plain .RAW file with [`parse_fast`]
+///
+/// ```no_run
+/// use pf_gat_parser::prelude::*;
+///
+/// let (case, _, _) = open_case("case.raw.gz", &ParseOptions::strict()).unwrap();
+/// println!("{} buses", case.buses.len());
+/// ```
+pub fn open_case<P: AsRef<Path>>(filepath: P, options: &ParseOptions) -> Result<(PSSEData, SourceMap, Vec<Diagnostic>), ParseError> {
+    let mut file: File = File::open(&filepath)?;
+    let mut magic: Vec<u8> = Vec::with_capacity(ZIP_MAGIC.len());
+    (&mut file).take(ZIP_MAGIC.len() as u64).read_to_end(&mut magic)?;
+    file.rewind()?;
+
+    if magic.starts_with(&GZIP_MAGIC) {
+        parse_reader(MultiGzDecoder::new(BufReader::new(file)), options)
+    } else if magic == ZSTD_MAGIC {
+        let decoder = StreamingDecoder::new(BufReader::new(file)).map_err(invalid_data)?;
+        parse_reader(decoder, options)
+    } else if magic == ZIP_MAGIC {
+        parse_zip_member(file, options)
+    } else {
+        parse_fast(filepath, options)
+    }
+}
+
+fn parse_zip_member(file: File, options: &ParseOptions) -> Result<(PSSEData, SourceMap, Vec<Diagnostic>), ParseError> {
+    let mut archive: ZipArchive<BufReader<File>> = ZipArchive::new(BufReader::new(file)).map_err(invalid_data)?;
+    let index: usize = (0..archive.len())
+        .find(|&index| matches!(archive.name_for_index(index), Some(Ok(name)) if name.to_lowercase().ends_with(".raw")))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "the zip archive has no .raw member"))?;
+    let member = archive.by_index(index).map_err(invalid_data)?;
+    parse_reader(member, options)
+}
+
+fn invalid_data<E: std::error::Error + Send + Sync + 'static>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::*;
+
+    fn open_written(name: &str, contents: &[u8]) -> Result<(PSSEData, SourceMap, Vec<Diagnostic>), ParseError> {
+        let filepath = std::env::temp_dir().join(format!("pf_gat_parser_{}", name));
+        std::fs::write(&filepath, contents).unwrap();
+        let parsed = open_case(&filepath, &ParseOptions::strict());
+        std::fs::remove_file(&filepath).unwrap();
+        parsed
+    }
+
+    #[test]
+    fn open_compressed_cases() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let filepath: String = format!("{}/test-cases/TEST_parse_psse_v35.RAW", dir);
+        let raw: Vec<u8> = std::fs::read(&filepath).unwrap();
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::strict()).unwrap();
+        assert_eq!(open_case(&filepath, &ParseOptions::strict()).unwrap().0, data);
+
+        let mut gzip: GzEncoder<Vec<u8>> = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(&raw).unwrap();
+        assert_eq!(open_written("case.raw.gz", &gzip.finish().unwrap()).unwrap().0, data);
+
+        let mut zstd: Vec<u8> = Vec::new();
+        ruzstd::encoding::compress(raw.as_slice(), &mut zstd, ruzstd::encoding::CompressionLevel::Fastest);
+        assert_eq!(open_written("case.raw.zst", &zstd).unwrap().0, data);
+
+        //The .raw member is picked out of the other files of the case
+        let mut zip: ZipWriter<io::Cursor<Vec<u8>>> = ZipWriter::new(io::Cursor::new(Vec::new()));
+        zip.start_file("case/case.dyr", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"101 'GENCLS' 1 6.0 0.0 /").unwrap();
+        zip.start_file("case/case.RAW", SimpleFileOptions::default()).unwrap();
+        zip.write_all(&raw).unwrap();
+        assert_eq!(open_written("case.zip", &zip.finish().unwrap().into_inner()).unwrap().0, data);
+    }
+
+    #[test]
+    fn zip_without_raw_member() {
+        let mut zip: ZipWriter<io::Cursor<Vec<u8>>> = ZipWriter::new(io::Cursor::new(Vec::new()));
+        zip.start_file("case.seq", SimpleFileOptions::default()).unwrap();
+        zip.write_all(b"0 / END OF POSITIVE SEQUENCE DATA").unwrap();
+        let err: ParseError = open_written("no_raw.zip", &zip.finish().unwrap().into_inner()).unwrap_err();
+        assert!(matches!(err, ParseError::Io(ref err) if err.kind() == io::ErrorKind::NotFound), "{}", err);
+    }
+}
@@ -0,0 +1,159 @@
+use std::str::from_utf8;
+
+use crate::io::psse::{pssedata::PSSEDatatype, tokenizer::Tokenizer};
+
+/// Positions of the columns read by the parser on each line of a record in the file being read
+///
+/// `columns[line][index]` is where the column `index` of the record's line `line` sits on the line,
+/// or `None` when the file does not have that column.
+pub(crate) type ColumnMap = Vec<Vec<Option<usize>>>;
+
+/// Columns of a record located by name in V36 cases
+struct Layout {
+    /// The V35 columns of each line followed by those added in V36, in the order of the indices the parser reads
+    columns: &'static [&'static [&'static str]],
+    /// The columns of each line in the order of V36 files, used to read the lines without a heading by position
+    v36: &'static [&'static [&'static str]],
+}
+
+const BUS: Layout = Layout {
+    columns: &[&["I", "NAME", "BASKV", "IDE", "AREA", "ZONE", "OWNER", "VM", "VA", "NVHI", "NVLO", "EVHI", "EVLO", "LONGNAME"]],
+    v36: &[&["I", "NAME", "LONGNAME", "BASKV", "IDE", "AREA", "ZONE", "OWNER", "VM", "VA", "NVHI", "NVLO", "EVHI", "EVLO"]],
+};
+
+const LOAD: Layout = Layout {
+    columns: &[&[
+        "I", "ID", "STAT", "AREA", "ZONE", "PL", "QL", "IP", "IQ", "YP", "YQ", "OWNER", "SCALE", "INTRPT", "DGENP", "DGENQ", "DGENM", "LOADTYPE", "PRIORITY",
+    ]],
+    v36: &[&[
+        "I", "ID", "STAT", "PRIORITY", "AREA", "ZONE", "PL", "QL", "IP", "IQ", "YP", "YQ", "OWNER", "SCALE", "INTRPT", "DGENP", "DGENQ", "DGENM", "LOADTYPE",
+    ]],
+};
+
+const GENERATOR: Layout = Layout {
+    columns: &[&[
+        "I", "ID", "PG", "QG", "QT", "QB", "VS", "IREG", "NREG", "MBASE", "ZR", "ZX", "RT", "XT", "GTAP", "STAT", "RMPCT", "PT", "PB", "BASLOD",
+        "O1", "F1", "O2", "F2", "O3", "F3", "O4", "F4", "WMOD", "WPF", "GENTYPE",
+    ]],
+    v36: &[&[
+        "I", "ID", "GENTYPE", "PG", "QG", "QT", "QB", "VS", "IREG", "NREG", "MBASE", "ZR", "ZX", "RT", "XT", "GTAP", "STAT", "RMPCT", "PT", "PB", "BASLOD",
+        "O1", "F1", "O2", "F2", "O3", "F3", "O4", "F4", "WMOD", "WPF",
+    ]],
+};
+
+const BRANCH: Layout = Layout {
+    columns: &[&[
+        "I", "J", "CKT", "R", "X", "B", "NAME", "RATE1", "RATE2", "RATE3", "RATE4", "RATE5", "RATE6", "RATE7", "RATE8", "RATE9", "RATE10", "RATE11", "RATE12",
+        "GI", "BI", "GJ", "BJ", "STAT", "MET", "LEN", "O1", "F1", "O2", "F2", "O3", "F3", "O4", "F4", "BRTYPE",
+    ]],
+    v36: &[&[
+        "I", "J", "CKT", "BRTYPE", "R", "X", "B", "NAME", "RATE1", "RATE2", "RATE3", "RATE4", "RATE5", "RATE6", "RATE7", "RATE8", "RATE9", "RATE10", "RATE11", "RATE12",
+        "GI", "BI", "GJ", "BJ", "STAT", "MET", "LEN", "O1", "F1", "O2", "F2", "O3", "F3", "O4", "F4",
+    ]],
+};
+
+const TRANSFORMER: Layout = Layout {
+    columns: &[
+        &["I", "J", "K", "CKT", "CW", "CZ", "CM", "MAG1", "MAG2", "NMETR", "NAME", "STAT", "O1", "F1", "O2", "F2", "O3", "F3", "O4", "F4", "VECGRP", "ZCOD", "DESC"],
+        TRANSFORMER_IMPEDANCES,
+        &[
+            "WINDV1", "NOMV1", "ANG1", "WDG1RATE1", "WDG1RATE2", "WDG1RATE3", "WDG1RATE4", "WDG1RATE5", "WDG1RATE6", "WDG1RATE7", "WDG1RATE8", "WDG1RATE9",
+            "WDG1RATE10", "WDG1RATE11", "WDG1RATE12", "COD1", "CONT1", "NODE1", "RMA1", "RMI1", "VMA1", "VMI1", "NTP1", "TAB1", "CR1", "CX1", "CNXA1", "TAPSTEP1",
+        ],
+        &[
+            "WINDV2", "NOMV2", "ANG2", "WDG2RATE1", "WDG2RATE2", "WDG2RATE3", "WDG2RATE4", "WDG2RATE5", "WDG2RATE6", "WDG2RATE7", "WDG2RATE8", "WDG2RATE9",
+            "WDG2RATE10", "WDG2RATE11", "WDG2RATE12", "COD2", "CONT2", "NODE2", "RMA2", "RMI2", "VMA2", "VMI2", "NTP2", "TAB2", "CR2", "CX2", "CNXA2", "TAPSTEP2",
+        ],
+        &[
+            "WINDV3", "NOMV3", "ANG3", "WDG3RATE1", "WDG3RATE2", "WDG3RATE3", "WDG3RATE4", "WDG3RATE5", "WDG3RATE6", "WDG3RATE7", "WDG3RATE8", "WDG3RATE9",
+            "WDG3RATE10", "WDG3RATE11", "WDG3RATE12", "COD3", "CONT3", "NODE3", "RMA3", "RMI3", "VMA3", "VMI3", "NTP3", "TAB3", "CR3", "CX3", "CNXA3", "TAPSTEP3",
+        ],
+    ],
+    v36: &[
+        &["I", "J", "K", "CKT", "CW", "CZ", "CM", "MAG1", "MAG2", "NMETR", "NAME", "DESC", "STAT", "O1", "F1", "O2", "F2", "O3", "F3", "O4", "F4", "VECGRP", "ZCOD"],
+        TRANSFORMER_IMPEDANCES,
+        &[
+            "WINDV1", "NOMV1", "ANG1", "WDG1RATE1", "WDG1RATE2", "WDG1RATE3", "WDG1RATE4", "WDG1RATE5", "WDG1RATE6", "WDG1RATE7", "WDG1RATE8", "WDG1RATE9",
+            "WDG1RATE10", "WDG1RATE11", "WDG1RATE12", "COD1", "CONT1", "NODE1", "RMA1", "RMI1", "VMA1", "VMI1", "NTP1", "TAPSTEP1", "TAB1", "CR1", "CX1", "CNXA1",
+        ],
+        &[
+            "WINDV2", "NOMV2", "ANG2", "WDG2RATE1", "WDG2RATE2", "WDG2RATE3", "WDG2RATE4", "WDG2RATE5", "WDG2RATE6", "WDG2RATE7", "WDG2RATE8", "WDG2RATE9",
+            "WDG2RATE10", "WDG2RATE11", "WDG2RATE12", "COD2", "CONT2", "NODE2", "RMA2", "RMI2", "VMA2", "VMI2", "NTP2", "TAPSTEP2", "TAB2", "CR2", "CX2", "CNXA2",
+        ],
+        &[
+            "WINDV3", "NOMV3", "ANG3", "WDG3RATE1", "WDG3RATE2", "WDG3RATE3", "WDG3RATE4", "WDG3RATE5", "WDG3RATE6", "WDG3RATE7", "WDG3RATE8", "WDG3RATE9",
+            "WDG3RATE10", "WDG3RATE11", "WDG3RATE12", "COD3", "CONT3", "NODE3", "RMA3", "RMI3", "VMA3", "VMI3", "NTP3", "TAPSTEP3", "TAB3", "CR3", "CX3", "CNXA3",
+        ],
+    ],
+};
+
+const TRANSFORMER_IMPEDANCES: &[&str] = &["R1-2", "X1-2", "SBASE1-2", "R2-3", "X2-3", "SBASE2-3", "R3-1", "X3-1", "SBASE3-1", "VMSTAR", "ANSTAR"];
+
+//V36 inserted columns in the middle of the bus, load, generator, branch and transformer records
+fn layout(datatype: PSSEDatatype) -> Option<&'static Layout> {
+    match datatype {
+        PSSEDatatype::Bus => Some(&BUS),
+        PSSEDatatype::Load => Some(&LOAD),
+        PSSEDatatype::Generator => Some(&GENERATOR),
+        PSSEDatatype::Branch => Some(&BRANCH),
+        PSSEDatatype::Transformer => Some(&TRANSFORMER),
+        _ => None,
+    }
+}
+
+/// Locates the columns of each record line of a V36 section in its `@!` heading lines
+///
+/// The n-th heading line describes the n-th line of the records. The lines without a heading are read by position
+/// in the V36 column order. Returns `None` for the sections whose columns did not move in V36, which are read by position.
+pub(crate) fn column_map(datatype: PSSEDatatype, headings: &[&[u8]]) -> Option<ColumnMap> {
+    let layout: &Layout = layout(datatype)?;
+    Some(layout.columns.iter().zip(layout.v36).enumerate().map(|(line, (columns, v36))| {
+        let names: Vec<String> = match headings.get(line).and_then(|heading| from_utf8(heading).ok()) {
+            Some(heading) => Tokenizer::new(heading.trim_start().trim_start_matches('@').trim_start_matches('!')).map(normalize).collect(),
+            None => v36.iter().map(|name| name.to_string()).collect(),
+        };
+        columns.iter().map(|column| names.iter().position(|name| name == column)).collect()
+    }).collect())
+}
+
+//Headings pad and space out names inside their quotes ('N A M E')
+fn normalize(name: &str) -> String {
+    name.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_uppercase).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_located_by_name() {
+        let headings: [&[u8]; 1] = [b"@!   I,'ID',STAT,  ZONE,   AREA, 'N E W',     PL,        QL"];
+        let columns: ColumnMap = column_map(PSSEDatatype::Load, &headings).unwrap();
+        assert_eq!(columns.len(), 1);
+        assert_eq!(columns[0][..7], [Some(0), Some(1), Some(2), Some(4), Some(3), Some(6), Some(7)]);
+        assert_eq!(columns[0][7], None);
+
+        //Each heading line maps the matching line of the record, the others are read in the V36 order
+        let headings: [&[u8]; 2] = [b"@! I, J, K, 'CKT'", b"@! R1-2, X1-2"];
+        let columns: ColumnMap = column_map(PSSEDatatype::Transformer, &headings).unwrap();
+        assert_eq!(columns.len(), 5);
+        assert_eq!(columns[1][..3], [Some(0), Some(1), None]);
+        assert_eq!(columns[2][22..], [Some(22), Some(24), Some(25), Some(26), Some(27), Some(23)]);
+        assert_eq!(column_map(PSSEDatatype::Area, &headings), None);
+    }
+
+    #[test]
+    fn layouts_cover_v36_columns() {
+        //Every V36 column is read, and every column read is in the V36 order
+        for datatype in [PSSEDatatype::Bus, PSSEDatatype::Load, PSSEDatatype::Generator, PSSEDatatype::Branch, PSSEDatatype::Transformer] {
+            let layout: &Layout = layout(datatype).unwrap();
+            for (columns, v36) in layout.columns.iter().zip(layout.v36) {
+                let mut sorted: Vec<&str> = columns.to_vec();
+                let mut order: Vec<&str> = v36.to_vec();
+                sorted.sort_unstable();
+                order.sort_unstable();
+                assert_eq!(sorted, order, "{:?}", datatype);
+            }
+        }
+    }
+}
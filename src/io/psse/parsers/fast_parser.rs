@@ -1,187 +1,242 @@
-use std::{collections::HashMap, fs::File, io, str::from_utf8};
+use std::{borrow::Cow, collections::HashMap, fs::File, io::Read, path::Path, str::from_utf8};
 
 use memmap2::Mmap;
 
-use crate::io::psse::{components::{area::parse_areas, branch::parse_lines, bus::parse_buses, facts::parse_facts, fixed_shunt::parse_fixedshunts, generator::parse_generators, header::HeaderInfo, impedance_correction::parse_impedance_correction_table, interarea_transfer::parse_area_transfers, load::parse_loads, multisection_line::parse_multisection_lines, multiterminal_dc_line::parse_multiterminal_dc_line, owner::parse_owners, system_switching_device::parse_system_switching_device, transformer::parse_transformers, two_terminal_dc::parse_two_terminal_dc_line, vsc_dc::parse_vsc_dc_line, zone::parse_zones}, pssedata::PSSEData};
-
-/*
-******* Section Encodings *******
-0: Header
-1: Bus
-2: Load
-3: Fixed Shunt
-4: Generator
-5: Branch (Lines) (Non-Transformer Branch)
-6: Transformer
-7: Area (Area Interchange)
-*/
-
-pub fn parse_fast(filepath: &str) -> Result<PSSEData, io::Error>  {
+use crate::io::psse::{components::{area::parse_areas, branch::parse_lines, bus::{parse_bus_shunts, parse_buses}, facts::parse_facts, fixed_shunt::parse_fixedshunts, generator::parse_generators, gne_device::parse_gne_devices, header::HeaderInfo, impedance_correction::parse_impedance_correction_table, induction_machine::parse_induction_machines, interarea_transfer::parse_area_transfers, load::parse_loads, multisection_line::parse_multisection_lines, multiterminal_dc_line::parse_multiterminal_dc_line, owner::parse_owners, substation::parse_substations, switched_shunt::parse_switched_shunts, system_switching_device::parse_system_switching_device, transformer::parse_transformers, two_terminal_dc::parse_two_terminal_dc_line, vsc_dc::parse_vsc_dc_line, zone::parse_zones}, encoding::to_utf8, error::{Diagnostic, DiagnosticKind, ParseError}, options::ParseOptions, parsers::{columns::column_map, sections::{locate_sections, split_lines, Section}}, pssedata::{PSSEData, PSSEDatatype, SourceMap}, record::{Record, SectionContext}};
+
+/// Parses a PSS/E .RAW file into a [`PSSEData`] struct
+///
+/// The file is memory mapped and handed to [`parse_bytes`], see it for how the case is read.
+pub fn parse_fast<P: AsRef<Path>>(filepath: P, options: &ParseOptions) -> Result<(PSSEData, SourceMap, Vec<Diagnostic>), ParseError>  {
     let file: File = File::open(filepath)?;
     let mmap = unsafe { Mmap::map(&file)? };
+    parse_bytes(&mmap, options)
+}
 
-    let mut psse_data: PSSEData = PSSEData::default();
+/// Parses a PSS/E .RAW case read from any source (stdin, a socket, an archive entry) into a [`PSSEData`] struct
+///
+/// The whole case is read into memory and handed to [`parse_bytes`]. Prefer [`parse_fast`] for files on disk,
+/// which avoids the copy by memory mapping them.
+pub fn parse_reader<R: Read>(mut reader: R, options: &ParseOptions) -> Result<(PSSEData, SourceMap, Vec<Diagnostic>), ParseError> {
+    let mut bytes: Vec<u8> = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    parse_bytes(&bytes, options)
+}
 
-    //Finds section boundaries
-    let lines: Vec<&[u8]> = mmap.split(|&c| c == b'\n').collect();
-    let mut section_starts: HashMap<usize, usize> = HashMap::new();
-    let mut section_ends: HashMap<usize, usize> = HashMap::new();
-    let mut section_number: usize = 0;
-    let mut found_header: bool = false;
+/// Parses the contents of a PSS/E .RAW case held in memory into a [`PSSEData`] struct
+///
+/// Each section is located and handed off to its component parser.
+/// Sections are identified by the `begin ... data` comment on their terminator, falling back to the
+/// revision's section order (see [`section_order`](super::sections::section_order)) when it is missing.
+/// V36 bus, load, generator, branch and transformer fields are located by the names in the section's `@!`
+/// column headings, as V36 inserted columns in the middle of these records. A record line without a heading
+/// is read by position in the V36 column order.
+/// In strict mode the first malformed record aborts the parse, in lenient mode the defaults are used
+/// and every substitution is returned as a [`Diagnostic`] alongside the data.
+/// Sections found out of their expected place are reported as diagnostics in either mode.
+/// The case is returned with the [`SourceMap`] of the lines its records were read from.
+/// The records of change cases (IC = 1) may stop after the fields they change, and the fields each line gave
+/// are kept in the source map to tell them apart from the defaults.
+///
+/// ```
+/// use pf_gat_parser::prelude::*;
+///
+/// let raw: &[u8] = b"0, 100.0, 33, 0, 0, 60.0\n\n\n101, 'NORTH', 138.0, 3, 1, 1, 1, 1.0, 0.0\n0 / END OF BUS DATA\nQ\n";
+/// let (case, _, _) = parse_bytes(raw, &ParseOptions::strict()).unwrap();
+/// assert_eq!(case.buses[0].name, "NORTH");
+/// ```
+pub fn parse_bytes(bytes: &[u8], options: &ParseOptions) -> Result<(PSSEData, SourceMap, Vec<Diagnostic>), ParseError> {
+    let mut psse_data: PSSEData = PSSEData::default();
+    let mut source: SourceMap = SourceMap::default();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
 
+    let (bytes, replaced): (Cow<[u8]>, Vec<usize>) = to_utf8(bytes, options.encoding, options.mode);
+    //Finds the case identification, skipping any leading comments
+    let lines: Vec<&[u8]> = split_lines(&bytes);
+    let mut header_index: usize = 0;
+    let header_ctx: SectionContext = SectionContext::new(1, options);
     for (i, line_bytes) in lines.iter().enumerate() {
         if i > 5 { break; }
         if let Ok(line) = from_utf8(line_bytes) {
             let trimmed_line = line.trim();
             if !trimmed_line.is_empty() && !trimmed_line.starts_with("/") && !trimmed_line.starts_with("@") {
-                let parts: Vec<&str> = trimmed_line.split("/").nth(0).unwrap().split(",").map(|s| s.trim()).collect();
-                if parts.len() >= 4 && parts.len() <= 7 && !found_header {
+                let rec: Record = Record::new(trimmed_line, i + 1, PSSEDatatype::Header, &header_ctx);
+                if rec.len() >= 4 && rec.len() <= 7 {
                     psse_data.header = HeaderInfo {
-                        ic: parts[0].parse().unwrap_or(0),
-                        sbase: parts[1].parse().unwrap_or(100.0),
-                        revision: parts[2].parse().unwrap_or(33),
-                        transformer_rating_code: parts[3].parse().unwrap_or(0),
-                        branch_rating_code: parts[4].parse().unwrap_or(0),
-                        system_frequency: parts[5].parse().unwrap_or(60.0),
+                        ic: rec.required(0, "IC", 0)?,
+                        sbase: rec.required(1, "SBASE", 100.0)?,
+                        revision: rec.required(2, "REV", 33)?,
+                        transformer_rating_code: rec.required(3, "XFRRAT", 0)?,
+                        branch_rating_code: rec.optional(4, "NXFRAT", 0)?,
+                        system_frequency: rec.optional(5, "BASFRQ", 60.0)?,
                     };
-                    found_header = true;
-                    // Since V34+ have the leading /0 to indicate buses and also contain other junk at the start,
-                    // Skip the attempt to locate the bus section
-                    // Increase the section number to get to start the bus section
-                    section_starts.insert(section_number, i + 1);
-                    section_number += 1;
-                    if psse_data.header.revision >= 34 {
-                        break;
-                    }
-                // Specifically check for the bus section by looking for sections longer than 10 and starting with a numeric character
-                } else if (parts.len() >= 8) & (trimmed_line.chars().next().unwrap().is_numeric()) {
-                    section_starts.insert(section_number, i + 1);
-                    section_number += 1;
+                    header_index = i;
                     break;
                 }
             }
         }
     }
-    let mut found_section_start: bool = false;
-    for (i, line_bytes) in lines.iter().enumerate() {
-        if let Ok(line) = from_utf8(line_bytes) {
-            let trimmed_line = line.trim();
-            // Check if the line is a section start
-            if trimmed_line.starts_with("0 /") {
-                // If a section start has already been found, set the start and end to the same value
-                if found_section_start {
-                    section_starts.insert(section_number, i );
-                    section_number += 1;
+    diagnostics.extend(header_ctx.into_diagnostics());
+
+    let revision: i8 = psse_data.header.revision;
+    let header: HeaderInfo = psse_data.header.clone();
+    let sections: Vec<Section> = locate_sections(&lines, header_index, revision, &mut diagnostics);
+    //Lines whose invalid bytes were replaced are reported against the section holding them
+    for &index in &replaced {
+        let section: PSSEDatatype = sections.iter().find(|section| (section.start..section.end).contains(&index)).map_or(PSSEDatatype::Header, |section| section.datatype);
+        diagnostics.push(Diagnostic { line: index + 1, section, kind: DiagnosticKind::ReplacedEncoding });
+    }
+    let mut findings: Findings = Findings::default();
+    for section in sections {
+        let (buses, fixed_shunts, parsed): (usize, usize, usize) = (psse_data.buses.len(), psse_data.fixed_shunts.len(), section_len(&psse_data, section.datatype));
+        match section.datatype {
+            PSSEDatatype::Bus => {
+                psse_data.buses.extend(parse_section(&lines, &section, &header, options, &mut findings, |lines, ctx| parse_buses(lines, ctx, revision))?);
+                //V30 has no fixed shunt data, the shunts are part of the bus records
+                if revision <= 30 {
+                    psse_data.fixed_shunts.extend(parse_section(&lines, &section, &header, options, &mut findings, parse_bus_shunts)?);
                 }
-                // Sets the section end to the previous section and flags that a new section has been found
-                found_section_start = true;
-                section_ends.insert(section_number - 1, i);
-                continue;
-            }
-            // If a section start has been found, and the line is not a comment or another line start, add it to the section start
-            if found_section_start & !trimmed_line.starts_with("@") & !trimmed_line.starts_with("0 /") {
-                // Look specifically for lines that contain data after the section start
-                // then add it to the section start and revert the found_section_start variable
-                section_starts.insert(section_number, i );
-                section_number += 1;
-                found_section_start = false;
             }
+            PSSEDatatype::Load => psse_data.loads.extend(parse_section(&lines, &section, &header, options, &mut findings, |lines, ctx| parse_loads(lines, ctx, revision))?),
+            PSSEDatatype::FixedShunt => psse_data.fixed_shunts.extend(parse_section(&lines, &section, &header, options, &mut findings, parse_fixedshunts)?),
+            PSSEDatatype::Generator => psse_data.generators.extend(parse_section(&lines, &section, &header, options, &mut findings, |lines, ctx| parse_generators(lines, ctx, revision))?),
+            PSSEDatatype::Branch => psse_data.branches.extend(parse_section(&lines, &section, &header, options, &mut findings, |lines, ctx| parse_lines(lines, ctx, revision))?),
+            PSSEDatatype::SystemSwitchingDevice => psse_data.switching_devices.extend(parse_section(&lines, &section, &header, options, &mut findings, parse_system_switching_device)?),
+            PSSEDatatype::Transformer => psse_data.transformers.extend(parse_section(&lines, &section, &header, options, &mut findings, |lines, ctx| parse_transformers(lines, ctx, revision))?),
+            PSSEDatatype::Area => psse_data.areas.extend(parse_section(&lines, &section, &header, options, &mut findings, parse_areas)?),
+            PSSEDatatype::TwoTerminalDc => psse_data.two_terminal_dc.extend(parse_section(&lines, &section, &header, options, &mut findings, |lines, ctx| parse_two_terminal_dc_line(lines, ctx, revision))?),
+            PSSEDatatype::VscDc => psse_data.vsc_dc.extend(parse_section(&lines, &section, &header, options, &mut findings, |lines, ctx| parse_vsc_dc_line(lines, ctx, revision))?),
+            PSSEDatatype::ImpedanceCorrection => psse_data.impedance_correction.extend(parse_section(&lines, &section, &header, options, &mut findings, |lines, ctx| parse_impedance_correction_table(lines, ctx, revision))?),
+            PSSEDatatype::MultiTerminalDc => psse_data.multi_terminal_line.extend(parse_section(&lines, &section, &header, options, &mut findings, parse_multiterminal_dc_line)?),
+            PSSEDatatype::MultiSectionLine => psse_data.multi_section_line.extend(parse_section(&lines, &section, &header, options, &mut findings, parse_multisection_lines)?),
+            PSSEDatatype::Zone => psse_data.zones.extend(parse_section(&lines, &section, &header, options, &mut findings, parse_zones)?),
+            PSSEDatatype::InterAreaTransfer => psse_data.inter_area_transfer.extend(parse_section(&lines, &section, &header, options, &mut findings, parse_area_transfers)?),
+            PSSEDatatype::Owner => psse_data.owners.extend(parse_section(&lines, &section, &header, options, &mut findings, parse_owners)?),
+            PSSEDatatype::Facts => psse_data.facts.extend(parse_section(&lines, &section, &header, options, &mut findings, |lines, ctx| parse_facts(lines, ctx, revision))?),
+            PSSEDatatype::SwitchedShunt => psse_data.switched_shunts.extend(parse_section(&lines, &section, &header, options, &mut findings, |lines, ctx| parse_switched_shunts(lines, ctx, revision))?),
+            PSSEDatatype::GneDevice => psse_data.gne_devices.extend(parse_section(&lines, &section, &header, options, &mut findings, parse_gne_devices)?),
+            PSSEDatatype::InductionMachine => psse_data.induction_machines.extend(parse_section(&lines, &section, &header, options, &mut findings, parse_induction_machines)?),
+            PSSEDatatype::Substation => psse_data.substations.extend(parse_section(&lines, &section, &header, options, &mut findings, parse_substations)?),
+            _ => {}
         }
-    }
-
-    let parse_adder: usize = (psse_data.header.revision >= 34) as usize;
-    //Parse Buses
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&1), section_ends.get(&1)) {
-        //let end_index = section_starts.get(&2).cloned().unwrap_or(lines.len())-1;
-        //This fixes some issues since buses are parsed first, and in v33, they don't have a leading '/0'
-        if start_index == end_index {
-            psse_data.buses = parse_buses(std::slice::from_ref(&lines[start_index-1]));
-        } else {
-            psse_data.buses = parse_buses(&lines[start_index..end_index]);
+        //Records of the sections of variable length are not tracked
+        let first_line: usize = section.start + 1;
+        let records: Vec<(usize, usize)> = match section.datatype {
+            PSSEDatatype::Transformer => psse_data.transformers[parsed..].iter()
+                .scan(first_line, |line, transformer| {
+                    let count: usize = if transformer.tertiary_bus == 0 { 4 } else { 5 };
+                    *line += count;
+                    Some((*line - count, count))
+                })
+                .collect(),
+            PSSEDatatype::TwoTerminalDc | PSSEDatatype::VscDc => (0..section_len(&psse_data, section.datatype) - parsed).map(|i| (first_line + 3 * i, 3)).collect(),
+            PSSEDatatype::ImpedanceCorrection | PSSEDatatype::MultiTerminalDc | PSSEDatatype::GneDevice | PSSEDatatype::Substation => Vec::new(),
+            datatype => (0..section_len(&psse_data, datatype) - parsed).map(|i| (first_line + i, 1)).collect(),
+        };
+        findings.locate(&mut source, section.datatype, &records);
+        //V30 fixed shunts come from the bus records
+        if section.datatype == PSSEDatatype::Bus && psse_data.fixed_shunts.len() > fixed_shunts {
+            let bus_lines: HashMap<i32, usize> = psse_data.buses[buses..].iter().enumerate().map(|(i, bus)| (bus.id, first_line + i)).collect();
+            let shunt_records: Vec<(usize, usize)> = psse_data.fixed_shunts[fixed_shunts..].iter().filter_map(|shunt| bus_lines.get(&shunt.bus_id).map(|&line| (line, 1))).collect();
+            findings.locate(&mut source, PSSEDatatype::FixedShunt, &shunt_records);
         }
     }
-    //Parse Loads
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&2), section_ends.get(&2)) {
-        psse_data.loads = parse_loads(&lines[start_index..end_index]);
-    }
-    //Parse Fixed Shunts
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&3), section_ends.get(&3)) {
-        psse_data.fixed_shunts = parse_fixedshunts(&lines[start_index..end_index]);
-    }
-    //Parse Generators
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&4), section_ends.get(&4)) {
-        psse_data.generators = parse_generators(&lines[start_index..end_index], psse_data.header.revision);
-    }
-    //Parse Branches
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&5), section_ends.get(&5)) {
-        psse_data.branches = parse_lines(&lines[start_index..end_index], psse_data.header.revision);
+    diagnostics.extend(findings.diagnostics);
+    diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+    //Return the completed PSS/E data struct
+    Ok((psse_data, source, diagnostics))
+}
+
+/// Number of records of the section held by the case
+fn section_len(data: &PSSEData, datatype: PSSEDatatype) -> usize {
+    match datatype {
+        PSSEDatatype::Bus => data.buses.len(),
+        PSSEDatatype::Load => data.loads.len(),
+        PSSEDatatype::FixedShunt => data.fixed_shunts.len(),
+        PSSEDatatype::Generator => data.generators.len(),
+        PSSEDatatype::Branch => data.branches.len(),
+        PSSEDatatype::SystemSwitchingDevice => data.switching_devices.len(),
+        PSSEDatatype::Transformer => data.transformers.len(),
+        PSSEDatatype::Area => data.areas.len(),
+        PSSEDatatype::TwoTerminalDc => data.two_terminal_dc.len(),
+        PSSEDatatype::VscDc => data.vsc_dc.len(),
+        PSSEDatatype::ImpedanceCorrection => data.impedance_correction.len(),
+        PSSEDatatype::MultiTerminalDc => data.multi_terminal_line.len(),
+        PSSEDatatype::MultiSectionLine => data.multi_section_line.len(),
+        PSSEDatatype::Zone => data.zones.len(),
+        PSSEDatatype::InterAreaTransfer => data.inter_area_transfer.len(),
+        PSSEDatatype::Owner => data.owners.len(),
+        PSSEDatatype::Facts => data.facts.len(),
+        PSSEDatatype::SwitchedShunt => data.switched_shunts.len(),
+        PSSEDatatype::GneDevice => data.gne_devices.len(),
+        PSSEDatatype::InductionMachine => data.induction_machines.len(),
+        PSSEDatatype::Substation => data.substations.len(),
+        PSSEDatatype::Header | PSSEDatatype::Unknown | PSSEDatatype::EndOfFile => 0,
     }
-    //Parse System Switching Devices (V34+ Only)
-    if psse_data.header.revision >= 34 {
-        if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&6), section_ends.get(&6)) {
-            psse_data.switching_devices = parse_system_switching_device(&lines[start_index..end_index]);
+}
+
+/// What the component parsers report besides the records they read
+#[derive(Default)]
+struct Findings {
+    diagnostics: Vec<Diagnostic>,
+    given: Option<HashMap<usize, Vec<&'static str>>>,
+}
+
+impl Findings {
+    /// Adds the records of `section`, given by their first line and number of lines, to the source map
+    ///
+    /// The fields given by their lines are only kept for change cases.
+    fn locate(&self, source: &mut SourceMap, section: PSSEDatatype, records: &[(usize, usize)]) {
+        source.extend(section, records.iter().map(|&(line, _)| line));
+        if let Some(given) = &self.given {
+            let fields = |line: usize| given.get(&line).cloned().unwrap_or_default();
+            source.extend_given(section, records.iter().map(|&(line, count)| (line..line + count).map(fields).collect()));
         }
-    } else {
-        psse_data.switching_devices = Vec::new();
-    }
-    //Parse Transformers
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&(6 + parse_adder)), section_ends.get(&(6 + parse_adder))) {
-        psse_data.transformers = parse_transformers(&lines[start_index..end_index], psse_data.header.revision);
-    }
-    //Parse Areas
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&(7 + parse_adder)), section_ends.get(&(7 + parse_adder))) {
-        psse_data.areas = parse_areas(&lines[start_index..end_index]);
     }
-    //Parse Two Terminal DC
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&(8 + parse_adder)), section_ends.get(&(8 + parse_adder))) {
-        psse_data.two_terminal_dc = parse_two_terminal_dc_line(&lines[start_index..end_index], psse_data.header.revision);
-    }
-    //Parse VSC DC line
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&(9 + parse_adder)), section_ends.get(&(9 + parse_adder))) {
-        psse_data.vsc_dc = parse_vsc_dc_line(&lines[start_index..end_index], psse_data.header.revision);
-    }
-    //Parse Impedance Correction tables
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&(10 + parse_adder)), section_ends.get(&(10 + parse_adder))) {
-        psse_data.impedance_correction = parse_impedance_correction_table(&lines[start_index..end_index], psse_data.header.revision);
-    }
-    //Parse Multi-Terminal DC line
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&(11 + parse_adder)), section_ends.get(&(11 + parse_adder))) {
-        psse_data.multi_terminal_line = parse_multiterminal_dc_line(&lines[start_index..end_index]);
-    }
-    //Parse Multi-Terminal DC line
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&(12 + parse_adder)), section_ends.get(&(12 + parse_adder))) {
-        psse_data.multi_section_line = parse_multisection_lines(&lines[start_index..end_index]);
-    }
-    //Parse Zones
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&(13 + parse_adder)), section_ends.get(&(13 + parse_adder))) {
-        psse_data.zones = parse_zones(&lines[start_index..end_index]);
-    }
-    //Parse Inter-Area Transfer
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&(14 + parse_adder)), section_ends.get(&(14 + parse_adder))) {
-        psse_data.inter_area_transfer = parse_area_transfers(&lines[start_index..end_index]);
+}
+
+/// Hands the lines of a section to its component parser, collecting any lenient mode diagnostics
+/// and, for change cases, the fields each line gave a value for
+///
+/// From V36 the fields of the records that gained columns in the middle are located by the section's `@!` headings,
+/// or by their V36 positions on the lines without a heading.
+/// The records of change cases may leave fields off the end of their lines.
+fn parse_section<T>(
+    lines: &[&[u8]],
+    section: &Section,
+    header: &HeaderInfo,
+    options: &ParseOptions,
+    findings: &mut Findings,
+    parser: impl FnOnce(&[&[u8]], &SectionContext) -> Result<Vec<T>, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    let mut ctx: SectionContext = SectionContext::new(section.start + 1, options);
+    if let Some(columns) = (header.revision >= 36).then(|| column_map(section.datatype, &lines[section.headings..section.start])).flatten() {
+        ctx = ctx.with_columns(columns);
     }
-    //Parse Owners
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&(15 + parse_adder)), section_ends.get(&(15 + parse_adder))) {
-        psse_data.owners = parse_owners(&lines[start_index..end_index]);
+    if header.ic == 1 {
+        ctx = ctx.for_change_case();
     }
-    //Parse FACTS
-    if let (Some(&start_index), Some(&end_index)) = (section_starts.get(&(16 + parse_adder)), section_ends.get(&(16 + parse_adder))) {
-        psse_data.facts = parse_facts(&lines[start_index..end_index], psse_data.header.revision);
+    let parsed: Vec<T> = parser(&lines[section.start..section.end], &ctx)?;
+    if header.ic == 1 {
+        //V30 bus lines are read twice, for the bus and for its fixed shunt, so the fields are added to those already given
+        ctx.take_given(findings.given.get_or_insert_with(HashMap::new));
     }
-    //Return the completed PSS/E data struct
-    Ok(psse_data)
+    findings.diagnostics.extend(ctx.into_diagnostics());
+    Ok(parsed)
 }
 
 #[cfg(test)]
 mod tests {
     use std::time::Instant;
 
+    use crate::io::psse::encoding::Encoding;
+
     use super::*;
 
     #[test]
     fn test_speed() {
         let start = Instant::now();
-        let _ = parse_fast("/test-cases/TEST_parse_psse_v33.RAW");
+        let _ = parse_fast("/test-cases/TEST_parse_psse_v33.RAW", &ParseOptions::default());
         println!("Time to read and parse file: {:?}", start.elapsed());
     }
 
@@ -189,7 +244,8 @@ mod tests {
     fn test_parse_v33() {
         let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
         let filepath: String = format!("{}/test-cases/TEST_parse_psse_v33.RAW", dir);
-        let data: PSSEData = parse_fast(&filepath).unwrap();
+        let (data, source, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::default()).unwrap();
+        assert!(diagnostics.is_empty());
         //Test Header
         assert_eq!(data.header, HeaderInfo { ic: 0, sbase: 923.45, revision: 33, transformer_rating_code: 0, branch_rating_code: 3, system_frequency: 45.67 });
         //Test Bus
@@ -201,7 +257,8 @@ mod tests {
         assert_eq!(data.loads[0].dgen_mw, 0.0);
         // Test Fixed Shunt
         assert_eq!(data.generators[0].bus_id, 24568);
-        assert_eq!(data.generators[0].base_loaded, 2);
+        assert_eq!(data.generators[0].owner1, 2);
+        assert_eq!(data.generators[0].base_loaded, 0);
         assert_eq!(data.generators[0].machine_mode, 0);
         //Test Branches
         assert_eq!(data.branches[0].from_bus, 347);
@@ -213,6 +270,10 @@ mod tests {
         assert_eq!(data.transformers[0].from_bus, 24568);
         assert_eq!(data.transformers[0].tertiary_bus, 0);
         assert_eq!(data.transformers[0].rma1, 1.23456);
+        assert_eq!(source.line(PSSEDatatype::Transformer, 0), Some(14));
+        assert_eq!(source.line(PSSEDatatype::VscDc, 0), Some(25));
+        //The fields given are only kept for change cases
+        assert_eq!(source.given_fields(PSSEDatatype::Bus, 0), None);
         //Test Parse Transformer
         assert_eq!(data.areas[0].area_id, 2);
         assert_eq!(data.areas[0].swing_bus_id, 1);
@@ -227,7 +288,8 @@ mod tests {
         assert_eq!(data.vsc_dc[0].name, "TST-VSC".to_string());
         //Test Parse Impedance Correction Tables
         assert_eq!(data.impedance_correction[0].index, 2);
-        assert_eq!(data.impedance_correction[0].correction_table_entries[0].imag_complex_scaling_factor, -45.6789);
+        assert_eq!(data.impedance_correction[0].correction_table_entries[0].real_complex_scaling_factor, 1.23456);
+        assert_eq!(data.impedance_correction[0].correction_table_entries[1].tap, -45.6789);
         //Test Parse Multi-Terminal DC line
         // --- I don't have any examples of this entry in any cases I've found, so I don't have a practical format reference
         assert_eq!(data.multi_terminal_line, Vec::new());
@@ -246,5 +308,337 @@ mod tests {
         //Test Parse FACTS
         assert_eq!(data.owners[0].owner_id, 2);
         assert_eq!(data.owners[0].owner_name, "OWNER21".to_string());
+        //Test Parse Switched Shunts
+        assert_eq!(data.switched_shunts[0].bus_id, 20987);
+        assert_eq!(data.switched_shunts[0].control_mode, 3);
+        assert_eq!(data.switched_shunts[0].b_init, 78.901);
+        assert_eq!(data.switched_shunts[0].steps, vec![2, 2, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(data.switched_shunts[0].b_increment, vec![-78.901, 78.901, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        //Test Parse GNE Devices
+        assert_eq!(data.gne_devices[0].name, "GNE-V33".to_string());
+        assert_eq!(data.gne_devices[0].bus_ids, vec![20987]);
+        assert_eq!(data.gne_devices[0].real_data, vec![1.23456, 2.34567, 3.45678]);
+        assert_eq!(data.gne_devices[0].char_data, vec!["TEST".to_string()]);
+        //Test Parse Induction Machines
+        assert_eq!(data.induction_machines[0].bus_id, 24568);
+        assert_eq!(data.induction_machines[0].sbase, 123.45);
+        assert_eq!(data.induction_machines[0].mag_reactance, 3.45678);
+    }
+
+    fn check_v34_v35(data: &PSSEData) {
+        //Test Bus
+        assert_eq!(data.buses.len(), 3);
+        assert_eq!(data.buses[2].id, 152);
+        assert_eq!(data.buses[2].name, "MID500".to_string());
+        //Test Load
+        assert_eq!(data.loads[0].dgen_mw, 12.345);
+        //Test Generator
+        assert_eq!(data.generators[0].mbase, 900.0);
+        assert_eq!(data.generators[0].base_loaded, 1);
+        assert_eq!(data.generators[0].owner1, 1);
+        //Test Branches
+        assert_eq!(data.branches[0].name, "LINE-151-152".to_string());
+        assert_eq!(data.branches[0].rate3, 1400.0);
+        assert_eq!(data.branches[0].length, 150.0);
+        //Test System Switching Devices
+        assert_eq!(data.switching_devices[0].circuit, "@1".to_string());
+        assert_eq!(data.switching_devices[0].name, "BREAKER-1".to_string());
+        //Test Transformer
+        assert_eq!(data.transformers[0].from_bus, 101);
+        assert_eq!(data.transformers[0].w1_rate3, 1050.0);
+        assert_eq!(data.transformers[0].nominal_volt2, 500.0);
+        //Test DC lines and correction tables
+        assert_eq!(data.two_terminal_dc[0].rec_comm_bus_node, 1);
+        assert_eq!(data.vsc_dc[0].control_node_2, 2);
+        assert_eq!(data.impedance_correction[0].correction_table_entries.len(), 11);
+        //Test Multi-Section line, Zones and Owners
+        assert_eq!(data.multi_section_line[0].dummy_bus_ids[0], 151);
+        assert_eq!(data.zones.len(), 2);
+        assert_eq!(data.owners[0].owner_name, "OWNER ONE".to_string());
+        //Test FACTS
+        assert_eq!(data.facts[0].deivce_name, "FACTS-1".to_string());
+        assert_eq!(data.facts[0].from_bus, 151);
+        //Test Switched Shunts
+        assert_eq!(data.switched_shunts[0].bus_id, 152);
+        assert_eq!(data.switched_shunts[0].b_init, 125.0);
+        assert_eq!(data.switched_shunts[0].block_status, vec![1, 0, 1, 1, 1, 1, 1, 1]);
+        assert_eq!(data.switched_shunts[0].steps, vec![2, 1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(data.switched_shunts[0].b_increment, vec![50.0, 25.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        //Test GNE Devices
+        assert_eq!(data.gne_devices[0].bus_ids, vec![151, 152]);
+        assert_eq!(data.gne_devices[0].real_data, vec![0.01, 0.25]);
+        assert_eq!(data.gne_devices[0].int_data, vec![1]);
+        //Test Induction Machines
+        assert_eq!(data.induction_machines[0].bus_id, 151);
+        assert_eq!(data.induction_machines[0].id, "M1".to_string());
+        assert_eq!(data.induction_machines[0].sched_active_power, -85.0);
+    }
+
+    #[test]
+    fn test_parse_v34() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let filepath: String = format!("{}/test-cases/TEST_parse_psse_v34.RAW", dir);
+        let (data, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::default()).unwrap();
+        assert!(diagnostics.is_empty());
+        assert_eq!(data.header.revision, 34);
+        check_v34_v35(&data);
+        //V34 switched shunts have no identifier
+        assert_eq!(data.switched_shunts[0].id, "1".to_string());
+    }
+
+    #[test]
+    fn test_parse_v35() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let filepath: String = format!("{}/test-cases/TEST_parse_psse_v35.RAW", dir);
+        let (data, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::default()).unwrap();
+        assert!(diagnostics.is_empty());
+        assert_eq!(data.header, HeaderInfo { ic: 0, sbase: 100.0, revision: 35, transformer_rating_code: 0, branch_rating_code: 1, system_frequency: 60.0 });
+        check_v34_v35(&data);
+        assert_eq!(data.switched_shunts[0].id, "1".to_string());
+        //Load types are only written from V35
+        assert_eq!(data.loads[0].load_type, "INDUSTRIAL".to_string());
+        //Test Substations
+        assert_eq!(data.substations.len(), 1);
+        assert_eq!(data.substations[0].name, "NUCLEAR PLANT SWITCHYARD".to_string());
+        assert_eq!(data.substations[0].switching_devices[0].rate2, 2100.0);
+        assert_eq!(data.substations[0].terminals[1].equipment_type, "I".to_string());
+        assert_eq!(data.substations[0].terminals[1].id, "M1".to_string());
+        assert_eq!(data.substation_node(151, 2).map(|node| node.name.as_str()), Some("NB2"));
+        assert_eq!(data.substation_node(152, 2), None);
+    }
+
+    #[test]
+    fn test_parse_v36() {
+        //The V36 test case is the V35 one with columns inserted in the middle of the bus, load, generator, branch
+        //and transformer records, and added at the end of the load, generator and branch records
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let (v35, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(format!("{}/test-cases/TEST_parse_psse_v35.RAW", dir), &ParseOptions::strict()).unwrap();
+        let (mut data, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(format!("{}/test-cases/TEST_parse_psse_v36.RAW", dir), &ParseOptions::strict()).unwrap();
+        assert!(diagnostics.is_empty());
+        assert_eq!(data.header.revision, 36);
+        check_v34_v35(&data);
+        assert_eq!(data.loads[0].load_type, "INDUSTRIAL".to_string());
+        //The V36 columns
+        assert_eq!(data.buses[0].long_name, "NUCLEAR UNIT A".to_string());
+        assert_eq!(data.loads[0].priority, 3);
+        assert_eq!(data.generators[0].gen_type, "NUCLEAR".to_string());
+        assert_eq!(data.branches[0].branch_type, "OVERHEAD".to_string());
+        assert_eq!(data.transformers[0].description, "UNIT A STEP-UP".to_string());
+        assert_eq!((data.transformers[0].tap_step_1, data.transformers[0].tap_step_2), (0.625, 0.0));
+
+        data.buses.iter_mut().for_each(|bus| bus.long_name = String::new());
+        data.loads.iter_mut().for_each(|load| load.priority = 0);
+        data.generators.iter_mut().for_each(|generator| generator.gen_type = String::new());
+        data.branches.iter_mut().for_each(|branch| branch.branch_type = String::new());
+        data.transformers.iter_mut().for_each(|transformer| {
+            transformer.description = String::new();
+            transformer.tap_step_1 = 0.0;
+        });
+        assert_eq!(PSSEData { header: v35.header.clone(), ..data }, v35);
+    }
+
+    #[test]
+    fn test_parse_v36_without_headings() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let text: String = std::fs::read_to_string(format!("{}/test-cases/TEST_parse_psse_v36.RAW", dir)).unwrap();
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(text.as_bytes(), &ParseOptions::strict()).unwrap();
+
+        //Lines without a heading are read in the V36 column order, here the transformer lines after the first
+        let lines: Vec<&str> = text.lines().filter(|line| !["@!   R1-2", "@!WINDV"].iter().any(|heading| line.starts_with(heading))).collect();
+        let (partial, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(lines.join("\n").as_bytes(), &ParseOptions::strict()).unwrap();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(partial, data);
+
+        //and without any heading, every record of the sections located by name
+        let lines: Vec<&str> = text.lines().filter(|line| !line.starts_with("@!")).collect();
+        let (positional, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(lines.join("\n").as_bytes(), &ParseOptions::strict()).unwrap();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(positional, data);
+    }
+
+    #[test]
+    fn test_parse_bytes_and_reader() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let filepath: String = format!("{}/test-cases/TEST_parse_psse_v35.RAW", dir);
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::strict()).unwrap();
+        let bytes: Vec<u8> = std::fs::read(&filepath).unwrap();
+        assert_eq!(parse_bytes(&bytes, &ParseOptions::strict()).unwrap().0, data);
+        assert_eq!(parse_reader(bytes.as_slice(), &ParseOptions::strict()).unwrap().0, data);
+        assert_eq!(parse_reader(File::open(&filepath).unwrap(), &ParseOptions::strict()).unwrap().0, data);
+    }
+
+    #[test]
+    fn test_parse_crlf() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let filepath: String = format!("{}/test-cases/TEST_parse_psse_v35.RAW", dir);
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::strict()).unwrap();
+        //Windows line endings, with notes appended after the Q record
+        let text: String = std::fs::read_to_string(&filepath).unwrap().replace("\r\n", "\n").replace('\n', "\r\n");
+        let crlf: String = format!("{}\r\nNOTES: 0 / BEGIN BUS DATA\r\n101, 'NOT A BUS'\r\n", text.trim_end());
+        let (parsed, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(crlf.as_bytes(), &ParseOptions::strict()).unwrap();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(parsed, data);
+    }
+
+    #[test]
+    fn test_parse_latin1() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let raw: Vec<u8> = std::fs::read(format!("{}/test-cases/TEST_parse_psse_v33.RAW", dir)).unwrap();
+        //Latin-1 names in a single and a multi-line record
+        let text: String = String::from_utf8(raw).unwrap().replacen("'TESTBUS", "'M#NCHEN", 1).replacen("'IR39 SUB", "'IR39 S#B", 1);
+        let latin1: Vec<u8> = text.bytes().map(|byte| if byte == b'#' { 0xDC } else { byte }).collect();
+
+        let (data, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(&latin1, &ParseOptions::strict()).unwrap();
+        assert!(diagnostics.is_empty());
+        assert_eq!(data.buses[0].name, "MÜNCHEN".to_string());
+        assert!(data.transformers[0].name.starts_with("IR39 SÜB"));
+        let options: ParseOptions = ParseOptions { encoding: Encoding::Latin1, ..ParseOptions::strict() };
+        assert_eq!(parse_bytes(&latin1, &options).unwrap().0, data);
+
+        //Reading as UTF-8 fails on the bus, or keeps every record with the invalid bytes replaced
+        let options: ParseOptions = ParseOptions { encoding: Encoding::Utf8, ..ParseOptions::strict() };
+        let err: ParseError = parse_bytes(&latin1, &options).unwrap_err();
+        assert!(matches!(err, ParseError::InvalidEncoding { line: 4, section: PSSEDatatype::Bus }), "{}", err);
+        let options: ParseOptions = ParseOptions { encoding: Encoding::Utf8, ..ParseOptions::lenient() };
+        let (replaced, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(&latin1, &options).unwrap();
+        assert_eq!(replaced.buses[0].name, "M\u{FFFD}NCHEN".to_string());
+        assert_eq!(replaced.transformers.len(), data.transformers.len());
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0], Diagnostic { line: 4, section: PSSEDatatype::Bus, kind: DiagnosticKind::ReplacedEncoding });
+        assert_eq!(diagnostics[1].section, PSSEDatatype::Transformer);
+    }
+
+    #[test]
+    fn test_parse_v30() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let filepath: String = format!("{}/test-cases/TEST_parse_psse_v30.RAW", dir);
+        let (data, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::strict()).unwrap();
+        assert!(diagnostics.is_empty());
+        assert_eq!(data.header.revision, 30);
+        //V30 buses carry their own shunt and the owner at the end
+        assert_eq!(data.buses.len(), 4);
+        assert_eq!(data.buses[0].owner, 3);
+        assert_eq!(data.buses[3].area, 2);
+        assert_eq!(data.buses[3].vm_pu, 0.99);
+        assert_eq!(data.fixed_shunts.len(), 2);
+        assert_eq!(data.fixed_shunts[1].bus_id, 153);
+        assert_eq!(data.fixed_shunts[1].bl_mvar, 50.0);
+        //No SCALE on loads and no MET on branches
+        assert_eq!(data.loads[0].owner, 2);
+        assert_eq!(data.loads[0].scale, 1);
+        assert_eq!(data.generators[0].owner1, 3);
+        assert_eq!(data.branches[0].status, 1);
+        assert_eq!(data.branches[0].meter_end, 1);
+        assert_eq!(data.branches[0].length, 150.0);
+        assert_eq!(data.branches[0].owner1, 1);
+        assert_eq!(data.transformers[0].name, "NUC-A GSU".to_string());
+        assert_eq!(data.transformers[0].owner1, 3);
+        //DC lines are numbered rather than named
+        assert_eq!(data.two_terminal_dc[0].name, "1".to_string());
+        assert_eq!(data.two_terminal_dc[0].inv_bus_id, 153);
+        //No ADJM or STAT on switched shunts
+        assert_eq!(data.switched_shunts[0].upper_limit, 1.05);
+        assert_eq!(data.switched_shunts[0].b_init, 60.0);
+        assert_eq!(data.switched_shunts[0].steps[..2], [2, 1]);
+        assert_eq!(data.switched_shunts[0].b_increment[..2], [30.0, 20.0]);
+        assert_eq!(data.zones[0].zone_name, "ZONE-1".to_string());
+        assert_eq!(data.facts[0].volt_ref_code, 0);
+        assert_eq!(data.facts[0].master_device_name, String::new());
+    }
+
+    #[test]
+    fn test_parse_v31() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let filepath: String = format!("{}/test-cases/TEST_parse_psse_v31.RAW", dir);
+        let (data, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::strict()).unwrap();
+        assert!(diagnostics.is_empty());
+        assert_eq!(data.header.revision, 31);
+        assert_eq!(data.buses[0].owner, 3);
+        assert_eq!(data.buses[3].vm_pu, 0.99);
+        assert_eq!(data.fixed_shunts.len(), 1);
+        assert_eq!(data.loads[0].scale, 1);
+        assert_eq!(data.generators[0].machine_powerfactor, 1.0);
+        assert_eq!(data.branches[0].length, 150.0);
+        //Three winding transformers have no vector group or connection angles
+        assert_eq!(data.transformers[0].tertiary_bus, 154);
+        assert_eq!(data.transformers[0].w3_rate3, 120.0);
+        assert_eq!(data.transformers[0].impedance_correction_table_3, 0);
+        assert_eq!(data.vsc_dc[0].name, "VSC-31".to_string());
+        assert_eq!(data.vsc_dc[0].converter_2_bus_id, 153);
+        assert_eq!(data.switched_shunts[0].controlled_bus_name, String::new());
+        assert_eq!(data.switched_shunts[0].steps[..2], [2, 1]);
+        assert_eq!(data.facts[0].regulated_bus_id, 153);
+    }
+
+    #[test]
+    fn test_parse_v32() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let filepath: String = format!("{}/test-cases/TEST_parse_psse_v32.RAW", dir);
+        let (data, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::strict()).unwrap();
+        assert!(diagnostics.is_empty());
+        assert_eq!(data.header.revision, 32);
+        assert_eq!(data.loads[0].scale, 0);
+        //MET was added in V32
+        assert_eq!(data.branches[0].meter_end, 2);
+        assert_eq!(data.branches[0].length, 150.0);
+        assert_eq!(data.transformers[0].w1_rate1, 1200.0);
+        //Switched shunts follow the FACTS devices and have ADJM and STAT
+        assert_eq!(data.facts[0].deivce_name, "FACTS-32".to_string());
+        assert_eq!(data.switched_shunts[0].adjust_method, 0);
+        assert_eq!(data.switched_shunts[0].status, 1);
+        assert_eq!(data.switched_shunts[0].upper_limit, 1.05);
+        assert_eq!(data.switched_shunts[0].b_increment[..2], [30.0, 20.0]);
+        assert_eq!(data.gne_devices[0].name, "GNE-V32".to_string());
+    }
+
+    #[test]
+    fn test_parse_omitted_section() {
+        //Add a second bus and drop the fixed shunt section from the v33 test case, as some tools do for empty sections
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let contents: String = std::fs::read_to_string(format!("{}/test-cases/TEST_parse_psse_v33.RAW", dir)).unwrap();
+        let mut lines: Vec<&str> = contents.lines().collect();
+        let shunt_start: usize = lines.iter().position(|line| line.contains("begin fixed shunt data")).unwrap();
+        lines.drain(shunt_start..shunt_start + 2);
+        lines.insert(4, "    348, 'SECONDBUS   ',    12.3400,   1,    78,  876,    3,   1.0, 0.0");
+        let filepath = std::env::temp_dir().join("pf_gat_parser_test_parse_omitted_section.RAW");
+        std::fs::write(&filepath, lines.join("\n")).unwrap();
+        let (data, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::strict()).unwrap();
+        std::fs::remove_file(&filepath).unwrap();
+        assert_eq!(data.buses.len(), 2);
+        assert_eq!(data.buses[0].id, 347);
+        assert_eq!(data.buses[1].id, 348);
+        assert_eq!(data.fixed_shunts, Vec::new());
+        assert_eq!(data.generators[0].bus_id, 24568);
+        assert_eq!(data.branches[0].from_bus, 347);
+        assert_eq!(data.induction_machines[0].bus_id, 24568);
+        //The generator data sits where the fixed shunt data was expected
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, shunt_start + 2);
+        assert_eq!(diagnostics[0].section, PSSEDatatype::Generator);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::SectionMismatch { expected: Some(PSSEDatatype::FixedShunt) });
+    }
+
+    #[test]
+    fn test_parse_error_line_and_lenient_mode() {
+        //Corrupt the bus base voltage of the v33 test case and check the error points at it
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let contents: String = std::fs::read_to_string(format!("{}/test-cases/TEST_parse_psse_v33.RAW", dir)).unwrap();
+        let bus_line: usize = contents.lines().position(|line| line.trim_start().starts_with("347, 'TESTBUS")).unwrap() + 1;
+        let corrupted: String = contents.replacen("'TESTBUS     ',    89.4321", "'TESTBUS     ',    8x.4321", 1);
+        assert_ne!(corrupted, contents);
+        let filepath = std::env::temp_dir().join("pf_gat_parser_test_parse_error_line.RAW");
+        std::fs::write(&filepath, corrupted).unwrap();
+        let err: ParseError = parse_fast(&filepath, &ParseOptions::strict()).unwrap_err();
+        let (data, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::lenient()).unwrap();
+        std::fs::remove_file(&filepath).unwrap();
+        assert_eq!(err.line(), Some(bus_line));
+        assert_eq!(err.section(), Some(PSSEDatatype::Bus));
+        assert!(matches!(err, ParseError::InvalidField { field: "BASKV", .. }));
+        //Lenient mode falls back to the default and reports the substitution on the same line
+        assert_eq!(data.buses[0].base_kv, 999.99);
+        assert_eq!(data.buses[0].name, "TESTBUS".to_string());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, bus_line);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::InvalidField { field: "BASKV", text: "8x.4321".to_string() });
     }
 }
@@ -1 +1,4 @@
-pub mod fast_parser;
\ No newline at end of file
+pub mod fast_parser;
+pub mod sections;pub mod rawx;
+pub mod archive;
+pub(crate) mod columns;
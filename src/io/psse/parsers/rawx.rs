@@ -0,0 +1,582 @@
+use std::{collections::HashMap, path::Path};
+
+use serde_json::{Map, Value};
+
+use crate::io::psse::{components::{area::parse_areas, branch::parse_lines, bus::parse_buses, facts::parse_facts, fixed_shunt::parse_fixedshunts, generator::parse_generators, gne_device::parse_gne_devices, header::HeaderInfo, impedance_correction::{ImpedanceCorrectionEntry, ImpedanceCorrectionTable}, induction_machine::parse_induction_machines, interarea_transfer::parse_area_transfers, load::parse_loads, multisection_line::parse_multisection_lines, multiterminal_dc_line::parse_multiterminal_dc_line, owner::parse_owners, substation::parse_substations, switched_shunt::parse_switched_shunts, system_switching_device::parse_system_switching_device, transformer::parse_transformers, two_terminal_dc::parse_two_terminal_dc_line, vsc_dc::parse_vsc_dc_line, zone::parse_zones}, error::{Diagnostic, ParseError}, options::ParseOptions, pssedata::{PSSEData, PSSEDatatype}, record::{Record, SectionContext}};
+
+//RAWX is the V35 data model, so its records are read with the V35 column layouts
+pub(crate) const RAWX_REVISION: i8 = 35;
+
+/// Layout of a RAWX table whose rows hold the same fields as the records of a .RAW section
+pub(crate) struct RawxTable {
+    /// ## Name of the table in the `network` object
+    pub name: &'static str,
+    /// ## Section of the .RAW file holding the same records
+    pub section: PSSEDatatype,
+    /// ## RAWX fields on each line of the V35 .RAW record, in column order
+    pub lines: &'static [&'static [&'static str]],
+    /// ## Fields holding text rather than numbers
+    pub text: &'static [&'static str],
+}
+
+pub(crate) const CASEID_FIELDS: [&str; 8] = ["ic", "sbase", "rev", "xfrrat", "nxfrat", "basfrq", "title1", "title2"];
+
+pub(crate) const TABLES: [RawxTable; 17] = [
+    RawxTable {
+        name: "bus",
+        section: PSSEDatatype::Bus,
+        lines: &[&["ibus", "name", "baskv", "ide", "area", "zone", "owner", "vm", "va", "nvhi", "nvlo", "evhi", "evlo"]],
+        text: &["name"],
+    },
+    RawxTable {
+        name: "load",
+        section: PSSEDatatype::Load,
+        lines: &[&["ibus", "loadid", "stat", "area", "zone", "pl", "ql", "ip", "iq", "yp", "yq", "owner", "scale", "intrpt", "dgenp", "dgenq", "dgenm", "loadtype"]],
+        text: &["loadid", "loadtype"],
+    },
+    RawxTable {
+        name: "fixshunt",
+        section: PSSEDatatype::FixedShunt,
+        lines: &[&["ibus", "shntid", "stat", "gl", "bl"]],
+        text: &["shntid"],
+    },
+    RawxTable {
+        name: "generator",
+        section: PSSEDatatype::Generator,
+        lines: &[&[
+            "ibus", "machid", "pg", "qg", "qt", "qb", "vs", "ireg", "nreg", "mbase", "zr", "zx", "rt", "xt", "gtap", "stat", "rmpct", "pt", "pb",
+            "baslod", "o1", "f1", "o2", "f2", "o3", "f3", "o4", "f4", "wmod", "wpf",
+        ]],
+        text: &["machid"],
+    },
+    RawxTable {
+        name: "acline",
+        section: PSSEDatatype::Branch,
+        lines: &[&[
+            "ibus", "jbus", "ckt", "rpu", "xpu", "bpu", "name", "rate1", "rate2", "rate3", "rate4", "rate5", "rate6", "rate7", "rate8", "rate9",
+            "rate10", "rate11", "rate12", "gi", "bi", "gj", "bj", "stat", "met", "len", "o1", "f1", "o2", "f2", "o3", "f3", "o4", "f4",
+        ]],
+        text: &["ckt", "name"],
+    },
+    RawxTable {
+        name: "sysswd",
+        section: PSSEDatatype::SystemSwitchingDevice,
+        lines: &[&[
+            "ibus", "jbus", "ckt", "xpu", "rate1", "rate2", "rate3", "rate4", "rate5", "rate6", "rate7", "rate8", "rate9", "rate10", "rate11",
+            "rate12", "stat", "nstat", "met", "stype", "name",
+        ]],
+        text: &["ckt", "name"],
+    },
+    RawxTable {
+        name: "transformer",
+        section: PSSEDatatype::Transformer,
+        lines: &[
+            &[
+                "ibus", "jbus", "kbus", "ckt", "cw", "cz", "cm", "mag1", "mag2", "nmet", "name", "stat", "o1", "f1", "o2", "f2", "o3", "f3", "o4", "f4",
+                "vecgrp", "zcod",
+            ],
+            &["r1_2", "x1_2", "sbase1_2", "r2_3", "x2_3", "sbase2_3", "r3_1", "x3_1", "sbase3_1", "vmstar", "anstar"],
+            &[
+                "windv1", "nomv1", "ang1", "wdg1rate1", "wdg1rate2", "wdg1rate3", "wdg1rate4", "wdg1rate5", "wdg1rate6", "wdg1rate7", "wdg1rate8",
+                "wdg1rate9", "wdg1rate10", "wdg1rate11", "wdg1rate12", "cod1", "cont1", "node1", "rma1", "rmi1", "vma1", "vmi1", "ntp1", "tab1",
+                "cr1", "cx1", "cnxa1",
+            ],
+            &[
+                "windv2", "nomv2", "ang2", "wdg2rate1", "wdg2rate2", "wdg2rate3", "wdg2rate4", "wdg2rate5", "wdg2rate6", "wdg2rate7", "wdg2rate8",
+                "wdg2rate9", "wdg2rate10", "wdg2rate11", "wdg2rate12", "cod2", "cont2", "node2", "rma2", "rmi2", "vma2", "vmi2", "ntp2", "tab2",
+                "cr2", "cx2", "cnxa2",
+            ],
+            &[
+                "windv3", "nomv3", "ang3", "wdg3rate1", "wdg3rate2", "wdg3rate3", "wdg3rate4", "wdg3rate5", "wdg3rate6", "wdg3rate7", "wdg3rate8",
+                "wdg3rate9", "wdg3rate10", "wdg3rate11", "wdg3rate12", "cod3", "cont3", "node3", "rma3", "rmi3", "vma3", "vmi3", "ntp3", "tab3",
+                "cr3", "cx3", "cnxa3",
+            ],
+        ],
+        text: &["ckt", "name", "vecgrp"],
+    },
+    RawxTable {
+        name: "area",
+        section: PSSEDatatype::Area,
+        lines: &[&["iarea", "isw", "pdes", "ptol", "arname"]],
+        text: &["arname"],
+    },
+    RawxTable {
+        name: "twotermdc",
+        section: PSSEDatatype::TwoTerminalDc,
+        lines: &[
+            &["name", "mdc", "rdc", "setvl", "vschd", "vcmod", "rcomp", "delti", "met", "dcvmin", "cccitmx", "cccacc"],
+            &["ipr", "nbr", "anmxr", "anmnr", "rcr", "xcr", "ebasr", "trr", "tapr", "tmxr", "tmnr", "stpr", "icr", "ndr", "ifr", "itr", "idr", "xcapr"],
+            &["ipi", "nbi", "anmxi", "anmni", "rci", "xci", "ebasi", "tri", "tapi", "tmxi", "tmni", "stpi", "ici", "ndi", "ifi", "iti", "idi", "xcapi"],
+        ],
+        text: &["name", "met", "idr", "idi"],
+    },
+    RawxTable {
+        name: "vscdc",
+        section: PSSEDatatype::VscDc,
+        lines: &[
+            &["name", "mdc", "rdc", "o1", "f1", "o2", "f2", "o3", "f3", "o4", "f4"],
+            &[
+                "ibus1", "type1", "mode1", "dcset1", "acset1", "aloss1", "bloss1", "minloss1", "smax1", "imax1", "pwf1", "maxq1", "minq1", "vsreg1",
+                "nreg1", "rmpct1",
+            ],
+            &[
+                "ibus2", "type2", "mode2", "dcset2", "acset2", "aloss2", "bloss2", "minloss2", "smax2", "imax2", "pwf2", "maxq2", "minq2", "vsreg2",
+                "nreg2", "rmpct2",
+            ],
+        ],
+        text: &["name"],
+    },
+    RawxTable {
+        name: "msline",
+        section: PSSEDatatype::MultiSectionLine,
+        lines: &[&["ibus", "jbus", "mslid", "met", "dum1", "dum2", "dum3", "dum4", "dum5", "dum6", "dum7", "dum8", "dum9"]],
+        text: &["mslid"],
+    },
+    RawxTable {
+        name: "zone",
+        section: PSSEDatatype::Zone,
+        lines: &[&["izone", "zoname"]],
+        text: &["zoname"],
+    },
+    RawxTable {
+        name: "iatrans",
+        section: PSSEDatatype::InterAreaTransfer,
+        lines: &[&["arfrom", "arto", "trid", "ptran"]],
+        text: &["trid"],
+    },
+    RawxTable {
+        name: "owner",
+        section: PSSEDatatype::Owner,
+        lines: &[&["iowner", "owname"]],
+        text: &["owname"],
+    },
+    RawxTable {
+        name: "facts",
+        section: PSSEDatatype::Facts,
+        lines: &[&[
+            "name", "ibus", "jbus", "mode", "pdes", "qdes", "vset", "shmx", "trmx", "vtmn", "vtmx", "vsmx", "imx", "linx", "rmpct", "owner",
+            "set1", "set2", "vsref", "fcreg", "nreg", "mname",
+        ]],
+        text: &["name", "mname"],
+    },
+    RawxTable {
+        name: "swshunt",
+        section: PSSEDatatype::SwitchedShunt,
+        lines: &[&[
+            "ibus", "shntid", "modsw", "adjm", "stat", "vswhi", "vswlo", "swreg", "nreg", "rmpct", "rmidnt", "binit",
+            "s1", "n1", "b1", "s2", "n2", "b2", "s3", "n3", "b3", "s4", "n4", "b4", "s5", "n5", "b5", "s6", "n6", "b6", "s7", "n7", "b7", "s8", "n8", "b8",
+        ]],
+        text: &["shntid", "rmidnt"],
+    },
+    RawxTable {
+        name: "indmach",
+        section: PSSEDatatype::InductionMachine,
+        lines: &[&[
+            "ibus", "imid", "stat", "scode", "dcode", "area", "zone", "owner", "tcode", "bcode", "mbase", "ratekv", "pcode", "pset", "h", "a", "b",
+            "d", "e", "ra", "xa", "xm", "r1", "x1", "r2", "x2", "x3", "e1", "se1", "e2", "se2", "ia1", "ia2", "xamult",
+        ]],
+        text: &["imid"],
+    },
+];
+
+//The converter and DC bus lines of a multi-terminal DC record are kept in their own tables, keyed by the DC line name
+pub(crate) const MULTI_TERMINAL_TABLES: [(&str, &[&str]); 3] = [
+    ("ntermdc", &["name", "nconv", "ndcbs", "ndcln", "mdc", "vconv", "vcmod", "vconvn"]),
+    ("ntermdcconv", &["name", "ib", "n", "angmx", "angmn", "rc", "xc", "ebas", "tr", "tap", "tpmx", "tpmn", "setvl", "dcpf", "marg", "cnvcod"]),
+    ("ntermdcbus", &["name", "idc", "ib", "area", "zone", "dcname", "idc2", "rgrnd", "owner"]),
+];
+pub(crate) const MULTI_TERMINAL_TEXT: [&str; 2] = ["name", "dcname"];
+
+pub(crate) const IMPCOR_FIELDS: [&str; 4] = ["itable", "tap", "refact", "imfact"];
+
+//The GNE bus, real, integer and character fields are numbered up to the counts given on each row
+pub(crate) const GNE_FIELDS: [&str; 3] = ["name", "model", "nterm"];
+pub(crate) const GNE_COUNT_FIELDS: [&str; 6] = ["nreal", "nintg", "nchar", "stat", "owner", "nmet"];
+
+//The node, switching device and terminal blocks of a substation are kept in their own tables, keyed by the substation number
+pub(crate) const SUBSTATION_FIELDS: [&str; 5] = ["isub", "name", "lati", "long", "srg"];
+pub(crate) const SUBSTATION_NODE_FIELDS: [&str; 7] = ["isub", "inode", "name", "ibus", "stat", "vm", "va"];
+pub(crate) const SUBSTATION_DEVICE_FIELDS: [&str; 12] = ["isub", "inode", "jnode", "swdid", "name", "type", "stat", "nstat", "xpu", "rate1", "rate2", "rate3"];
+pub(crate) const SUBSTATION_TERMINAL_FIELDS: [&str; 7] = ["isub", "inode", "type", "eqid", "ibus", "jbus", "kbus"];
+
+/// Parses a PSS/E .RAWX (JSON) file into a [`PSSEData`] struct
+///
+/// Each table of the `network` object is mapped onto the V35 .RAW record layout by field name and handed to the same
+/// component parsers as [`parse_fast`](super::fast_parser::parse_fast), so both formats give the same data.
+/// Fields may be listed in any order and missing fields or `null` values take the PSS/E defaults.
+/// Tables without a .RAW counterpart (system-wide data, ratings, ...) are ignored.
+/// A multi-terminal DC line holds a single converter and DC bus, further rows for a line are an error in strict mode
+/// and are reported as a diagnostic in lenient mode.
+/// Line numbers in errors and diagnostics are the 1-based row of the record in its table.
+///
+/// ```no_run
+/// use pf_gat_parser::prelude::*;
+///
+/// let (case, _) = parse_rawx("case.rawx", &ParseOptions::strict()).unwrap();
+/// println!("{} buses", case.buses.len());
+/// ```
+pub fn parse_rawx<P: AsRef<Path>>(filepath: P, options: &ParseOptions) -> Result<(PSSEData, Vec<Diagnostic>), ParseError> {
+    let json: Value = serde_json::from_slice(&std::fs::read(filepath)?)?;
+    let network: &Map<String, Value> = json.get("network").and_then(Value::as_object)
+        .ok_or_else(|| ParseError::InvalidTable { table: "network".to_string() })?;
+
+    let mut psse_data: PSSEData = PSSEData::default();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    let caseid: Option<Table> = Table::read(network, "caseid")?;
+    let fields: Vec<String> = match &caseid {
+        Some(table) if !table.rows.is_empty() => table.line_values(0, &CASEID_FIELDS[..6])?,
+        _ => Vec::new(),
+    };
+    let header_ctx: SectionContext = SectionContext::new(1, options).with_fields(vec![fields]);
+    let rec: Record = header_ctx.record(&[], 0, PSSEDatatype::Header)?;
+    psse_data.header = HeaderInfo {
+        ic: rec.optional(0, "IC", 0)?,
+        sbase: rec.optional(1, "SBASE", 100.0)?,
+        revision: rec.optional(2, "REV", RAWX_REVISION)?,
+        transformer_rating_code: rec.optional(3, "XFRRAT", 0)?,
+        branch_rating_code: rec.optional(4, "NXFRAT", 0)?,
+        system_frequency: rec.optional(5, "BASFRQ", 60.0)?,
+    };
+    drop(rec);
+    diagnostics.extend(header_ctx.into_diagnostics());
+
+    for layout in &TABLES {
+        let Some(table) = Table::read(network, layout.name)? else { continue };
+        let mut records: Vec<Vec<RecordLine>> = Vec::with_capacity(table.rows.len());
+        for row in 0..table.rows.len() {
+            let mut lines: &[&[&str]] = layout.lines;
+            //Two winding transformers have no third winding line
+            if layout.section == PSSEDatatype::Transformer && table.value(row, "kbus").as_f64().unwrap_or(0.0) == 0.0 {
+                lines = &lines[..lines.len() - 1];
+            }
+            records.push(lines.iter().map(|fields| table.line_values(row, fields).map(RecordLine::Fields)).collect::<Result<_, _>>()?);
+        }
+        let diagnostics: &mut Vec<Diagnostic> = &mut diagnostics;
+        match layout.section {
+            PSSEDatatype::Bus => psse_data.buses = parse_table(records, options, diagnostics, |lines, ctx| parse_buses(lines, ctx, RAWX_REVISION))?,
+            PSSEDatatype::Load => psse_data.loads = parse_table(records, options, diagnostics, |lines, ctx| parse_loads(lines, ctx, RAWX_REVISION))?,
+            PSSEDatatype::FixedShunt => psse_data.fixed_shunts = parse_table(records, options, diagnostics, parse_fixedshunts)?,
+            PSSEDatatype::Generator => psse_data.generators = parse_table(records, options, diagnostics, |lines, ctx| parse_generators(lines, ctx, RAWX_REVISION))?,
+            PSSEDatatype::Branch => psse_data.branches = parse_table(records, options, diagnostics, |lines, ctx| parse_lines(lines, ctx, RAWX_REVISION))?,
+            PSSEDatatype::SystemSwitchingDevice => psse_data.switching_devices = parse_table(records, options, diagnostics, parse_system_switching_device)?,
+            PSSEDatatype::Transformer => psse_data.transformers = parse_table(records, options, diagnostics, |lines, ctx| parse_transformers(lines, ctx, RAWX_REVISION))?,
+            PSSEDatatype::Area => psse_data.areas = parse_table(records, options, diagnostics, parse_areas)?,
+            PSSEDatatype::TwoTerminalDc => psse_data.two_terminal_dc = parse_table(records, options, diagnostics, |lines, ctx| parse_two_terminal_dc_line(lines, ctx, RAWX_REVISION))?,
+            PSSEDatatype::VscDc => psse_data.vsc_dc = parse_table(records, options, diagnostics, |lines, ctx| parse_vsc_dc_line(lines, ctx, RAWX_REVISION))?,
+            PSSEDatatype::MultiSectionLine => psse_data.multi_section_line = parse_table(records, options, diagnostics, parse_multisection_lines)?,
+            PSSEDatatype::Zone => psse_data.zones = parse_table(records, options, diagnostics, parse_zones)?,
+            PSSEDatatype::InterAreaTransfer => psse_data.inter_area_transfer = parse_table(records, options, diagnostics, parse_area_transfers)?,
+            PSSEDatatype::Owner => psse_data.owners = parse_table(records, options, diagnostics, parse_owners)?,
+            PSSEDatatype::Facts => psse_data.facts = parse_table(records, options, diagnostics, |lines, ctx| parse_facts(lines, ctx, RAWX_REVISION))?,
+            PSSEDatatype::SwitchedShunt => psse_data.switched_shunts = parse_table(records, options, diagnostics, |lines, ctx| parse_switched_shunts(lines, ctx, RAWX_REVISION))?,
+            PSSEDatatype::InductionMachine => psse_data.induction_machines = parse_table(records, options, diagnostics, parse_induction_machines)?,
+            _ => {}
+        }
+    }
+
+    psse_data.impedance_correction = read_impedance_correction(network)?;
+    let multi_terminal_ctx: SectionContext = SectionContext::new(1, options);
+    if let Some(records) = multi_terminal_records(network, &multi_terminal_ctx)? {
+        psse_data.multi_terminal_line = parse_table(records, options, &mut diagnostics, parse_multiterminal_dc_line)?;
+    }
+    diagnostics.extend(multi_terminal_ctx.into_diagnostics());
+    if let Some(records) = gne_records(network)? {
+        psse_data.gne_devices = parse_table(records, options, &mut diagnostics, parse_gne_devices)?;
+    }
+    if let Some(records) = substation_records(network)? {
+        psse_data.substations = parse_table(records, options, &mut diagnostics, parse_substations)?;
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+    Ok((psse_data, diagnostics))
+}
+
+/// A RAWX table with its field names mapped to their column
+struct Table<'a> {
+    name: &'a str,
+    columns: HashMap<String, usize>,
+    rows: Vec<&'a [Value]>,
+}
+
+impl<'a> Table<'a> {
+    /// Reads the table called `name` from the network, if present
+    fn read(network: &'a Map<String, Value>, name: &'a str) -> Result<Option<Table<'a>>, ParseError> {
+        let Some(table) = network.get(name) else { return Ok(None) };
+        let invalid = || ParseError::InvalidTable { table: name.to_string() };
+        let fields: &Vec<Value> = table.get("fields").and_then(Value::as_array).ok_or_else(invalid)?;
+        let data: &Vec<Value> = table.get("data").and_then(Value::as_array).ok_or_else(invalid)?;
+        let columns: HashMap<String, usize> = fields.iter().enumerate()
+            .map(|(column, field)| field.as_str().map(|field| (field.to_lowercase(), column)).ok_or_else(invalid))
+            .collect::<Result<_, _>>()?;
+        //Tables holding a single record (case identification, system-wide data) give the row itself as the data
+        let rows: Vec<&[Value]> = if data.iter().all(Value::is_array) {
+            data.iter().filter_map(|row| row.as_array().map(Vec::as_slice)).collect()
+        } else {
+            vec![data.as_slice()]
+        };
+        Ok(Some(Table { name, columns, rows }))
+    }
+
+    /// The value of `field` on a row, `null` if the table has no such field
+    fn value(&self, row: usize, field: &str) -> &'a Value {
+        self.columns.get(field).and_then(|&column| self.rows[row].get(column)).unwrap_or(&Value::Null)
+    }
+
+    /// The text of `field` as the parsers read it from a .RAW record, blank for `null`
+    fn field_value(&self, row: usize, field: &str) -> Result<String, ParseError> {
+        Ok(match self.value(row, field) {
+            Value::Null => String::new(),
+            Value::Bool(value) => (*value as i32).to_string(),
+            Value::Number(value) => value.to_string(),
+            //Text loses its padding, as quoted fields do in a .RAW record
+            Value::String(value) => value.trim().to_string(),
+            _ => return Err(ParseError::InvalidTable { table: self.name.to_string() }),
+        })
+    }
+
+    /// The values of the `fields` of a row, in order, as the fields of a .RAW record line
+    fn line_values(&self, row: usize, fields: &[&str]) -> Result<Vec<String>, ParseError> {
+        fields.iter().map(|field| self.field_value(row, field)).collect()
+    }
+
+    /// The rows whose `key` field matches, grouped by the key's text
+    fn group_by(&self, key: &str) -> HashMap<String, Vec<usize>> {
+        let mut groups: HashMap<String, Vec<usize>> = HashMap::new();
+        for row in 0..self.rows.len() {
+            groups.entry(key_text(self.value(row, key))).or_default().push(row);
+        }
+        groups
+    }
+}
+
+/// A line of a .RAW record built from the rows of RAWX tables
+enum RecordLine {
+    /// The values of the fields on the line, in column order
+    Fields(Vec<String>),
+    /// The `0 /` terminator closing a block of substation data
+    Terminator,
+}
+
+//Keys are compared by their text so that 1 and "1" refer to the same record
+fn key_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.trim().to_string(),
+        value => value.to_string(),
+    }
+}
+
+/// Hands the .RAW records built for each row of a table to its component parser
+///
+/// The parser reads the values of the rows as they are, without writing them out as .RAW text to split again.
+/// Errors and diagnostics are moved from the line of the built record onto the row it was built from.
+fn parse_table<T>(
+    records: Vec<Vec<RecordLine>>,
+    options: &ParseOptions,
+    diagnostics: &mut Vec<Diagnostic>,
+    parser: impl FnOnce(&[&[u8]], &SectionContext) -> Result<Vec<T>, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    let mut starts: Vec<usize> = Vec::with_capacity(records.len());
+    let mut lines: Vec<&[u8]> = Vec::new();
+    let mut fields: Vec<Vec<String>> = Vec::new();
+    for record in records {
+        starts.push(lines.len());
+        for line in record {
+            //The line text only tells the records apart from the terminators, the values come from the context
+            match line {
+                RecordLine::Fields(values) => {
+                    lines.push(b"-");
+                    fields.push(values);
+                }
+                RecordLine::Terminator => {
+                    lines.push(b"0 /");
+                    fields.push(Vec::new());
+                }
+            }
+        }
+    }
+    let row = |line: usize| starts.partition_point(|&start| start < line);
+    let ctx: SectionContext = SectionContext::new(1, options).with_fields(fields);
+    let parsed: Vec<T> = parser(&lines, &ctx).map_err(|err| match err {
+        ParseError::InvalidField { line, section, field, text } => ParseError::InvalidField { line: row(line), section, field, text },
+        ParseError::MissingField { line, section, field } => ParseError::MissingField { line: row(line), section, field },
+        ParseError::TruncatedRecord { line, section, expected_lines } => ParseError::TruncatedRecord { line: row(line), section, expected_lines },
+        ParseError::InvalidEncoding { line, section } => ParseError::InvalidEncoding { line: row(line), section },
+        err => err,
+    })?;
+    diagnostics.extend(ctx.into_diagnostics().into_iter().map(|diagnostic| Diagnostic { line: row(diagnostic.line), ..diagnostic }));
+    Ok(parsed)
+}
+
+fn read_impedance_correction(network: &Map<String, Value>) -> Result<Vec<ImpedanceCorrectionTable>, ParseError> {
+    let Some(table) = Table::read(network, "impcor")? else { return Ok(Vec::new()) };
+    let mut correction_tables: Vec<ImpedanceCorrectionTable> = Vec::new();
+    for row in 0..table.rows.len() {
+        let number = |field: &str| table.value(row, field).as_f64().unwrap_or(0.0);
+        let index: i32 = number(IMPCOR_FIELDS[0]) as i32;
+        let entry: ImpedanceCorrectionEntry = ImpedanceCorrectionEntry {
+            tap: number(IMPCOR_FIELDS[1]),
+            real_complex_scaling_factor: number(IMPCOR_FIELDS[2]),
+            imag_complex_scaling_factor: number(IMPCOR_FIELDS[3]),
+        };
+        //Each row is one point of a table, the points of a table are listed together
+        match correction_tables.last_mut() {
+            Some(correction_table) if correction_table.index == index => correction_table.correction_table_entries.push(entry),
+            _ => correction_tables.push(ImpedanceCorrectionTable { index, correction_table_entries: vec![entry] }),
+        }
+    }
+    Ok(correction_tables)
+}
+
+/// Builds the multi-terminal DC records from the lines and their converter and DC bus tables
+///
+/// A record holds a single converter and DC bus, so only the first of each is read for a line.
+/// Lines with more are an error in strict mode, and are reported as a diagnostic in lenient mode.
+fn multi_terminal_records(network: &Map<String, Value>, ctx: &SectionContext) -> Result<Option<Vec<Vec<RecordLine>>>, ParseError> {
+    let [(name, fields), (conv_name, conv_fields), (bus_name, bus_fields)] = MULTI_TERMINAL_TABLES;
+    let Some(table) = Table::read(network, name)? else { return Ok(None) };
+    let converters: Option<Table> = Table::read(network, conv_name)?;
+    let buses: Option<Table> = Table::read(network, bus_name)?;
+    let converter_rows: HashMap<String, Vec<usize>> = converters.as_ref().map(|table| table.group_by("name")).unwrap_or_default();
+    let bus_rows: HashMap<String, Vec<usize>> = buses.as_ref().map(|table| table.group_by("name")).unwrap_or_default();
+
+    let mut records: Vec<Vec<RecordLine>> = Vec::with_capacity(table.rows.len());
+    for row in 0..table.rows.len() {
+        let key: String = key_text(table.value(row, "name"));
+        let mut record: Vec<RecordLine> = vec![RecordLine::Fields(table.line_values(row, fields)?)];
+        for (lookup, groups, lookup_name, line_fields) in [(&converters, &converter_rows, conv_name, conv_fields), (&buses, &bus_rows, bus_name, bus_fields)] {
+            let rows: &[usize] = groups.get(&key).map_or(&[], Vec::as_slice);
+            if rows.len() > 1 {
+                ctx.report(ParseError::UnreadRows { line: row + 1, section: PSSEDatatype::MultiTerminalDc, table: lookup_name, rows: rows.len() })?;
+            }
+            record.push(match (lookup, rows.first()) {
+                (Some(lookup), Some(&lookup_row)) => RecordLine::Fields(lookup.line_values(lookup_row, &line_fields[1..])?),
+                _ => RecordLine::Fields(Vec::new()),
+            });
+        }
+        records.push(record);
+    }
+    Ok(Some(records))
+}
+
+fn gne_records(network: &Map<String, Value>) -> Result<Option<Vec<Vec<RecordLine>>>, ParseError> {
+    let Some(table) = Table::read(network, "gne")? else { return Ok(None) };
+    let mut records: Vec<Vec<RecordLine>> = Vec::with_capacity(table.rows.len());
+    for row in 0..table.rows.len() {
+        //A row can't hold more values than it has columns, whatever its counts say
+        let count = |field: &str| (table.value(row, field).as_f64().unwrap_or(0.0).max(0.0) as usize).min(table.rows[row].len());
+        let numbered = |prefix: &str, count: usize| (1..=count).map(|i| format!("{}{}", prefix, i)).collect::<Vec<String>>();
+        let buses: Vec<String> = numbered("bus", count("nterm"));
+        let mut line: Vec<&str> = GNE_FIELDS.to_vec();
+        line.extend(buses.iter().map(String::as_str));
+        line.extend(&GNE_COUNT_FIELDS[..3]);
+        let mut record: Vec<RecordLine> = vec![RecordLine::Fields(table.line_values(row, &line)?), RecordLine::Fields(table.line_values(row, &GNE_COUNT_FIELDS[3..])?)];
+        //The real, integer and character data follow on lines of their own
+        for (prefix, count_field) in [("real", "nreal"), ("intg", "nintg"), ("char", "nchar")] {
+            let values: Vec<String> = numbered(prefix, count(count_field));
+            let fields: Vec<&str> = values.iter().map(String::as_str).collect();
+            if !fields.is_empty() {
+                record.push(RecordLine::Fields(table.line_values(row, &fields)?));
+            }
+        }
+        records.push(record);
+    }
+    Ok(Some(records))
+}
+
+fn substation_records(network: &Map<String, Value>) -> Result<Option<Vec<Vec<RecordLine>>>, ParseError> {
+    let Some(table) = Table::read(network, "sub")? else { return Ok(None) };
+    let blocks: [(Option<Table>, &[&str]); 3] = [
+        (Table::read(network, "subnode")?, &["inode", "name", "ibus", "stat", "vm", "va"]),
+        (Table::read(network, "subswd")?, &["inode", "jnode", "swdid", "name", "type", "stat", "nstat", "xpu", "rate1", "rate2", "rate3"]),
+        (Table::read(network, "subterm")?, &["ibus", "inode", "type", "jbus", "kbus", "eqid"]),
+    ];
+    let groups: Vec<HashMap<String, Vec<usize>>> = blocks.iter().map(|(block, _)| block.as_ref().map(|block| block.group_by("isub")).unwrap_or_default()).collect();
+
+    let mut records: Vec<Vec<RecordLine>> = Vec::with_capacity(table.rows.len());
+    for row in 0..table.rows.len() {
+        let key: String = key_text(table.value(row, "isub"));
+        let mut record: Vec<RecordLine> = vec![RecordLine::Fields(table.line_values(row, &SUBSTATION_FIELDS)?)];
+        //Each block of the substation is closed by a terminator, as in the .RAW file
+        record.push(RecordLine::Terminator);
+        for ((block, fields), group) in blocks.iter().zip(&groups) {
+            if let (Some(block), Some(rows)) = (block, group.get(&key)) {
+                for &block_row in rows {
+                    record.push(RecordLine::Fields(block.line_values(block_row, fields)?));
+                }
+            }
+            record.push(RecordLine::Terminator);
+        }
+        records.push(record);
+    }
+    Ok(Some(records))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::psse::error::DiagnosticKind;
+
+    use super::*;
+
+    #[test]
+    fn parse_rawx_by_field_name() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let (data, diagnostics): (PSSEData, Vec<Diagnostic>) = parse_rawx(format!("{}/test-cases/TEST_parse_psse_v35.rawx", dir), &ParseOptions::strict()).unwrap();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        //To avoid checking everything, check bits and pieces
+        assert_eq!(data.header.sbase, 100.0);
+        assert_eq!(data.header.revision, 35);
+        //Fields listed out of order and left out
+        assert_eq!(data.buses[0].id, 101);
+        assert_eq!(data.buses[0].name, "NUC-A".to_string());
+        assert_eq!(data.buses[0].vm_pu, 1.02);
+        assert_eq!(data.buses[0].nvhi, 1.1);
+        assert_eq!(data.buses[1].type_code, 1);
+        assert_eq!(data.loads[0].id, "1".to_string());
+        assert_eq!(data.loads[0].pl_mw, 120.5);
+        assert_eq!(data.loads[0].scale, 1);
+        assert_eq!(data.branches[0].rate1, 250.0);
+        assert_eq!(data.branches[0].name, "O'NEIL".to_string());
+        assert_eq!(data.transformers[0].to_bus, 102);
+        assert_eq!(data.transformers[0].winding_1_volt, 1.025);
+        assert_eq!(data.transformers[0].winding_2_volt, 1.0);
+        assert_eq!(data.impedance_correction[0].correction_table_entries.len(), 2);
+        assert_eq!(data.substations[0].nodes[1].bus_id, 102);
+        assert_eq!(data.substations[0].terminals[0].id, "1".to_string());
+    }
+
+    #[test]
+    fn invalid_rawx_tables() {
+        let filepath = std::env::temp_dir().join("pf_gat_parser_invalid_table.rawx");
+        std::fs::write(&filepath, r#"{"network": {"bus": {"fields": ["ibus"], "data": 101}}}"#).unwrap();
+        let err: ParseError = parse_rawx(&filepath, &ParseOptions::strict()).unwrap_err();
+        assert!(matches!(err, ParseError::InvalidTable { ref table } if table == "bus"), "{}", err);
+        std::fs::write(&filepath, r#"{"network": {"bus": {"fields": ["ibus", "vm"], "data": [[101, 1.0], [102, "high"]]}}}"#).unwrap();
+        let err: ParseError = parse_rawx(&filepath, &ParseOptions::strict()).unwrap_err();
+        std::fs::remove_file(&filepath).unwrap();
+        //Errors point to the row of the record in its table
+        assert_eq!(err.line(), Some(2));
+    }
+
+    #[test]
+    fn rawx_values_read_as_given() {
+        let filepath = std::env::temp_dir().join("pf_gat_parser_multi_terminal.rawx");
+        std::fs::write(&filepath, r#"{"network": {
+            "bus": {"fields": ["ibus", "name"], "data": [[101, "O'NEIL \"NORTH\""]]},
+            "ntermdc": {"fields": ["name", "nconv", "ndcbs", "ndcln", "mdc", "vconv"], "data": [["DC'1\"", 2, 1, 1, 1, 101]]},
+            "ntermdcconv": {"fields": ["name", "ib", "n"], "data": [["DC'1\"", 101, 2], ["DC'1\"", 102, 2]]},
+            "ntermdcbus": {"fields": ["name", "idc", "ib"], "data": [["DC'1\"", 1, 101]]}
+        }}"#).unwrap();
+        //Each line holds a single converter, the second one cannot be read
+        let err: ParseError = parse_rawx(&filepath, &ParseOptions::strict()).unwrap_err();
+        assert!(matches!(err, ParseError::UnreadRows { line: 1, section: PSSEDatatype::MultiTerminalDc, table: "ntermdcconv", rows: 2 }), "{}", err);
+        let (data, diagnostics): (PSSEData, Vec<Diagnostic>) = parse_rawx(&filepath, &ParseOptions::lenient()).unwrap();
+        std::fs::remove_file(&filepath).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::UnreadRows { table: "ntermdcconv", rows: 2 });
+        //Names holding both kinds of quotes are kept as they are
+        assert_eq!(data.buses[0].name, "O'NEIL \"NORTH\"".to_string());
+        assert_eq!(data.multi_terminal_line[0].name, "DC'1\"".to_string());
+        assert_eq!(data.multi_terminal_line[0].ac_conv_bus_1_id, 101);
+        assert_eq!(data.multi_terminal_line[0].dc_bus_num, 1);
+    }
+}
@@ -0,0 +1,534 @@
+use std::str::from_utf8;
+
+use crate::io::psse::{error::{Diagnostic, DiagnosticKind}, pssedata::PSSEDatatype};
+
+/// A block of records in the .RAW file belonging to a single section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section {
+    /// ## Data contained in the section
+    pub datatype: PSSEDatatype,
+    /// ## Index of the first `@!` column heading line of the section, equal to `start` when it has none (0-based)
+    pub headings: usize,
+    /// ## Index of the first record line of the section (0-based)
+    pub start: usize,
+    /// ## Index one past the last record line of the section, pointing at its `0 /` terminator
+    pub end: usize,
+}
+
+const V30_ORDER: [PSSEDatatype; 15] = [
+    PSSEDatatype::Bus,
+    PSSEDatatype::Load,
+    PSSEDatatype::Generator,
+    PSSEDatatype::Branch,
+    PSSEDatatype::Transformer,
+    PSSEDatatype::Area,
+    PSSEDatatype::TwoTerminalDc,
+    PSSEDatatype::SwitchedShunt,
+    PSSEDatatype::ImpedanceCorrection,
+    PSSEDatatype::MultiTerminalDc,
+    PSSEDatatype::MultiSectionLine,
+    PSSEDatatype::Zone,
+    PSSEDatatype::InterAreaTransfer,
+    PSSEDatatype::Owner,
+    PSSEDatatype::Facts,
+];
+
+const V31_ORDER: [PSSEDatatype; 17] = [
+    PSSEDatatype::Bus,
+    PSSEDatatype::Load,
+    PSSEDatatype::FixedShunt,
+    PSSEDatatype::Generator,
+    PSSEDatatype::Branch,
+    PSSEDatatype::Transformer,
+    PSSEDatatype::Area,
+    PSSEDatatype::TwoTerminalDc,
+    PSSEDatatype::VscDc,
+    PSSEDatatype::SwitchedShunt,
+    PSSEDatatype::ImpedanceCorrection,
+    PSSEDatatype::MultiTerminalDc,
+    PSSEDatatype::MultiSectionLine,
+    PSSEDatatype::Zone,
+    PSSEDatatype::InterAreaTransfer,
+    PSSEDatatype::Owner,
+    PSSEDatatype::Facts,
+];
+
+const V32_ORDER: [PSSEDatatype; 18] = [
+    PSSEDatatype::Bus,
+    PSSEDatatype::Load,
+    PSSEDatatype::FixedShunt,
+    PSSEDatatype::Generator,
+    PSSEDatatype::Branch,
+    PSSEDatatype::Transformer,
+    PSSEDatatype::Area,
+    PSSEDatatype::TwoTerminalDc,
+    PSSEDatatype::VscDc,
+    PSSEDatatype::ImpedanceCorrection,
+    PSSEDatatype::MultiTerminalDc,
+    PSSEDatatype::MultiSectionLine,
+    PSSEDatatype::Zone,
+    PSSEDatatype::InterAreaTransfer,
+    PSSEDatatype::Owner,
+    PSSEDatatype::Facts,
+    PSSEDatatype::SwitchedShunt,
+    PSSEDatatype::GneDevice,
+];
+
+const V33_ORDER: [PSSEDatatype; 19] = [
+    PSSEDatatype::Bus,
+    PSSEDatatype::Load,
+    PSSEDatatype::FixedShunt,
+    PSSEDatatype::Generator,
+    PSSEDatatype::Branch,
+    PSSEDatatype::Transformer,
+    PSSEDatatype::Area,
+    PSSEDatatype::TwoTerminalDc,
+    PSSEDatatype::VscDc,
+    PSSEDatatype::ImpedanceCorrection,
+    PSSEDatatype::MultiTerminalDc,
+    PSSEDatatype::MultiSectionLine,
+    PSSEDatatype::Zone,
+    PSSEDatatype::InterAreaTransfer,
+    PSSEDatatype::Owner,
+    PSSEDatatype::Facts,
+    PSSEDatatype::SwitchedShunt,
+    PSSEDatatype::GneDevice,
+    PSSEDatatype::InductionMachine,
+];
+
+const V34_ORDER: [PSSEDatatype; 20] = [
+    PSSEDatatype::Bus,
+    PSSEDatatype::Load,
+    PSSEDatatype::FixedShunt,
+    PSSEDatatype::Generator,
+    PSSEDatatype::Branch,
+    PSSEDatatype::SystemSwitchingDevice,
+    PSSEDatatype::Transformer,
+    PSSEDatatype::Area,
+    PSSEDatatype::TwoTerminalDc,
+    PSSEDatatype::VscDc,
+    PSSEDatatype::ImpedanceCorrection,
+    PSSEDatatype::MultiTerminalDc,
+    PSSEDatatype::MultiSectionLine,
+    PSSEDatatype::Zone,
+    PSSEDatatype::InterAreaTransfer,
+    PSSEDatatype::Owner,
+    PSSEDatatype::Facts,
+    PSSEDatatype::SwitchedShunt,
+    PSSEDatatype::GneDevice,
+    PSSEDatatype::InductionMachine,
+];
+
+const V35_ORDER: [PSSEDatatype; 21] = [
+    PSSEDatatype::Bus,
+    PSSEDatatype::Load,
+    PSSEDatatype::FixedShunt,
+    PSSEDatatype::Generator,
+    PSSEDatatype::Branch,
+    PSSEDatatype::SystemSwitchingDevice,
+    PSSEDatatype::Transformer,
+    PSSEDatatype::Area,
+    PSSEDatatype::TwoTerminalDc,
+    PSSEDatatype::VscDc,
+    PSSEDatatype::ImpedanceCorrection,
+    PSSEDatatype::MultiTerminalDc,
+    PSSEDatatype::MultiSectionLine,
+    PSSEDatatype::Zone,
+    PSSEDatatype::InterAreaTransfer,
+    PSSEDatatype::Owner,
+    PSSEDatatype::Facts,
+    PSSEDatatype::SwitchedShunt,
+    PSSEDatatype::GneDevice,
+    PSSEDatatype::InductionMachine,
+    PSSEDatatype::Substation,
+];
+
+/// The order the data sections are written in for a given PSS/E revision
+///
+/// V31 added fixed shunt and VSC DC line data, V32 moved switched shunts after the FACTS devices and added GNE devices,
+/// and V33 added induction machines. Revisions before 30 are assumed to follow the V30 order, and V36 and later
+/// revisions the V35 order (their records are read as described in [`parse_bytes`](super::fast_parser::parse_bytes)).
+pub fn section_order(revision: i8) -> &'static [PSSEDatatype] {
+    match revision {
+        ..=30 => &V30_ORDER,
+        31 => &V31_ORDER,
+        32 => &V32_ORDER,
+        33 => &V33_ORDER,
+        34 => &V34_ORDER,
+        35.. => &V35_ORDER,
+    }
+}
+
+/// Splits the contents of a .RAW file into lines, without their line endings
+///
+/// Lines may end with LF or CRLF, or with a bare CR in files written by old Mac tools.
+pub(crate) fn split_lines(bytes: &[u8]) -> Vec<&[u8]> {
+    //A file without a single LF uses bare CR line endings
+    let separator: u8 = if !bytes.contains(&b'\n') && bytes.contains(&b'\r') { b'\r' } else { b'\n' };
+    bytes.split(|&c| c == separator).map(|line| line.strip_suffix(b"\r").unwrap_or(line)).collect()
+}
+
+/// Splits the lines following the case identification into their sections
+///
+/// Each `0 /` terminator usually names the section that follows it (`end of bus data, begin load data`),
+/// which is used to identify the section. When there is no readable name, the section is assumed to be
+/// the next one in the revision's known order. A diagnostic is recorded when a named section is not the
+/// one expected by that order (e.g. a tool omitted an empty section), or when the name is not recognized.
+pub(crate) fn locate_sections(lines: &[&[u8]], header_index: usize, revision: i8, diagnostics: &mut Vec<Diagnostic>) -> Vec<Section> {
+    let order: &[PSSEDatatype] = section_order(revision);
+    let mut sections: Vec<Section> = Vec::new();
+    //Records 2 and 3 of the case identification are the two title lines
+    let mut start: usize = (header_index + 3).min(lines.len());
+    let mut label: Option<(usize, String)> = None;
+
+    //V34+ cases carry system-wide data (GENERAL, GAUSS, RATING, ...) before the bus data, ending with the first terminator
+    let first_record: Option<&str> = lines[start..].iter()
+        .filter_map(|line_bytes| from_utf8(line_bytes).ok())
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty() && !line.starts_with('@'));
+    //An empty system-wide block is only the terminator, which names itself
+    let system_wide: bool = first_record.is_some_and(|line| {
+        line.starts_with(|c: char| c.is_ascii_alphabetic()) || (is_terminator(line) && line.to_lowercase().contains("system-wide"))
+    });
+    if system_wide {
+        if let Some(offset) = lines[start..].iter().position(|line_bytes| from_utf8(line_bytes).is_ok_and(is_terminator)) {
+            let index: usize = start + offset;
+            label = from_utf8(lines[index]).ok().and_then(section_label).map(|text| (index, text));
+            start = index + 1;
+        }
+    }
+
+    let mut position: usize = 0;
+    let mut index: usize = start;
+    while index < lines.len() {
+        let line: &str = from_utf8(lines[index]).unwrap_or("").trim();
+        //Q marks the end of the data, anything after it is not part of the case
+        if is_end_of_data(line) {
+            break;
+        }
+        if is_terminator(line) {
+            if let Some(datatype) = identify(order, &mut position, label.take(), diagnostics) {
+                sections.push(Section { datatype, headings: start, start: skip_comments(lines, start, index), end: index });
+            }
+            label = section_label(line).map(|text| (index, text));
+            start = index + 1;
+            //Substation data holds its own terminated node, switching device and terminal blocks
+            let next: Option<PSSEDatatype> = label.as_ref().and_then(|(_, text)| classify(text)).or_else(|| order.get(position).copied());
+            if next == Some(PSSEDatatype::Substation) {
+                index = substation_end(lines, start);
+                continue;
+            }
+        }
+        index += 1;
+    }
+    //A final section may run up to the Q record (or the end of the file) without a terminator
+    let end: usize = index;
+    let data_start: usize = skip_comments(lines, start, end);
+    let has_records: bool = lines[data_start..end].iter().any(|line_bytes| from_utf8(line_bytes).map_or(true, |line| !line.trim().is_empty()));
+    if has_records {
+        if let Some(datatype) = identify(order, &mut position, label, diagnostics) {
+            sections.push(Section { datatype, headings: start, start: data_start, end });
+        }
+    }
+    sections
+}
+
+/// Finds the terminator closing the substation data starting at `start`
+///
+/// Each substation is a record followed by four terminators: the one opening its node data and the ones
+/// closing its node, switching device and terminal data. A terminator in place of a substation record ends the section.
+fn substation_end(lines: &[&[u8]], start: usize) -> usize {
+    let mut index: usize = start;
+    let mut terminators: usize = 0;
+    while index < lines.len() {
+        let line: &str = from_utf8(lines[index]).unwrap_or("").trim();
+        if is_end_of_data(line) {
+            break;
+        }
+        if is_terminator(line) {
+            if terminators == 0 {
+                break;
+            }
+            terminators -= 1;
+        } else if terminators == 0 && !line.is_empty() && !line.starts_with('@') {
+            terminators = 4;
+        }
+        index += 1;
+    }
+    index
+}
+
+/// Decides which section follows a terminator, advancing the position in the known section order
+fn identify(order: &[PSSEDatatype], position: &mut usize, label: Option<(usize, String)>, diagnostics: &mut Vec<Diagnostic>) -> Option<PSSEDatatype> {
+    let expected: Option<PSSEDatatype> = order.get(*position).copied();
+    match label {
+        Some((terminator, text)) => match classify(&text) {
+            Some(found) => {
+                if expected != Some(found) {
+                    diagnostics.push(Diagnostic { line: terminator + 1, section: found, kind: DiagnosticKind::SectionMismatch { expected } });
+                }
+                //Carry on from the named section so later unnamed sections line up with it
+                if let Some(found_position) = order.iter().position(|&datatype| datatype == found) {
+                    *position = found_position + 1;
+                }
+                Some(found)
+            }
+            None => {
+                diagnostics.push(Diagnostic {
+                    line: terminator + 1,
+                    section: expected.unwrap_or(PSSEDatatype::Unknown),
+                    kind: DiagnosticKind::UnrecognizedSection { text },
+                });
+                *position += 1;
+                expected
+            }
+        },
+        //Sections past the end of the known order are not parsed
+        None => {
+            *position += 1;
+            expected
+        }
+    }
+}
+
+/// Checks if the line is a section terminator (`0 / end of ... data, begin ... data`)
+pub(crate) fn is_terminator(line: &str) -> bool {
+    let line: &str = line.trim();
+    line.strip_prefix('0').is_some_and(|rest| rest.trim_start().starts_with('/'))
+}
+
+/// Checks if the line is the `Q` record ending the case data, which may carry a `/` comment
+pub(crate) fn is_end_of_data(line: &str) -> bool {
+    let line: &str = line.trim();
+    line.strip_prefix(['Q', 'q']).is_some_and(|rest| rest.trim_start().is_empty() || rest.trim_start().starts_with('/'))
+}
+
+/// The text naming the section that follows a terminator, in lower case
+fn section_label(line: &str) -> Option<String> {
+    let comment: String = line.split_once('/')?.1.to_lowercase();
+    let (_, label) = comment.split_once("begin")?;
+    let label: &str = label.trim();
+    if label.is_empty() { None } else { Some(label.to_string()) }
+}
+
+/// Maps a section name from a terminator comment to its data type
+///
+/// Tools word the names differently (`nontransformer branch data`, `NON-TRANSFORMER BRANCH DATA`, ...),
+/// so the check is made on the alphanumeric characters only, with the more specific names checked first.
+fn classify(label: &str) -> Option<PSSEDatatype> {
+    let name: String = label.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    const NAMES: [(&str, PSSEDatatype); 23] = [
+        ("impedancecorrection", PSSEDatatype::ImpedanceCorrection),
+        ("nontransformerbranch", PSSEDatatype::Branch),
+        ("substation", PSSEDatatype::Substation),
+        ("switchingdevice", PSSEDatatype::SystemSwitchingDevice),
+        ("transformer", PSSEDatatype::Transformer),
+        ("multiterminal", PSSEDatatype::MultiTerminalDc),
+        ("twoterminal", PSSEDatatype::TwoTerminalDc),
+        ("vsc", PSSEDatatype::VscDc),
+        ("multisection", PSSEDatatype::MultiSectionLine),
+        ("interarea", PSSEDatatype::InterAreaTransfer),
+        ("area", PSSEDatatype::Area),
+        ("fixedshunt", PSSEDatatype::FixedShunt),
+        ("fixedbusshunt", PSSEDatatype::FixedShunt),
+        ("switchedshunt", PSSEDatatype::SwitchedShunt),
+        ("facts", PSSEDatatype::Facts),
+        ("gne", PSSEDatatype::GneDevice),
+        ("inductionmachine", PSSEDatatype::InductionMachine),
+        ("generator", PSSEDatatype::Generator),
+        ("branch", PSSEDatatype::Branch),
+        ("load", PSSEDatatype::Load),
+        ("zone", PSSEDatatype::Zone),
+        ("owner", PSSEDatatype::Owner),
+        ("bus", PSSEDatatype::Bus),
+    ];
+    NAMES.iter().find(|(key, _)| name.contains(key)).map(|&(_, datatype)| datatype)
+}
+
+/// The name written in the terminator comments for each section
+pub(crate) fn section_name(datatype: PSSEDatatype) -> &'static str {
+    match datatype {
+        PSSEDatatype::Bus => "BUS",
+        PSSEDatatype::Load => "LOAD",
+        PSSEDatatype::FixedShunt => "FIXED SHUNT",
+        PSSEDatatype::Generator => "GENERATOR",
+        PSSEDatatype::Branch => "NON-TRANSFORMER BRANCH",
+        PSSEDatatype::SystemSwitchingDevice => "SYSTEM SWITCHING DEVICE",
+        PSSEDatatype::Transformer => "TRANSFORMER",
+        PSSEDatatype::Area => "AREA",
+        PSSEDatatype::TwoTerminalDc => "TWO-TERMINAL DC",
+        PSSEDatatype::VscDc => "VSC DC LINE",
+        PSSEDatatype::ImpedanceCorrection => "IMPEDANCE CORRECTION",
+        PSSEDatatype::MultiTerminalDc => "MULTI-TERMINAL DC",
+        PSSEDatatype::MultiSectionLine => "MULTI-SECTION LINE",
+        PSSEDatatype::Zone => "ZONE",
+        PSSEDatatype::InterAreaTransfer => "INTER-AREA TRANSFER",
+        PSSEDatatype::Owner => "OWNER",
+        PSSEDatatype::Facts => "FACTS DEVICE",
+        PSSEDatatype::SwitchedShunt => "SWITCHED SHUNT",
+        PSSEDatatype::GneDevice => "GNE",
+        PSSEDatatype::InductionMachine => "INDUCTION MACHINE",
+        PSSEDatatype::Substation => "SUBSTATION",
+        PSSEDatatype::Header | PSSEDatatype::Unknown | PSSEDatatype::EndOfFile => "UNKNOWN",
+    }
+}
+
+/// Skips the `@!` column heading comments at the top of a section
+fn skip_comments(lines: &[&[u8]], start: usize, end: usize) -> usize {
+    let mut index: usize = start;
+    while index < end && from_utf8(lines[index]).is_ok_and(|line| line.trim_start().starts_with('@')) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locate(text: &str, revision: i8) -> (Vec<Section>, Vec<Diagnostic>) {
+        let lines: Vec<&[u8]> = split_lines(text.as_bytes());
+        let mut diagnostics: Vec<Diagnostic> = Vec::new();
+        let sections: Vec<Section> = locate_sections(&lines, 0, revision, &mut diagnostics);
+        (sections, diagnostics)
+    }
+
+    #[test]
+    fn classify_section_names() {
+        assert_eq!(classify("nontransformer branch data"), Some(PSSEDatatype::Branch));
+        assert_eq!(classify("non-transformer branch data"), Some(PSSEDatatype::Branch));
+        assert_eq!(classify("transformer impedance correction tables"), Some(PSSEDatatype::ImpedanceCorrection));
+        assert_eq!(classify("area interchange data"), Some(PSSEDatatype::Area));
+        assert_eq!(classify("inter-area transfer data"), Some(PSSEDatatype::InterAreaTransfer));
+        assert_eq!(classify("system switching device data"), Some(PSSEDatatype::SystemSwitchingDevice));
+        assert_eq!(classify("substation switching device data"), Some(PSSEDatatype::Substation));
+        assert_eq!(classify("fixed bus shunt data"), Some(PSSEDatatype::FixedShunt));
+        assert_eq!(classify("bus data"), Some(PSSEDatatype::Bus));
+        assert_eq!(classify("something else"), None);
+    }
+
+    #[test]
+    fn section_names_classify() {
+        for revision in [33, 34, 35] {
+            for &datatype in section_order(revision) {
+                assert_eq!(classify(&section_name(datatype).to_lowercase()), Some(datatype));
+            }
+        }
+    }
+
+    #[test]
+    fn split_line_endings() {
+        let expected: Vec<&[u8]> = vec![b"1, 'BUS'", b"0 /", b"Q"];
+        assert_eq!(split_lines(b"1, 'BUS'\n0 /\nQ"), expected);
+        assert_eq!(split_lines(b"1, 'BUS'\r\n0 /\r\nQ"), expected);
+        assert_eq!(split_lines(b"1, 'BUS'\r0 /\rQ"), expected);
+    }
+
+    #[test]
+    fn data_ends_at_q() {
+        let (sections, diagnostics) = locate("0, 100.0, 33, 0, 1, 60.0\r
+TITLE 1\r
+TITLE 2\r
+1, 'BUS', 138.0\r
+0 / end of bus data, begin load data\r
+1, '1', 1\r
+Q / end of data\r
+Notes appended to the case:\r
+0 / end of load data, begin generator data\r
+1, '1', 10.0\r
+", 33);
+        let datatypes: Vec<PSSEDatatype> = sections.iter().map(|section| section.datatype).collect();
+        assert_eq!(datatypes, vec![PSSEDatatype::Bus, PSSEDatatype::Load]);
+        assert_eq!(sections[1].end, 6);
+        assert!(diagnostics.is_empty());
+        assert!(is_end_of_data("Q"));
+        assert!(!is_end_of_data("QUEBEC"));
+    }
+
+    #[test]
+    fn omitted_section_uses_label() {
+        //The fixed shunt section has been left out entirely
+        let (sections, diagnostics) = locate("0, 100.0, 33, 0, 1, 60.0
+TITLE 1
+TITLE 2
+1, 'BUS', 138.0
+0 / end of bus data, begin load data
+1, '1', 1
+0 / end of load data, begin generator data
+1, '1', 10.0
+0 / end of generator data, begin branch data
+Q", 33);
+        let datatypes: Vec<PSSEDatatype> = sections.iter().map(|section| section.datatype).collect();
+        assert_eq!(datatypes, vec![PSSEDatatype::Bus, PSSEDatatype::Load, PSSEDatatype::Generator]);
+        assert_eq!(sections[2].start, 7);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, 7);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::SectionMismatch { expected: Some(PSSEDatatype::FixedShunt) });
+    }
+
+    #[test]
+    fn unlabeled_sections_follow_order() {
+        let (sections, diagnostics) = locate("0, 100.0, 35, 0, 1, 60.0
+TITLE 1
+TITLE 2
+GENERAL, THRSHZ=0.0001
+0 / END OF SYSTEM-WIDE DATA
+@! I, 'NAME'
+1, 'BUS', 138.0
+0 /
+0 /
+0 / END OF FIXED SHUNT DATA, BEGIN GENERATOR DATA
+1, '1', 10.0
+0 /
+Q
+notes after the end of the case", 35);
+        let datatypes: Vec<PSSEDatatype> = sections.iter().map(|section| section.datatype).collect();
+        assert_eq!(datatypes, vec![PSSEDatatype::Bus, PSSEDatatype::Load, PSSEDatatype::FixedShunt, PSSEDatatype::Generator]);
+        assert_eq!(sections[0].start, 6);
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn unlabeled_v30_sections_follow_order() {
+        let (sections, diagnostics) = locate("0, 100.0, 30
+TITLE 1
+TITLE 2
+1, 'BUS', 138.0
+0 /
+1, '1', 1
+0 /
+1, '1', 10.0
+0 /
+Q", 30);
+        let datatypes: Vec<PSSEDatatype> = sections.iter().map(|section| section.datatype).collect();
+        //V30 has no fixed shunt section, so generators follow the loads
+        assert_eq!(datatypes, vec![PSSEDatatype::Bus, PSSEDatatype::Load, PSSEDatatype::Generator]);
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn substation_blocks_stay_in_one_section() {
+        let (sections, diagnostics) = locate("0, 100.0, 35, 0, 1, 60.0
+TITLE 1
+TITLE 2
+0 / END OF SYSTEM-WIDE DATA, BEGIN BUS DATA
+1, 'BUS', 138.0
+0 / END OF BUS DATA, BEGIN SUBSTATION DATA
+1, 'STATION'
+0 / BEGIN SUBSTATION NODE DATA
+1, 'NB1', 1, 1
+0 / END OF SUBSTATION NODE DATA, BEGIN SUBSTATION SWITCHING DEVICE DATA
+0 / END OF SUBSTATION SWITCHING DEVICE DATA, BEGIN SUBSTATION TERMINAL DATA
+1, 1, 'M', '1'
+0 / END OF SUBSTATION TERMINAL DATA
+0 / END OF SUBSTATION DATA, BEGIN INDUCTION MACHINE DATA
+1, 'M1'
+0 / END OF INDUCTION MACHINE DATA
+Q", 35);
+        let datatypes: Vec<PSSEDatatype> = sections.iter().map(|section| section.datatype).collect();
+        assert_eq!(datatypes, vec![PSSEDatatype::Bus, PSSEDatatype::Substation, PSSEDatatype::InductionMachine]);
+        assert_eq!((sections[1].start, sections[1].end), (6, 13));
+        //Both sections are found out of their V35 place
+        assert_eq!(diagnostics.len(), 2);
+    }
+}
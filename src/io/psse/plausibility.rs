@@ -0,0 +1,339 @@
+use std::fmt::{self, Display};
+
+use super::pssedata::{PSSEData, PSSEDatatype, SourceMap};
+
+/// How serious a [`Finding`] is, from least to most
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// Lowercase name, as written in reports
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// An engineering plausibility check run by [`check_plausibility`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// Branch reactance X smaller in magnitude than [`PlausibilityRules::min_reactance`]
+    NearZeroReactance,
+    /// Branch resistance R below zero
+    NegativeResistance,
+    /// Bus normal voltage limits with NVLO at or above NVHI
+    VoltageLimitsInverted,
+    /// Generator reactive power limits with QB above QT
+    ReactiveLimitsInverted,
+    /// Generator active power PG outside of PB..PT
+    ActivePowerOutsideLimits,
+    /// Transformer winding 1 ratio limits with RMI1 above RMA1
+    TapLimitsInverted,
+    /// Bus base voltage BASKV of zero
+    ZeroBaseKv,
+    /// Branch or transformer winding RATE1 above a non-zero RATE2
+    RatingsOutOfOrder,
+    /// Bus BASKV of 999.99 or load PL or QL of 99.99, the defaults the parser fills in for a blank field
+    ///
+    /// Values the lines of a change case gave explicitly, as kept in its [`SourceMap`], are not flagged.
+    SentinelDefault,
+}
+
+impl Rule {
+    /// Every rule, in the order they are run
+    pub const ALL: [Rule; 9] = [
+        Rule::NearZeroReactance,
+        Rule::NegativeResistance,
+        Rule::VoltageLimitsInverted,
+        Rule::ReactiveLimitsInverted,
+        Rule::ActivePowerOutsideLimits,
+        Rule::TapLimitsInverted,
+        Rule::ZeroBaseKv,
+        Rule::RatingsOutOfOrder,
+        Rule::SentinelDefault,
+    ];
+
+    /// Severity given to the rule by [`PlausibilityRules::default`]
+    pub fn default_severity(self) -> Severity {
+        match self {
+            Rule::NearZeroReactance | Rule::VoltageLimitsInverted | Rule::ReactiveLimitsInverted | Rule::TapLimitsInverted | Rule::ZeroBaseKv => Severity::Error,
+            Rule::NegativeResistance | Rule::ActivePowerOutsideLimits | Rule::RatingsOutOfOrder | Rule::SentinelDefault => Severity::Warning,
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Rule::NearZeroReactance => "reactance is near zero",
+            Rule::NegativeResistance => "resistance is negative",
+            Rule::VoltageLimitsInverted => "low voltage limit is not below the high limit",
+            Rule::ReactiveLimitsInverted => "minimum reactive power is above the maximum",
+            Rule::ActivePowerOutsideLimits => "active power is outside of its limits",
+            Rule::TapLimitsInverted => "minimum ratio is above the maximum",
+            Rule::ZeroBaseKv => "base voltage is zero",
+            Rule::RatingsOutOfOrder => "RATE1 is above RATE2",
+            Rule::SentinelDefault => "value is the default of a blank field",
+        }
+    }
+}
+
+/// Which rules [`check_plausibility`] runs and the severity of their findings
+///
+/// ```
+/// use pf_gat_parser::io::psse::plausibility::{PlausibilityRules, Rule, Severity};
+///
+/// let mut rules = PlausibilityRules::default()
+///     .with_severity(Rule::NegativeResistance, Severity::Error)
+///     .without(Rule::SentinelDefault);
+/// rules.min_reactance = 1e-5;
+/// assert_eq!(rules.severity(Rule::SentinelDefault), None);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlausibilityRules {
+    /// ## Reactance magnitude (p.u.) below which [`Rule::NearZeroReactance`] flags a branch
+    pub min_reactance: f64,
+    severities: [Option<Severity>; Rule::ALL.len()],
+}
+
+impl Default for PlausibilityRules {
+    /// Every rule at its default severity, flagging reactances under 1e-4 p.u.
+    fn default() -> Self {
+        PlausibilityRules { min_reactance: 1e-4, severities: Rule::ALL.map(|rule| Some(rule.default_severity())) }
+    }
+}
+
+impl PlausibilityRules {
+    /// Runs `rule` with its findings at `severity`
+    pub fn with_severity(mut self, rule: Rule, severity: Severity) -> Self {
+        self.severities[rule as usize] = Some(severity);
+        self
+    }
+
+    /// Skips `rule`
+    pub fn without(mut self, rule: Rule) -> Self {
+        self.severities[rule as usize] = None;
+        self
+    }
+
+    /// Severity of the findings of `rule`, or None if it is skipped
+    pub fn severity(&self, rule: Rule) -> Option<Severity> {
+        self.severities[rule as usize]
+    }
+}
+
+/// A suspicious value found by [`check_plausibility`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// ## Rule that flagged the value
+    pub rule: Rule,
+    /// ## Severity of the rule
+    pub severity: Severity,
+    /// ## Absolute (1-based) line number of the record in the .RAW file, if known (see [`SourceMap`])
+    pub line: Option<usize>,
+    /// ## Section of the record
+    pub section: PSSEDatatype,
+    /// ## Record identified by its buses and ID
+    pub record: String,
+    /// ## PSS/E mnemonic of the flagged field
+    pub field: &'static str,
+    /// ## Value of the flagged field
+    pub value: f64,
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.severity.name())?;
+        if let Some(line) = self.line {
+            write!(f, "line {} ", line)?;
+        }
+        write!(f, "({:?} data {}): {} = {}, {}", self.section, self.record, self.field, self.value, self.rule.description())
+    }
+}
+
+/// The findings of [`check_plausibility`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlausibilityReport {
+    /// ## Findings in the order of the sections of the case
+    pub findings: Vec<Finding>,
+}
+
+impl PlausibilityReport {
+    /// The most severe finding's severity, or None if nothing was flagged
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.findings.iter().map(|finding| finding.severity).max()
+    }
+
+    /// The findings as a JSON list with one object per finding, keyed by the names of the [`Finding`] fields
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::to_value(&self.findings)
+    }
+}
+
+/// Flags values of a case that are valid PSS/E data but unlikely to be intended
+///
+/// Each [`Rule`] enabled in `rules` is run over the records it applies to and every value it flags becomes a [`Finding`]
+/// at the rule's severity, tied to the line `source` gives for the record. Pass an empty [`SourceMap`] for cases not read from a .RAW file.
+///
+/// ```no_run
+/// use pf_gat_parser::prelude::*;
+///
+/// let (case, source, _) = open_case("case.raw", &ParseOptions::strict()).unwrap();
+/// let report: PlausibilityReport = check_plausibility(&case, &source, &PlausibilityRules::default());
+/// for finding in &report.findings {
+///     println!("{}", finding);
+/// }
+/// ```
+pub fn check_plausibility(data: &PSSEData, source: &SourceMap, rules: &PlausibilityRules) -> PlausibilityReport {
+    let mut checker: Checker = Checker { source, rules, findings: Vec::new() };
+    //A change record that gave the value explicitly did not leave it blank
+    let sentinel = |section: PSSEDatatype, position: usize, field: &str, value: f64, default: f64| {
+        value == default && !source.given_fields(section, position).and_then(<[_]>::first).is_some_and(|given| given.contains(&field))
+    };
+    for (position, bus) in data.buses.iter().enumerate() {
+        let record: String = bus.id.to_string();
+        let flag = |rule: Rule, field: &'static str, value: f64| (PSSEDatatype::Bus, position, &record, rule, field, value);
+        checker.check(bus.nvlo >= bus.nvhi, flag(Rule::VoltageLimitsInverted, "NVLO", bus.nvlo));
+        checker.check(bus.base_kv == 0.0, flag(Rule::ZeroBaseKv, "BASKV", bus.base_kv));
+        checker.check(sentinel(PSSEDatatype::Bus, position, "BASKV", bus.base_kv, 999.99), flag(Rule::SentinelDefault, "BASKV", bus.base_kv));
+    }
+    for (position, load) in data.loads.iter().enumerate() {
+        let record: String = format!("{} '{}'", load.bus_id, load.id);
+        let flag = |rule: Rule, field: &'static str, value: f64| (PSSEDatatype::Load, position, &record, rule, field, value);
+        checker.check(sentinel(PSSEDatatype::Load, position, "PL", load.pl_mw, 99.99), flag(Rule::SentinelDefault, "PL", load.pl_mw));
+        checker.check(sentinel(PSSEDatatype::Load, position, "QL", load.ql_mvar, 99.99), flag(Rule::SentinelDefault, "QL", load.ql_mvar));
+    }
+    for (position, generator) in data.generators.iter().enumerate() {
+        let record: String = format!("{} '{}'", generator.bus_id, generator.id);
+        let flag = |rule: Rule, field: &'static str, value: f64| (PSSEDatatype::Generator, position, &record, rule, field, value);
+        checker.check(generator.qmin > generator.qmax, flag(Rule::ReactiveLimitsInverted, "QB", generator.qmin));
+        checker.check(generator.pgen < generator.pmin || generator.pgen > generator.pmax, flag(Rule::ActivePowerOutsideLimits, "PG", generator.pgen));
+    }
+    for (position, branch) in data.branches.iter().enumerate() {
+        let record: String = format!("{}-{} '{}'", branch.from_bus, branch.to_bus, branch.circuit);
+        let flag = |rule: Rule, field: &'static str, value: f64| (PSSEDatatype::Branch, position, &record, rule, field, value);
+        checker.check(branch.x.abs() < rules.min_reactance, flag(Rule::NearZeroReactance, "X", branch.x));
+        checker.check(branch.r < 0.0, flag(Rule::NegativeResistance, "R", branch.r));
+        checker.check(branch.rate2 > 0.0 && branch.rate1 > branch.rate2, flag(Rule::RatingsOutOfOrder, "RATE1", branch.rate1));
+    }
+    for (position, transformer) in data.transformers.iter().enumerate() {
+        let record: String = format!("{}-{}-{} '{}'", transformer.from_bus, transformer.to_bus, transformer.tertiary_bus, transformer.circuit);
+        let flag = |rule: Rule, field: &'static str, value: f64| (PSSEDatatype::Transformer, position, &record, rule, field, value);
+        checker.check(transformer.rmi1 > transformer.rma1, flag(Rule::TapLimitsInverted, "RMI1", transformer.rmi1));
+        checker.check(transformer.w1_rate2 > 0.0 && transformer.w1_rate1 > transformer.w1_rate2, flag(Rule::RatingsOutOfOrder, "RATE1", transformer.w1_rate1));
+        checker.check(transformer.w2_rate2 > 0.0 && transformer.w2_rate1 > transformer.w2_rate2, flag(Rule::RatingsOutOfOrder, "RATE1", transformer.w2_rate1));
+        //Two-winding transformers have no winding 3 ratings
+        let three_winding: bool = transformer.tertiary_bus != 0;
+        checker.check(three_winding && transformer.w3_rate2 > 0.0 && transformer.w3_rate1 > transformer.w3_rate2, flag(Rule::RatingsOutOfOrder, "RATE1", transformer.w3_rate1));
+    }
+    PlausibilityReport { findings: checker.findings }
+}
+
+struct Checker<'a> {
+    source: &'a SourceMap,
+    rules: &'a PlausibilityRules,
+    findings: Vec<Finding>,
+}
+
+impl Checker<'_> {
+    fn check(&mut self, flagged: bool, (section, position, record, rule, field, value): (PSSEDatatype, usize, &String, Rule, &'static str, f64)) {
+        let Some(severity) = self.rules.severity(rule) else { return };
+        if flagged {
+            let line: Option<usize> = self.source.line(section, position);
+            self.findings.push(Finding { rule, severity, line, section, record: record.clone(), field, value });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::psse::{error::Diagnostic, options::ParseOptions, parsers::fast_parser::parse_bytes};
+
+    use super::*;
+
+    const CASE: &str = "0, 100.0, 33, 0, 0, 60.0
+PLAUSIBILITY CASE
+
+101, 'NORTH', 138.0, 3, 1, 1, 1, 1.0, 0.0, 1.1, 0.9
+102, 'SOUTH',      , 1, 1, 1, 1, 1.0, 0.0, 0.9, 1.1
+103, 'EAST', 138.0, 1, 1, 1, 1, 1.0, 0.0, 1.1, 0.9
+0 / END OF BUS DATA, BEGIN LOAD DATA
+102, '1', 1, 1, 1,     , 5.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0
+101, '2', 1, 1, 1, 99.99, 0.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0
+0 / END OF LOAD DATA, BEGIN FIXED SHUNT DATA
+0 / END OF FIXED SHUNT DATA, BEGIN GENERATOR DATA
+101, '1', 150.0, 0.0, 50.0, -50.0, 1.0, 0, 100.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1, 100.0, 100.0, 0.0, 1, 1.0
+0 / END OF GENERATOR DATA, BEGIN BRANCH DATA
+101, 102, '1', -0.01, 0.00001, 0.0, 200.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+0 / END OF BRANCH DATA, BEGIN TRANSFORMER DATA
+101, 102, 103, '1', 1, 1, 1, 0.0, 0.0, 2, 'T3', 1, 1, 1.0, 0, 1.0, 0, 1.0, 0, 1.0, ''
+0.0, 0.05, 100.0, 0.0, 0.05, 100.0, 0.0, 0.05, 100.0, 1.0, 0.0
+1.0, 0.0, 0.0, 100.0, 120.0, 130.0, 0, 0, 1.1, 0.9, 1.1, 0.9, 33, 0, 0.0, 0.0, 0.0
+1.0, 0.0, 0.0, 150.0, 120.0, 130.0, 0, 0, 1.1, 0.9, 1.1, 0.9, 33, 0, 0.0, 0.0, 0.0
+1.0, 0.0, 0.0, 140.0, 120.0, 130.0, 0, 0, 1.1, 0.9, 1.1, 0.9, 33, 0, 0.0, 0.0, 0.0
+0 / END OF TRANSFORMER DATA
+Q
+";
+
+    #[test]
+    fn plausibility_findings() {
+        let (data, source, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(CASE.as_bytes(), &ParseOptions::strict()).unwrap();
+        let report: PlausibilityReport = check_plausibility(&data, &source, &PlausibilityRules::default());
+        let flagged: Vec<(Rule, Option<usize>, &str)> = report.findings.iter().map(|finding| (finding.rule, finding.line, finding.field)).collect();
+        //The ratings of transformer windings 2 and 3 are out of order
+        assert_eq!(flagged, vec![
+            (Rule::VoltageLimitsInverted, Some(5), "NVLO"),
+            (Rule::SentinelDefault, Some(5), "BASKV"),
+            (Rule::SentinelDefault, Some(8), "PL"),
+            (Rule::SentinelDefault, Some(9), "PL"),
+            (Rule::ActivePowerOutsideLimits, Some(12), "PG"),
+            (Rule::NearZeroReactance, Some(14), "X"),
+            (Rule::NegativeResistance, Some(14), "R"),
+            (Rule::RatingsOutOfOrder, Some(14), "RATE1"),
+            (Rule::RatingsOutOfOrder, Some(16), "RATE1"),
+            (Rule::RatingsOutOfOrder, Some(16), "RATE1"),
+        ]);
+        assert_eq!((report.findings[8].value, report.findings[9].value), (150.0, 140.0));
+        assert_eq!(report.max_severity(), Some(Severity::Error));
+
+        //Rules can be skipped or given another severity
+        let rules: PlausibilityRules = PlausibilityRules::default().without(Rule::SentinelDefault).with_severity(Rule::NearZeroReactance, Severity::Info);
+        let report: PlausibilityReport = check_plausibility(&data, &source, &rules);
+        assert_eq!(report.findings.len(), 7);
+        assert_eq!(report.findings[2].severity, Severity::Info);
+        assert_eq!(report.findings[2].to_string(), "info: line 14 (Branch data 101-102 '1'): X = 0.00001, reactance is near zero");
+
+        //Cases built in code are checked by value too
+        let report: PlausibilityReport = check_plausibility(&data, &SourceMap::default(), &PlausibilityRules::default());
+        assert_eq!(report.findings.len(), 10);
+        assert!(report.findings.iter().all(|finding| finding.line.is_none()));
+    }
+
+    #[test]
+    fn sentinel_given_in_change_case() {
+        //A change case that gives PL as 99.99 did not leave it blank
+        let changes: String = CASE.replacen("0, 100.0", "1, 100.0", 1);
+        let (data, source, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(changes.as_bytes(), &ParseOptions::strict()).unwrap();
+        let report: PlausibilityReport = check_plausibility(&data, &source, &PlausibilityRules::default());
+        let sentinels: Vec<(Option<usize>, &str)> = report.findings.iter().filter(|finding| finding.rule == Rule::SentinelDefault).map(|finding| (finding.line, finding.field)).collect();
+        assert_eq!(sentinels, vec![(Some(5), "BASKV"), (Some(8), "PL")]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn plausibility_json() {
+        let (data, source, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(CASE.as_bytes(), &ParseOptions::strict()).unwrap();
+        let json: serde_json::Value = check_plausibility(&data, &source, &PlausibilityRules::default()).to_json().unwrap();
+        assert_eq!(json[2], serde_json::json!({"rule": "sentinel_default", "severity": "warning", "line": 8, "section": "Load", "record": "102 '1'", "field": "PL", "value": 99.99}));
+    }
+}
@@ -1,8 +1,10 @@
+use std::collections::HashMap;
+
 use super::components::structs::*;
 
 // Enum to keep track of which section we are parsing
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PSSEDatatype {
     Header,
     Bus,
@@ -10,6 +12,7 @@ pub enum PSSEDatatype {
     FixedShunt,
     Generator,
     Branch,
+    SystemSwitchingDevice,
     Transformer,
     Area,
     TwoTerminalDc,
@@ -23,12 +26,14 @@ pub enum PSSEDatatype {
     Facts,
     SwitchedShunt,
     GneDevice,
+    InductionMachine,
+    Substation,
     Unknown, // For lines we don't recognize or sections not yet handled
     EndOfFile,
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct PSSEData {
     pub header: HeaderInfo,
     pub buses: Vec<Bus>,
@@ -49,5 +54,54 @@ pub struct PSSEData {
     pub owners: Vec<Owner>,
     pub facts: Vec<Facts>,
     pub switched_shunts: Vec<SwitchedShunt>,
+    pub gne_devices: Vec<GneDevice>,
     pub induction_machines: Vec<InductionMachine>,
+    pub substations: Vec<Substation>,
+}
+
+/// Where the records of a case were read from in its .RAW file
+///
+/// Returned by the .RAW parsers next to the case, see [`parse_bytes`](crate::io::psse::parsers::fast_parser::parse_bytes).
+/// Records of the fixed length sections have a line, records of the impedance correction, multi-terminal DC,
+/// GNE and substation sections have none. Lines follow the positions of the records in their vectors
+/// as parsed, so they no longer match once records are added, removed or reordered.
+/// For change cases (IC = 1) the map also keeps which fields each line gave a value for,
+/// telling the values of the change file apart from the defaults the parser filled in for blank or omitted fields.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMap {
+    lines: HashMap<PSSEDatatype, Vec<usize>>,
+    given: HashMap<PSSEDatatype, Vec<Vec<Vec<&'static str>>>>,
+}
+
+impl SourceMap {
+    /// Absolute (1-based) line number of the first line of the record at `index` in `section`
+    pub fn line(&self, section: PSSEDatatype, index: usize) -> Option<usize> {
+        self.lines.get(&section)?.get(index).copied()
+    }
+
+    /// Records the lines of the next records of `section`
+    pub(crate) fn extend(&mut self, section: PSSEDatatype, lines: impl IntoIterator<Item = usize>) {
+        self.lines.entry(section).or_default().extend(lines);
+    }
+
+    /// The mnemonics of the fields each line of the record at `index` in `section` gave a value for
+    ///
+    /// Only kept for the records of change cases, None for any other record.
+    pub(crate) fn given_fields(&self, section: PSSEDatatype, index: usize) -> Option<&[Vec<&'static str>]> {
+        self.given.get(&section)?.get(index).map(Vec::as_slice)
+    }
+
+    /// Records the fields given by the lines of the next records of `section`
+    pub(crate) fn extend_given(&mut self, section: PSSEDatatype, records: impl IntoIterator<Item = Vec<Vec<&'static str>>>) {
+        self.given.entry(section).or_default().extend(records);
+    }
+}
+
+impl PSSEData {
+    /// Finds the substation node `node` of bus `bus_id`, as referenced by the NREG fields of regulating equipment
+    pub fn substation_node(&self, bus_id: i32, node: i32) -> Option<&SubstationNode> {
+        self.substations.iter()
+            .flat_map(|substation| substation.nodes.iter())
+            .find(|substation_node| substation_node.bus_id == bus_id && substation_node.node == node)
+    }
 }
\ No newline at end of file
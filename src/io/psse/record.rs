@@ -0,0 +1,363 @@
+use std::{cell::RefCell, collections::HashMap, str::{from_utf8, FromStr}, sync::Mutex};
+
+use super::{error::{Diagnostic, ParseError}, options::{ParseMode, ParseOptions}, parsers::columns::ColumnMap, pssedata::PSSEDatatype, tokenizer::Tokenizer};
+
+/// Location and options handed to each component parser
+///
+/// Component parsers only receive the lines belonging to their section, so the context keeps
+/// track of where those lines sit in the .RAW file to report absolute line numbers.
+/// In lenient mode it also collects the diagnostics raised while parsing the section.
+/// For change cases it also keeps the fields each line gave a value for.
+/// Sections whose columns are located by their `@!` headings carry the positions of the columns the parser reads.
+#[derive(Debug)]
+pub struct SectionContext {
+    /// ## Absolute (1-based) line number of the first line handed to the parser
+    pub first_line: usize,
+    /// ## Strict or lenient handling of malformed records
+    pub mode: ParseMode,
+    diagnostics: Mutex<Vec<Diagnostic>>,
+    columns: Option<ColumnMap>,
+    fields: Option<Vec<Vec<String>>>,
+    given: Mutex<Vec<(usize, Vec<&'static str>)>>,
+    change_case: bool,
+}
+
+impl Default for SectionContext {
+    fn default() -> Self {
+        SectionContext::new(1, &ParseOptions::default())
+    }
+}
+
+impl SectionContext {
+    pub fn new(first_line: usize, options: &ParseOptions) -> Self {
+        SectionContext { first_line, mode: options.mode, diagnostics: Mutex::new(Vec::new()), columns: None, fields: None, given: Mutex::new(Vec::new()), change_case: false }
+    }
+
+    /// Reads the fields of the records by the column positions found in the section's headings
+    pub(crate) fn with_columns(self, columns: ColumnMap) -> Self {
+        SectionContext { columns: Some(columns), ..self }
+    }
+
+    /// Reads the records of a change case (IC = 1)
+    ///
+    /// A change record may stop after the fields it changes, so fields left off the end of its line are not reported.
+    /// The fields each line gives a value for are kept, see [`SectionContext::take_given`].
+    pub(crate) fn for_change_case(self) -> Self {
+        SectionContext { change_case: true, ..self }
+    }
+
+    /// Takes the fields of each line of the section from `fields` instead of splitting the line
+    ///
+    /// Used for records whose values are already separated, such as the rows of a .RAWX table.
+    /// The lines handed to the parser then only tell a record apart from a terminator or a blank line.
+    pub(crate) fn with_fields(self, fields: Vec<Vec<String>>) -> Self {
+        SectionContext { fields: Some(fields), ..self }
+    }
+
+    /// Absolute line number of the line at `index` within the section
+    pub fn line_number(&self, index: usize) -> usize {
+        self.first_line + index
+    }
+
+    /// Diagnostics collected while parsing the section, ordered by line
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<Diagnostic> = self.diagnostics.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+        diagnostics.sort_by_key(|diagnostic| diagnostic.line);
+        diagnostics
+    }
+
+    /// Adds the mnemonics of the fields each line of a change case gave a value for to `lines`, keyed by absolute line number
+    ///
+    /// Lines that left all the fields read blank have no entry, and nothing is kept outside change cases.
+    /// A line read more than once (to find out the shape of its record first) lists each field once.
+    pub(crate) fn take_given(&self, lines: &mut HashMap<usize, Vec<&'static str>>) {
+        let given: Vec<(usize, Vec<&'static str>)> = std::mem::take(&mut *self.given.lock().unwrap_or_else(|poisoned| poisoned.into_inner()));
+        for (line, fields) in given {
+            let known: &mut Vec<&'static str> = lines.entry(line).or_default();
+            for field in fields {
+                if !known.contains(&field) {
+                    known.push(field);
+                }
+            }
+        }
+    }
+
+    /// Fails with the error in strict mode, or records it as a diagnostic in lenient mode
+    pub(crate) fn report(&self, err: ParseError) -> Result<(), ParseError> {
+        match self.mode {
+            ParseMode::Strict => Err(err),
+            ParseMode::Lenient => {
+                let diagnostic: Diagnostic = Diagnostic::from_error(err)?;
+                self.diagnostics.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(diagnostic);
+                Ok(())
+            }
+        }
+    }
+
+    /// Splits the line at `index` of the section into a record
+    pub(crate) fn record<'a>(&'a self, line_bytes: &'a [u8], index: usize, section: PSSEDatatype) -> Result<Record<'a>, ParseError> {
+        self.record_at(line_bytes, index, 0, section)
+    }
+
+    /// Splits the line at `index` of the section, the `record_line`-th line of its record, into a record
+    fn record_at<'a>(&'a self, line_bytes: &'a [u8], index: usize, record_line: usize, section: PSSEDatatype) -> Result<Record<'a>, ParseError> {
+        let line: usize = self.line_number(index);
+        let columns: Option<&'a [Option<usize>]> = self.columns.as_ref().and_then(|columns| columns.get(record_line)).map(Vec::as_slice);
+        if let Some(fields) = &self.fields {
+            let parts: Vec<&'a str> = fields.get(index).map_or_else(Vec::new, |fields| fields.iter().map(String::as_str).collect());
+            return Ok(Record::from_parts(parts, columns, line, section, self));
+        }
+        match from_utf8(line_bytes) {
+            Ok(text) => Ok(Record::from_parts(Tokenizer::new(text).collect(), columns, line, section, self)),
+            Err(_) => {
+                self.report(ParseError::InvalidEncoding { line, section })?;
+                Ok(Record::from_parts(Vec::new(), columns, line, section, self))
+            }
+        }
+    }
+
+    /// Splits the `N` lines of a multi-line record starting at `start`, failing if the section ends early
+    ///
+    /// In lenient mode the lines past the end of the section are treated as blank.
+    pub(crate) fn records<'a, const N: usize>(&'a self, lines: &[&'a [u8]], start: usize, section: PSSEDatatype) -> Result<[Record<'a>; N], ParseError> {
+        if start + N > lines.len() {
+            self.report(ParseError::TruncatedRecord { line: self.line_number(start), section, expected_lines: N })?;
+        }
+        let mut records: Vec<Record<'a>> = Vec::with_capacity(N);
+        for index in start..start + N {
+            match lines.get(index) {
+                Some(line_bytes) => records.push(self.record_at(line_bytes, index, index - start, section)?),
+                None => records.push(Record::new("", self.line_number(index), section, self)),
+            }
+        }
+        Ok(records.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+
+    /// Splits the line `offset` lines into a record of `expected_lines` lines starting at `start`
+    ///
+    /// Used for records whose number of lines is only known once the first line has been read.
+    /// Running past the end of the section fails in strict mode, and gives blank lines in lenient mode.
+    pub(crate) fn record_line<'a>(&'a self, lines: &[&'a [u8]], start: usize, offset: usize, expected_lines: usize, section: PSSEDatatype) -> Result<Record<'a>, ParseError> {
+        let index: usize = start + offset;
+        match lines.get(index) {
+            Some(line_bytes) => self.record(line_bytes, index, section),
+            None => {
+                //Only report the truncation once, on the first missing line
+                if index == lines.len() {
+                    self.report(ParseError::TruncatedRecord { line: self.line_number(start), section, expected_lines })?;
+                }
+                Ok(Record::new("", self.line_number(index), section, self))
+            }
+        }
+    }
+}
+
+/// A single line of a .RAW record split into its fields
+///
+/// The line is split with the shared [`Tokenizer`], and fields are read by position along with
+/// their PSS/E mnemonic so a failure can be reported against the exact line, section and field.
+/// The positions are those of the V35 layout, with the columns V36 inserted numbered after them,
+/// which are looked up in the column map of sections located by heading.
+/// In change cases the fields the line gave a value for are handed back to the context when the record is dropped.
+pub(crate) struct Record<'a> {
+    parts: Vec<&'a str>,
+    columns: Option<&'a [Option<usize>]>,
+    line: usize,
+    section: PSSEDatatype,
+    ctx: &'a SectionContext,
+    given: RefCell<Vec<&'static str>>,
+}
+
+impl<'a> Record<'a> {
+    pub fn new(text: &'a str, line: usize, section: PSSEDatatype, ctx: &'a SectionContext) -> Self {
+        Record::from_parts(Tokenizer::new(text).collect(), None, line, section, ctx)
+    }
+
+    fn from_parts(parts: Vec<&'a str>, columns: Option<&'a [Option<usize>]>, line: usize, section: PSSEDatatype, ctx: &'a SectionContext) -> Self {
+        Record { parts, columns, line, section, ctx, given: RefCell::new(Vec::new()) }
+    }
+
+    /// Number of fields on the line
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// Reads a field that must be present on the line
+    ///
+    /// A blank field means "use the default", but a line too short to contain the field is an error.
+    pub fn required<T: FromStr>(&self, index: usize, field: &'static str, default: T) -> Result<T, ParseError> {
+        match self.get(index, field) {
+            Some(text) => self.convert(text, field, default),
+            None => self.missing(field, default),
+        }
+    }
+
+    /// Reads a field that may be left off the end of the line, falling back to the default
+    pub fn optional<T: FromStr>(&self, index: usize, field: &'static str, default: T) -> Result<T, ParseError> {
+        match self.get(index, field) {
+            Some(text) => self.convert(text, field, default),
+            None => Ok(default),
+        }
+    }
+
+    /// Reads a quoted text field that must be present on the line
+    pub fn required_str(&self, index: usize, field: &'static str) -> Result<String, ParseError> {
+        match self.get(index, field) {
+            Some(text) => Ok(text.to_string()),
+            None => self.missing(field, String::new()),
+        }
+    }
+
+    /// Reads a quoted text field that may be left off the end of the line
+    pub fn optional_str(&self, index: usize, field: &'static str, default: &str) -> Result<String, ParseError> {
+        match self.get(index, field) {
+            Some(text) => Ok(text.to_string()),
+            None => Ok(default.to_string()),
+        }
+    }
+
+    /// Reads the number of values that follow on the line, which must be between 0 and `most`
+    ///
+    /// A count out of range is an invalid field, so a corrupt count can't size the record past the values the line holds.
+    pub fn count(&self, index: usize, field: &'static str, default: i32, most: usize) -> Result<i32, ParseError> {
+        let count: i32 = self.required(index, field, default)?;
+        if usize::try_from(count).is_ok_and(|count| count <= most) {
+            return Ok(count);
+        }
+        self.ctx.report(ParseError::InvalidField { line: self.line, section: self.section, field, text: count.to_string() })?;
+        Ok(default)
+    }
+
+    //A column missing from the headings is treated like a field left off the end of the line
+    fn get(&self, index: usize, field: &'static str) -> Option<&'a str> {
+        let text: Option<&'a str> = match self.columns {
+            Some(columns) => columns.get(index).copied().flatten().and_then(|position| self.parts.get(position)).copied(),
+            None => self.parts.get(index).copied(),
+        };
+        if self.ctx.change_case && text.is_some_and(|text| !text.is_empty()) {
+            self.given.borrow_mut().push(field);
+        }
+        text
+    }
+
+    fn convert<T: FromStr>(&self, text: &str, field: &'static str, default: T) -> Result<T, ParseError> {
+        if text.is_empty() {
+            return Ok(default);
+        }
+        match text.parse() {
+            Ok(value) => Ok(value),
+            Err(_) => {
+                self.ctx.report(ParseError::InvalidField {
+                    line: self.line,
+                    section: self.section,
+                    field,
+                    text: text.to_string(),
+                })?;
+                Ok(default)
+            }
+        }
+    }
+
+    fn missing<T>(&self, field: &'static str, default: T) -> Result<T, ParseError> {
+        if self.ctx.change_case {
+            return Ok(default);
+        }
+        self.ctx.report(ParseError::MissingField { line: self.line, section: self.section, field })?;
+        Ok(default)
+    }
+}
+
+impl Drop for Record<'_> {
+    fn drop(&mut self) {
+        let given: Vec<&'static str> = std::mem::take(self.given.get_mut());
+        if !given.is_empty() {
+            self.ctx.given.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push((self.line, given));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::psse::error::DiagnosticKind;
+
+    use super::*;
+
+    #[test]
+    fn record_reports_line_and_field() {
+        let ctx: SectionContext = SectionContext::default();
+        let record: Record = Record::new("  347, 'TESTBUS', abc", 12, PSSEDatatype::Bus, &ctx);
+        assert_eq!(record.required::<i32>(0, "I", 0).unwrap(), 347);
+        assert_eq!(record.required_str(1, "NAME").unwrap(), "TESTBUS".to_string());
+        match record.required::<f64>(2, "BASKV", 0.0) {
+            Err(ParseError::InvalidField { line, section, field, text }) => {
+                assert_eq!(line, 12);
+                assert_eq!(section, PSSEDatatype::Bus);
+                assert_eq!(field, "BASKV");
+                assert_eq!(text, "abc".to_string());
+            }
+            other => panic!("expected an invalid field error, got {:?}", other),
+        }
+        assert!(matches!(record.required::<i8>(3, "IDE", 1), Err(ParseError::MissingField { line: 12, field: "IDE", .. })));
+        assert_eq!(record.optional::<i8>(3, "IDE", 1).unwrap(), 1);
+    }
+
+    #[test]
+    fn blank_field_uses_default() {
+        let ctx: SectionContext = SectionContext::default();
+        let record: Record = Record::new("347,,", 1, PSSEDatatype::Bus, &ctx);
+        assert_eq!(record.required::<f64>(1, "BASKV", 999.99).unwrap(), 999.99);
+        assert_eq!(record.required::<i8>(2, "IDE", 1).unwrap(), 1);
+    }
+
+    #[test]
+    fn quoted_names_and_comments() {
+        let ctx: SectionContext = SectionContext::default();
+        let record: Record = Record::new("347 'NORTH, 1/A' 138.0 / bus comment", 1, PSSEDatatype::Bus, &ctx);
+        assert_eq!(record.len(), 3);
+        assert_eq!(record.required_str(1, "NAME").unwrap(), "NORTH, 1/A".to_string());
+        assert_eq!(record.required::<f64>(2, "BASKV", 0.0).unwrap(), 138.0);
+    }
+
+    #[test]
+    fn lenient_records_diagnostics() {
+        let ctx: SectionContext = SectionContext::new(1, &ParseOptions::lenient());
+        let record: Record = Record::new("347, abc", 12, PSSEDatatype::Bus, &ctx);
+        assert_eq!(record.required::<f64>(1, "BASKV", 0.0).unwrap(), 0.0);
+        assert_eq!(record.required::<i8>(2, "IDE", 1).unwrap(), 1);
+        assert!(ctx.records::<2>(&[b"1,2".as_slice()], 0, PSSEDatatype::Transformer).is_ok());
+        drop(record);
+        let diagnostics: Vec<Diagnostic> = ctx.into_diagnostics();
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::TruncatedRecord { expected_lines: 2 });
+        assert_eq!(diagnostics[1].kind, DiagnosticKind::InvalidField { field: "BASKV", text: "abc".to_string() });
+        assert_eq!(diagnostics[2].kind, DiagnosticKind::MissingField { field: "IDE" });
+    }
+
+    #[test]
+    fn given_fields_tracked() {
+        //Only change cases keep the fields given
+        let ctx: SectionContext = SectionContext::default();
+        let record: Record = Record::new("347, 'NEWNAME'", 5, PSSEDatatype::Bus, &ctx);
+        assert_eq!(record.required::<i32>(0, "I", 0).unwrap(), 347);
+        drop(record);
+        let mut given: HashMap<usize, Vec<&str>> = HashMap::new();
+        ctx.take_given(&mut given);
+        assert!(given.is_empty());
+
+        let ctx: SectionContext = SectionContext::new(5, &ParseOptions::default()).for_change_case();
+        let record: Record = Record::new("347, 'NEWNAME', , 2", 5, PSSEDatatype::Bus, &ctx);
+        assert_eq!(record.required::<i32>(0, "I", 0).unwrap(), 347);
+        assert_eq!(record.optional_str(1, "NAME", "").unwrap(), "NEWNAME".to_string());
+        assert_eq!(record.required::<f64>(2, "BASKV", 999.99).unwrap(), 999.99);
+        assert_eq!(record.optional::<i8>(3, "IDE", 1).unwrap(), 2);
+        assert_eq!(record.optional::<i16>(4, "AREA", 1).unwrap(), 1);
+        drop(record);
+        //A line read again lists its fields once
+        let again: Record = Record::new("347", 5, PSSEDatatype::Bus, &ctx);
+        assert_eq!(again.required::<i32>(0, "I", 0).unwrap(), 347);
+        drop(again);
+        ctx.take_given(&mut given);
+        assert_eq!(given.len(), 1);
+        assert_eq!(given[&5], vec!["I", "NAME", "IDE"]);
+    }
+}
@@ -0,0 +1,129 @@
+/// Splits a line of a .RAW record into its fields without copying
+///
+/// Follows the PSS/E free format rules:
+/// * Fields are separated by a comma or by one or more blanks
+/// * Text in single or double quotes is one field, even if it contains commas, blanks or slashes
+/// * A `/` outside of quotes starts a comment, and the rest of the line is ignored
+/// * Consecutive commas (or a trailing comma) give an empty field, meaning "use the default"
+///
+/// Quotes and the padding around and inside them are stripped from the returned fields.
+///
+/// ```
+/// use pf_gat_parser::io::psse::tokenizer::Tokenizer;
+///
+/// let fields: Vec<&str> = Tokenizer::new("101, 'NORTH, 1 ' 138.0,, / bus comment").collect();
+/// assert_eq!(fields, vec!["101", "NORTH, 1", "138.0", "", ""]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tokenizer<'a> {
+    text: &'a str,
+    pos: usize,
+    after_comma: bool,
+    done: bool,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Tokenizer { text, pos: 0, after_comma: false, done: false }
+    }
+
+    fn skip_blanks(&mut self) {
+        let rest: &str = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    //A field also ends at the blanks before a comma, so the comma is consumed along with it
+    fn end_field(&mut self) {
+        self.skip_blanks();
+        self.after_comma = self.peek() == Some(',');
+        if self.after_comma {
+            self.pos += 1;
+        }
+    }
+
+    //Returns the empty field left after a trailing comma, then stops
+    fn finish(&mut self) -> Option<&'a str> {
+        self.done = true;
+        if self.after_comma { Some("") } else { None }
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.done {
+            return None;
+        }
+        self.skip_blanks();
+        let field: &'a str = match self.peek() {
+            None | Some('/') => return self.finish(),
+            Some(',') => {
+                self.pos += 1;
+                self.after_comma = true;
+                return Some("");
+            }
+            Some(quote @ ('\'' | '"')) => {
+                let start: usize = self.pos + 1;
+                //An unterminated quote runs to the end of the line
+                let end: usize = self.text[start..].find(quote).map_or(self.text.len(), |offset| start + offset);
+                self.pos = (end + 1).min(self.text.len());
+                self.text[start..end].trim()
+            }
+            Some(_) => {
+                let start: usize = self.pos;
+                let rest: &str = &self.text[start..];
+                let end: usize = rest.find(|c: char| c == ',' || c == '/' || c == '\'' || c == '"' || c.is_whitespace()).map_or(self.text.len(), |offset| start + offset);
+                self.pos = end;
+                &self.text[start..end]
+            }
+        };
+        self.end_field();
+        Some(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokenize(text: &str) -> Vec<&str> {
+        Tokenizer::new(text).collect()
+    }
+
+    #[test]
+    fn comma_separated() {
+        assert_eq!(tokenize("  347, 'TESTBUS     ',    89.4321,   2"), vec!["347", "TESTBUS", "89.4321", "2"]);
+    }
+
+    #[test]
+    fn blank_separated() {
+        assert_eq!(tokenize("347 'TESTBUS' 89.4321   2"), vec!["347", "TESTBUS", "89.4321", "2"]);
+        assert_eq!(tokenize("1 ,2\t, 3"), vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn quoted_delimiters() {
+        assert_eq!(tokenize("1, 'A, B/C', \"D 'E'\""), vec!["1", "A, B/C", "D 'E'"]);
+        assert_eq!(tokenize("1, 'UNTERMINATED, NAME"), vec!["1", "UNTERMINATED, NAME"]);
+    }
+
+    #[test]
+    fn empty_fields() {
+        assert_eq!(tokenize("347,,"), vec!["347", "", ""]);
+        assert_eq!(tokenize(",1"), vec!["", "1"]);
+        assert_eq!(tokenize("1, '', 2"), vec!["1", "", "2"]);
+        assert!(tokenize("   ").is_empty());
+    }
+
+    #[test]
+    fn inline_comments() {
+        assert_eq!(tokenize("0,   923.45, 33 / Comment, on the header"), vec!["0", "923.45", "33"]);
+        assert_eq!(tokenize("1, 2,/ comment"), vec!["1", "2", ""]);
+        assert!(tokenize("/ only a comment").is_empty());
+    }
+}
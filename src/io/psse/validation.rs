@@ -0,0 +1,302 @@
+use std::{collections::{HashMap, HashSet}, fmt::{self, Display}};
+
+use super::{components::codes::BusType, index::CaseIndex, pssedata::{PSSEData, PSSEDatatype, SourceMap}};
+
+/// A referential integrity problem found in a case
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    /// ## Absolute (1-based) line number of the record in the .RAW file, if known (see [`SourceMap`])
+    pub line: Option<usize>,
+    /// ## Section of the record
+    pub section: PSSEDatatype,
+    /// ## Record identified by its buses and ID
+    pub record: String,
+    /// ## What is wrong with the record
+    pub kind: IssueKind,
+}
+
+/// The kind of problem recorded by a [`ValidationIssue`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum IssueKind {
+    /// The field refers to a bus, area, zone, owner or impedance correction table that is not in the case
+    DanglingReference { field: &'static str, target: PSSEDatatype, id: i32 },
+    /// Another record of the section has the same key, the first one being at `first_line`
+    DuplicateKey { first_line: Option<usize> },
+    /// The record is in service and connected to a bus of type 4 (isolated)
+    IsolatedBus { field: &'static str, bus: i32 },
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {} ({:?} data {}): ", line, self.section, self.record)?,
+            None => write!(f, "{:?} data {}: ", self.section, self.record)?,
+        }
+        match &self.kind {
+            IssueKind::DanglingReference { field, target, id } => write!(f, "{} refers to {:?} {}, which is not in the case", field, target, id),
+            IssueKind::DuplicateKey { first_line: Some(line) } => write!(f, "duplicate of the record at line {}", line),
+            IssueKind::DuplicateKey { first_line: None } => write!(f, "duplicate of an earlier record"),
+            IssueKind::IsolatedBus { field, bus } => write!(f, "in service at isolated bus {} ({})", bus, field),
+        }
+    }
+}
+
+/// The problems found by [`validate`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    /// ## Problems in the order of the sections of the case
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Whether no problems were found
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Checks that the records of a case refer to each other consistently
+///
+/// Three kinds of problems are reported, each tied to the line `source` gives for the record
+/// (pass an empty [`SourceMap`] for cases not read from a .RAW file):
+/// * References to buses, areas, zones, owners and impedance correction tables that are not in the case.
+///   Bus areas, zones and owners are only checked against their sections when the case has any.
+///   Zero, which stands for "none" or "the record's own bus" in the optional fields (IREG, CONT1, ISW, TAB1, ...), is not a reference.
+/// * Records sharing their key: bus numbers, branch circuits (in either direction), transformer circuits,
+///   the bus and ID of loads, machines and shunts, and area, zone and owner numbers
+/// * In service loads, machines, shunts, branches and transformers connected to an isolated (type 4) bus
+///
+/// ```no_run
+/// use pf_gat_parser::prelude::*;
+///
+/// let (case, source, _) = open_case("case.raw", &ParseOptions::strict()).unwrap();
+/// for issue in &validate(&case, &source).issues {
+///     eprintln!("{}", issue);
+/// }
+/// ```
+pub fn validate(data: &PSSEData, source: &SourceMap) -> ValidationReport {
+    let mut checker: Checker = Checker::new(data, source);
+    checker.duplicates(&data.buses, PSSEDatatype::Bus, |bus| bus.id.to_string());
+    for (position, bus) in data.buses.iter().enumerate() {
+        let record: String = bus.id.to_string();
+        checker.group(PSSEDatatype::Bus, position, &record, "AREA", PSSEDatatype::Area, bus.area as i32);
+        checker.group(PSSEDatatype::Bus, position, &record, "ZONE", PSSEDatatype::Zone, bus.zone as i32);
+        checker.group(PSSEDatatype::Bus, position, &record, "OWNER", PSSEDatatype::Owner, bus.owner as i32);
+    }
+
+    checker.duplicates(&data.loads, PSSEDatatype::Load, |load| format!("{} '{}'", load.bus_id, load.id));
+    for (position, load) in data.loads.iter().enumerate() {
+        let record: String = format!("{} '{}'", load.bus_id, load.id);
+        checker.terminal(PSSEDatatype::Load, position, &record, "I", load.bus_id, load.status != 0);
+    }
+
+    checker.duplicates(&data.fixed_shunts, PSSEDatatype::FixedShunt, |shunt| format!("{} '{}'", shunt.bus_id, shunt.id));
+    for (position, shunt) in data.fixed_shunts.iter().enumerate() {
+        let record: String = format!("{} '{}'", shunt.bus_id, shunt.id);
+        checker.terminal(PSSEDatatype::FixedShunt, position, &record, "I", shunt.bus_id, shunt.status != 0);
+    }
+
+    checker.duplicates(&data.generators, PSSEDatatype::Generator, |generator| format!("{} '{}'", generator.bus_id, generator.id));
+    for (position, generator) in data.generators.iter().enumerate() {
+        let record: String = format!("{} '{}'", generator.bus_id, generator.id);
+        checker.terminal(PSSEDatatype::Generator, position, &record, "I", generator.bus_id, generator.status != 0);
+        checker.bus(PSSEDatatype::Generator, position, &record, "IREG", generator.reg_bus_id);
+    }
+
+    checker.duplicates(&data.branches, PSSEDatatype::Branch, |branch| {
+        format!("{}-{} '{}'", branch.from_bus.min(branch.to_bus), branch.from_bus.max(branch.to_bus), branch.circuit)
+    });
+    for (position, branch) in data.branches.iter().enumerate() {
+        let record: String = format!("{}-{} '{}'", branch.from_bus, branch.to_bus, branch.circuit);
+        checker.terminal(PSSEDatatype::Branch, position, &record, "I", branch.from_bus, branch.status != 0);
+        checker.terminal(PSSEDatatype::Branch, position, &record, "J", branch.to_bus, branch.status != 0);
+    }
+
+    checker.duplicates(&data.switching_devices, PSSEDatatype::SystemSwitchingDevice, |device| {
+        format!("{}-{} '{}'", device.from_bus.min(device.to_bus), device.from_bus.max(device.to_bus), device.circuit)
+    });
+    for (position, device) in data.switching_devices.iter().enumerate() {
+        let record: String = format!("{}-{} '{}'", device.from_bus, device.to_bus, device.circuit);
+        checker.terminal(PSSEDatatype::SystemSwitchingDevice, position, &record, "I", device.from_bus, device.status != 0);
+        checker.terminal(PSSEDatatype::SystemSwitchingDevice, position, &record, "J", device.to_bus, device.status != 0);
+    }
+
+    checker.duplicates(&data.transformers, PSSEDatatype::Transformer, |transformer| {
+        format!("{}-{}-{} '{}'", transformer.from_bus, transformer.to_bus, transformer.tertiary_bus, transformer.circuit)
+    });
+    for (position, transformer) in data.transformers.iter().enumerate() {
+        let record: String = format!("{}-{}-{} '{}'", transformer.from_bus, transformer.to_bus, transformer.tertiary_bus, transformer.circuit);
+        let in_service: bool = transformer.status != 0;
+        checker.terminal(PSSEDatatype::Transformer, position, &record, "I", transformer.from_bus, in_service);
+        checker.terminal(PSSEDatatype::Transformer, position, &record, "J", transformer.to_bus, in_service);
+        //A negative controlled bus number only sets which side of the transformer is controlled
+        checker.bus(PSSEDatatype::Transformer, position, &record, "CONT1", transformer.controlled_bus_id_1.abs());
+        checker.table(PSSEDatatype::Transformer, position, &record, "TAB1", transformer.impedance_correction_table_1);
+        checker.table(PSSEDatatype::Transformer, position, &record, "TAB2", transformer.impedance_correction_table_2);
+        if transformer.tertiary_bus != 0 {
+            checker.terminal(PSSEDatatype::Transformer, position, &record, "K", transformer.tertiary_bus, in_service);
+            checker.bus(PSSEDatatype::Transformer, position, &record, "CONT2", transformer.controlled_bus_id_2.abs());
+            checker.bus(PSSEDatatype::Transformer, position, &record, "CONT3", transformer.controlled_bus_id_3.abs());
+            checker.table(PSSEDatatype::Transformer, position, &record, "TAB3", transformer.impedance_correction_table_3);
+        }
+    }
+
+    checker.duplicates(&data.areas, PSSEDatatype::Area, |area| area.area_id.to_string());
+    for (position, area) in data.areas.iter().enumerate() {
+        checker.bus(PSSEDatatype::Area, position, &area.area_id.to_string(), "ISW", area.swing_bus_id);
+    }
+
+    for (position, section_line) in data.multi_section_line.iter().enumerate() {
+        let record: String = format!("{}-{} '{}'", section_line.from_bus, section_line.to_bus, section_line.circuit);
+        checker.bus(PSSEDatatype::MultiSectionLine, position, &record, "I", section_line.from_bus);
+        checker.bus(PSSEDatatype::MultiSectionLine, position, &record, "J", section_line.to_bus);
+        for &dummy_bus in &section_line.dummy_bus_ids {
+            checker.bus(PSSEDatatype::MultiSectionLine, position, &record, "DUMk", dummy_bus);
+        }
+    }
+
+    checker.duplicates(&data.zones, PSSEDatatype::Zone, |zone| zone.zone_id.to_string());
+    checker.duplicates(&data.owners, PSSEDatatype::Owner, |owner| owner.owner_id.to_string());
+
+    checker.duplicates(&data.switched_shunts, PSSEDatatype::SwitchedShunt, |shunt| format!("{} '{}'", shunt.bus_id, shunt.id));
+    for (position, shunt) in data.switched_shunts.iter().enumerate() {
+        let record: String = format!("{} '{}'", shunt.bus_id, shunt.id);
+        checker.terminal(PSSEDatatype::SwitchedShunt, position, &record, "I", shunt.bus_id, shunt.status != 0);
+        checker.bus(PSSEDatatype::SwitchedShunt, position, &record, "SWREG", shunt.controlled_bus_id);
+    }
+
+    checker.duplicates(&data.induction_machines, PSSEDatatype::InductionMachine, |machine| format!("{} '{}'", machine.bus_id, machine.id));
+    for (position, machine) in data.induction_machines.iter().enumerate() {
+        let record: String = format!("{} '{}'", machine.bus_id, machine.id);
+        checker.terminal(PSSEDatatype::InductionMachine, position, &record, "I", machine.bus_id, machine.status != 0);
+    }
+
+    ValidationReport { issues: checker.issues }
+}
+
+struct Checker<'a> {
+    source: &'a SourceMap,
+    index: CaseIndex<'a>,
+    areas: HashSet<i32>,
+    zones: HashSet<i32>,
+    owners: HashSet<i32>,
+    tables: HashSet<i32>,
+    issues: Vec<ValidationIssue>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(data: &'a PSSEData, source: &'a SourceMap) -> Self {
+        Checker {
+            source,
+            index: data.index(),
+            areas: data.areas.iter().map(|area| area.area_id).collect(),
+            zones: data.zones.iter().map(|zone| zone.zone_id).collect(),
+            owners: data.owners.iter().map(|owner| owner.owner_id).collect(),
+            tables: data.impedance_correction.iter().map(|table| table.index).collect(),
+            issues: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, section: PSSEDatatype, position: usize, record: &str, kind: IssueKind) {
+        let line: Option<usize> = self.source.line(section, position);
+        self.issues.push(ValidationIssue { line, section, record: record.to_string(), kind });
+    }
+
+    //Bus the record is connected to, which must exist and not be isolated while the record is in service
+    fn terminal(&mut self, section: PSSEDatatype, position: usize, record: &str, field: &'static str, bus: i32, in_service: bool) {
+        match self.index.bus(bus) {
+            None => self.push(section, position, record, IssueKind::DanglingReference { field, target: PSSEDatatype::Bus, id: bus }),
+            Some(found) if in_service && found.bus_type() == BusType::Isolated => self.push(section, position, record, IssueKind::IsolatedBus { field, bus }),
+            Some(_) => {}
+        }
+    }
+
+    //Optional bus reference, 0 standing for none
+    fn bus(&mut self, section: PSSEDatatype, position: usize, record: &str, field: &'static str, bus: i32) {
+        if bus != 0 && self.index.bus(bus).is_none() {
+            self.push(section, position, record, IssueKind::DanglingReference { field, target: PSSEDatatype::Bus, id: bus });
+        }
+    }
+
+    fn table(&mut self, section: PSSEDatatype, position: usize, record: &str, field: &'static str, table: i32) {
+        if table != 0 && !self.tables.contains(&table) {
+            self.push(section, position, record, IssueKind::DanglingReference { field, target: PSSEDatatype::ImpedanceCorrection, id: table });
+        }
+    }
+
+    //Area, zone or owner of a bus, only checked when the case defines any
+    fn group(&mut self, section: PSSEDatatype, position: usize, record: &str, field: &'static str, target: PSSEDatatype, id: i32) {
+        let defined: &HashSet<i32> = match target {
+            PSSEDatatype::Area => &self.areas,
+            PSSEDatatype::Zone => &self.zones,
+            _ => &self.owners,
+        };
+        if !defined.is_empty() && !defined.contains(&id) {
+            self.push(section, position, record, IssueKind::DanglingReference { field, target, id });
+        }
+    }
+
+    fn duplicates<T>(&mut self, records: &[T], section: PSSEDatatype, key: impl Fn(&T) -> String) {
+        let mut first: HashMap<String, usize> = HashMap::with_capacity(records.len());
+        for (position, record) in records.iter().enumerate() {
+            let record: String = key(record);
+            match first.get(&record) {
+                Some(&first_position) => {
+                    let first_line: Option<usize> = self.source.line(section, first_position);
+                    self.push(section, position, &record, IssueKind::DuplicateKey { first_line });
+                }
+                None => {
+                    first.insert(record, position);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::psse::{error::Diagnostic, options::ParseOptions, parsers::fast_parser::parse_bytes};
+
+    use super::*;
+
+    const CASE: &str = "0, 100.0, 33, 0, 0, 60.0
+VALIDATION CASE
+
+101, 'NORTH', 138.0, 3, 1, 1, 1, 1.0, 0.0
+102, 'SOUTH', 138.0, 1, 1, 1, 1, 1.0, 0.0
+103, 'SPARE', 138.0, 4, 1, 1, 1, 1.0, 0.0
+102, 'AGAIN', 138.0, 1, 1, 1, 1, 1.0, 0.0
+0 / END OF BUS DATA, BEGIN LOAD DATA
+102, '1', 1, 1, 1, 10.0, 5.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0
+103, '1', 1, 1, 1, 10.0, 5.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0
+104, '1', 1, 1, 1, 10.0, 5.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0
+0 / END OF LOAD DATA, BEGIN FIXED SHUNT DATA
+0 / END OF FIXED SHUNT DATA, BEGIN GENERATOR DATA
+0 / END OF GENERATOR DATA, BEGIN BRANCH DATA
+101, 102, '1', 0.01, 0.1, 0.0, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+102, 101, '1', 0.01, 0.1, 0.0, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+0 / END OF BRANCH DATA, BEGIN TRANSFORMER DATA
+0 / END OF TRANSFORMER DATA, BEGIN AREA DATA
+1, 105, 0.0, 10.0, 'AREA1'
+0 / END OF AREA DATA
+Q
+";
+
+    #[test]
+    fn validate_references() {
+        let (data, source, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(CASE.as_bytes(), &ParseOptions::strict()).unwrap();
+        let report: ValidationReport = validate(&data, &source);
+        assert_eq!(report.issues, vec![
+            ValidationIssue { line: Some(7), section: PSSEDatatype::Bus, record: "102".to_string(), kind: IssueKind::DuplicateKey { first_line: Some(5) } },
+            ValidationIssue { line: Some(10), section: PSSEDatatype::Load, record: "103 '1'".to_string(), kind: IssueKind::IsolatedBus { field: "I", bus: 103 } },
+            ValidationIssue { line: Some(11), section: PSSEDatatype::Load, record: "104 '1'".to_string(), kind: IssueKind::DanglingReference { field: "I", target: PSSEDatatype::Bus, id: 104 } },
+            ValidationIssue { line: Some(16), section: PSSEDatatype::Branch, record: "101-102 '1'".to_string(), kind: IssueKind::DuplicateKey { first_line: Some(15) } },
+            ValidationIssue { line: Some(19), section: PSSEDatatype::Area, record: "1".to_string(), kind: IssueKind::DanglingReference { field: "ISW", target: PSSEDatatype::Bus, id: 105 } },
+        ]);
+        assert!(!report.is_valid());
+        assert_eq!(report.issues[2].to_string(), "line 11 (Load data 104 '1'): I refers to Bus 104, which is not in the case");
+    }
+}
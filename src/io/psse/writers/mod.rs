@@ -0,0 +1,2 @@
+pub mod raw_writer;
+pub mod rawx_writer;
@@ -0,0 +1,641 @@
+use std::{fmt::Display, io::{self, Write}};
+
+use crate::io::psse::{components::structs::*, conversion::convert_revision, parsers::sections::{section_name, section_order}, pssedata::{PSSEData, PSSEDatatype}};
+
+//The GNE real, integer and character data are written 10 values to a line
+const GNE_VALUES_PER_LINE: usize = 10;
+//Number of impedance correction entries written per line in V34+
+const CORRECTION_ENTRIES_PER_LINE: usize = 6;
+
+/// Writes a [`PSSEData`] struct as a PSS/E .RAW file using the column layout of `revision`
+///
+/// Every section of the revision is written in order, each followed by its `0 / end of ..., begin ...` terminator,
+/// with text fields quoted. A case read from another revision is first mapped with [`convert_revision`], use it directly
+/// to find out what is lost in a downgrade. Fields that are not stored in [`PSSEData`] (case titles, system-wide data,
+/// terminal nodes) are written blank or with their defaults.
+/// V36 records are written by position without `@!` column headings, which the parser reads in the V36 column order.
+/// Only the V33 to V36 layouts can be written, any other revision returns an [`io::ErrorKind::InvalidInput`] error.
+///
+/// ```no_run
+/// use pf_gat_parser::prelude::*;
+///
+/// let case: PSSEData = read_raw("case.raw").unwrap();
+/// let file = std::fs::File::create("case_v35.raw").unwrap();
+/// write_raw(&case, std::io::BufWriter::new(file), 35).unwrap();
+/// ```
+pub fn write_raw<W: Write>(data: &PSSEData, mut writer: W, revision: i8) -> io::Result<()> {
+    if !(33..=36).contains(&revision) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("cannot write PSS/E revision {}, only 33 to 36 are supported", revision)));
+    }
+    //Cases from another revision are mapped to the target layout first
+    let converted: PSSEData;
+    let data: &PSSEData = if data.header.revision != revision {
+        converted = convert_revision(data, revision).0;
+        &converted
+    } else {
+        data
+    };
+    let header: &HeaderInfo = &data.header;
+    let mut line: Line = Line::new();
+    line.field(header.ic).field(header.sbase).field(revision).field(header.transformer_rating_code).field(header.branch_rating_code).field(header.system_frequency);
+    writeln!(writer, "{}     / PSS(R)E-{} RAW created by pf_gat_parser", line.text, revision)?;
+    //Case titles are not kept, so both title lines are left blank
+    writeln!(writer)?;
+    writeln!(writer)?;
+    if revision >= 34 {
+        writeln!(writer, "0 / END OF SYSTEM-WIDE DATA, BEGIN {} DATA", section_name(PSSEDatatype::Bus))?;
+    }
+
+    let order: &[PSSEDatatype] = section_order(revision);
+    for (position, &datatype) in order.iter().enumerate() {
+        for record in section_records(data, datatype, revision) {
+            for line in record {
+                writeln!(writer, "{}", line)?;
+            }
+        }
+        match order.get(position + 1) {
+            Some(&next) => writeln!(writer, "0 / END OF {} DATA, BEGIN {} DATA", section_name(datatype), section_name(next))?,
+            None => writeln!(writer, "0 / END OF {} DATA", section_name(datatype))?,
+        }
+    }
+    writeln!(writer, "Q")?;
+    writer.flush()
+}
+
+/// The lines of every record of a section in the column layout of `revision`, one entry per record
+pub(super) fn section_records(data: &PSSEData, datatype: PSSEDatatype, revision: i8) -> Vec<Vec<String>> {
+    let records: Vec<Vec<Line>> = match datatype {
+        PSSEDatatype::Bus => data.buses.iter().map(|bus| vec![bus_line(bus, revision)]).collect(),
+        PSSEDatatype::Load => data.loads.iter().map(|load| vec![load_line(load, revision)]).collect(),
+        PSSEDatatype::FixedShunt => data.fixed_shunts.iter().map(|shunt| vec![fixed_shunt_line(shunt)]).collect(),
+        PSSEDatatype::Generator => data.generators.iter().map(|generator| vec![generator_line(generator, revision)]).collect(),
+        PSSEDatatype::Branch => data.branches.iter().map(|branch| vec![branch_line(branch, revision)]).collect(),
+        PSSEDatatype::SystemSwitchingDevice => data.switching_devices.iter().map(|device| vec![switching_device_line(device)]).collect(),
+        PSSEDatatype::Transformer => data.transformers.iter().map(|transformer| transformer_lines(transformer, revision)).collect(),
+        PSSEDatatype::Area => data.areas.iter().map(|area| vec![area_line(area)]).collect(),
+        PSSEDatatype::TwoTerminalDc => data.two_terminal_dc.iter().map(|dc_line| two_terminal_dc_lines(dc_line, revision)).collect(),
+        PSSEDatatype::VscDc => data.vsc_dc.iter().map(|dc_line| vsc_dc_lines(dc_line, revision)).collect(),
+        PSSEDatatype::ImpedanceCorrection => data.impedance_correction.iter().map(|table| impedance_correction_lines(table, revision)).collect(),
+        PSSEDatatype::MultiTerminalDc => data.multi_terminal_line.iter().map(multiterminal_dc_lines).collect(),
+        PSSEDatatype::MultiSectionLine => data.multi_section_line.iter().map(|section_line| vec![multisection_line(section_line)]).collect(),
+        PSSEDatatype::Zone => data.zones.iter().map(|zone| vec![zone_line(zone)]).collect(),
+        PSSEDatatype::InterAreaTransfer => data.inter_area_transfer.iter().map(|transfer| vec![area_transfer_line(transfer)]).collect(),
+        PSSEDatatype::Owner => data.owners.iter().map(|owner| vec![owner_line(owner)]).collect(),
+        PSSEDatatype::Facts => data.facts.iter().map(|facts| vec![facts_line(facts, revision)]).collect(),
+        PSSEDatatype::SwitchedShunt => data.switched_shunts.iter().map(|shunt| vec![switched_shunt_line(shunt, revision)]).collect(),
+        PSSEDatatype::GneDevice => data.gne_devices.iter().map(gne_device_lines).collect(),
+        PSSEDatatype::InductionMachine => data.induction_machines.iter().map(|machine| vec![induction_machine_line(machine)]).collect(),
+        PSSEDatatype::Substation => data.substations.iter().map(substation_lines).collect(),
+        _ => Vec::new(),
+    };
+    records.into_iter().map(|lines| lines.into_iter().map(|line| line.text).collect()).collect()
+}
+
+/// A single line of a .RAW record being built up field by field
+struct Line {
+    text: String,
+}
+
+impl Line {
+    fn new() -> Self {
+        Line { text: String::new() }
+    }
+
+    fn field<T: Display>(&mut self, value: T) -> &mut Self {
+        if !self.text.is_empty() {
+            self.text.push_str(", ");
+        }
+        self.text.push_str(&value.to_string());
+        self
+    }
+
+    //Names containing a single quote are written in double quotes instead
+    fn quoted(&mut self, value: &str) -> &mut Self {
+        let quote: char = if value.contains('\'') { '"' } else { '\'' };
+        self.field(format!("{}{}{}", quote, value, quote))
+    }
+
+    fn fields<T: Display + Copy>(&mut self, values: &[T]) -> &mut Self {
+        values.iter().for_each(|&value| { self.field(value); });
+        self
+    }
+
+    fn take(&mut self) -> Line {
+        std::mem::replace(self, Line::new())
+    }
+}
+
+fn bus_line(bus: &Bus, revision: i8) -> Line {
+    let mut line: Line = Line::new();
+    line.field(bus.id).quoted(&bus.name);
+    if revision >= 36 {
+        line.quoted(&bus.long_name);
+    }
+    line.field(bus.base_kv).field(bus.type_code).field(bus.area).field(bus.zone).field(bus.owner)
+        .field(bus.vm_pu).field(bus.va_deg).field(bus.nvhi).field(bus.nvlo).field(bus.evhi).field(bus.evlo);
+    line
+}
+
+fn load_line(load: &Load, revision: i8) -> Line {
+    let mut line: Line = Line::new();
+    line.field(load.bus_id).quoted(&load.id).field(load.status);
+    if revision >= 36 {
+        line.field(load.priority);
+    }
+    line.field(load.area).field(load.zone).field(load.pl_mw).field(load.ql_mvar)
+        .field(load.ip_mw).field(load.iq_mvar).field(load.yp_mw).field(load.yq_mvar).field(load.owner).field(load.scale).field(load.interruptable);
+    if revision >= 34 {
+        line.field(load.dgen_mw).field(load.dgen_mvar).field(load.dgen_mode);
+    }
+    if revision >= 35 {
+        line.quoted(&load.load_type);
+    }
+    line
+}
+
+fn fixed_shunt_line(shunt: &FixedShunt) -> Line {
+    let mut line: Line = Line::new();
+    line.field(shunt.bus_id).quoted(&shunt.id).field(shunt.status).field(shunt.gl_mw).field(shunt.bl_mvar);
+    line
+}
+
+fn generator_line(generator: &Generator, revision: i8) -> Line {
+    let mut line: Line = Line::new();
+    line.field(generator.bus_id).quoted(&generator.id);
+    if revision >= 36 {
+        line.quoted(&generator.gen_type);
+    }
+    line.field(generator.pgen).field(generator.qgen).field(generator.qmax).field(generator.qmin)
+        .field(generator.voltage_set).field(generator.reg_bus_id);
+    if revision >= 34 {
+        line.field(generator.reg_bus_node);
+    }
+    line.field(generator.mbase).field(generator.zr).field(generator.zx).field(generator.rt).field(generator.xt).field(generator.gtap)
+        .field(generator.status).field(generator.rmpct).field(generator.pmax).field(generator.pmin);
+    if revision >= 34 {
+        line.field(generator.base_loaded);
+    }
+    line.field(generator.owner1).field(generator.owner1_percent).field(generator.owner2).field(generator.owner2_percent)
+        .field(generator.owner3).field(generator.owner3_percent).field(generator.owner4).field(generator.owner4_percent)
+        .field(generator.machine_mode).field(generator.machine_powerfactor);
+    line
+}
+
+fn branch_line(branch: &Branch, revision: i8) -> Line {
+    let mut line: Line = Line::new();
+    line.field(branch.from_bus).field(branch.to_bus).quoted(&branch.circuit);
+    if revision >= 36 {
+        line.quoted(&branch.branch_type);
+    }
+    line.field(branch.r).field(branch.x).field(branch.b);
+    if revision >= 34 {
+        line.quoted(&branch.name).fields(&[
+            branch.rate1, branch.rate2, branch.rate3, branch.rate4, branch.rate5, branch.rate6,
+            branch.rate7, branch.rate8, branch.rate9, branch.rate10, branch.rate11, branch.rate12,
+        ]);
+    } else {
+        line.fields(&[branch.rate1, branch.rate2, branch.rate3]);
+    }
+    line.field(branch.gi).field(branch.bi).field(branch.gj).field(branch.bj).field(branch.status).field(branch.meter_end).field(branch.length)
+        .field(branch.owner1).field(branch.owner1_percent).field(branch.owner2).field(branch.owner2_percent)
+        .field(branch.owner3).field(branch.owner3_percent).field(branch.owner4).field(branch.owner4_percent);
+    line
+}
+
+fn switching_device_line(device: &SystemSwitchingDevice) -> Line {
+    let mut line: Line = Line::new();
+    line.field(device.from_bus).field(device.to_bus).quoted(&device.circuit).field(device.x).fields(&[
+        device.rate1, device.rate2, device.rate3, device.rate4, device.rate5, device.rate6,
+        device.rate7, device.rate8, device.rate9, device.rate10, device.rate11, device.rate12,
+    ]).field(device.status).field(device.normal_status).field(device.meter_end).field(device.device_type).quoted(&device.name);
+    line
+}
+
+fn transformer_lines(transformer: &Transformer, revision: i8) -> Vec<Line> {
+    let three_winding: bool = transformer.tertiary_bus != 0;
+    let mut lines: Vec<Line> = Vec::with_capacity(5);
+    let mut line: Line = Line::new();
+
+    line.field(transformer.from_bus).field(transformer.to_bus).field(transformer.tertiary_bus).quoted(&transformer.circuit)
+        .field(transformer.cw).field(transformer.cz).field(transformer.cm).field(transformer.mag1).field(transformer.mag2)
+        .field(transformer.metered_end).quoted(&transformer.name);
+    if revision >= 36 {
+        line.quoted(&transformer.description);
+    }
+    line.field(transformer.status).field(transformer.owner1).field(transformer.owner1_percent).field(transformer.owner2).field(transformer.owner2_percent)
+        .field(transformer.owner3).field(transformer.owner3_percent).field(transformer.owner4).field(transformer.owner4_percent)
+        .quoted(&transformer.vector_group);
+    if three_winding {
+        line.field(transformer.zcod);
+    }
+    lines.push(line.take());
+
+    line.field(transformer.r12).field(transformer.x12).field(transformer.sbase12);
+    if three_winding {
+        line.field(transformer.r23).field(transformer.x23).field(transformer.sbase23).field(transformer.r31).field(transformer.x31).field(transformer.sbase31)
+            .field(transformer.star_vm).field(transformer.star_ang);
+    }
+    lines.push(line.take());
+
+    lines.push(winding_line(&Winding {
+        volt: transformer.winding_1_volt,
+        nominal_volt: transformer.nominal_volt1,
+        angle: transformer.angle1,
+        rates: [
+            transformer.w1_rate1, transformer.w1_rate2, transformer.w1_rate3, transformer.w1_rate4, transformer.w1_rate5, transformer.w1_rate6,
+            transformer.w1_rate7, transformer.w1_rate8, transformer.w1_rate9, transformer.w1_rate10, transformer.w1_rate11, transformer.w1_rate12,
+        ],
+        control_mode: transformer.control_mode_1,
+        controlled_bus_id: transformer.controlled_bus_id_1,
+        limits: [transformer.rma1, transformer.rmi1, transformer.vma1, transformer.vmi1],
+        tap_positions: transformer.tap_positions_1,
+        tap_step: transformer.tap_step_1,
+        impedance_correction_table: transformer.impedance_correction_table_1,
+        load_drop_comp: [transformer.load_drop_comp_r1, transformer.load_drop_comp_x1],
+        connection_ang: transformer.connection_ang_1,
+    }, revision));
+
+    if three_winding {
+        lines.push(winding_line(&Winding {
+            volt: transformer.winding_2_volt,
+            nominal_volt: transformer.nominal_volt2,
+            angle: transformer.angle2,
+            rates: [
+                transformer.w2_rate1, transformer.w2_rate2, transformer.w2_rate3, transformer.w2_rate4, transformer.w2_rate5, transformer.w2_rate6,
+                transformer.w2_rate7, transformer.w2_rate8, transformer.w2_rate9, transformer.w2_rate10, transformer.w2_rate11, transformer.w2_rate12,
+            ],
+            control_mode: transformer.control_mode_2,
+            controlled_bus_id: transformer.controlled_bus_id_2,
+            limits: [transformer.rma2, transformer.rmi2, transformer.vma2, transformer.vmi2],
+            tap_positions: transformer.tap_positions_2,
+        tap_step: transformer.tap_step_2,
+            impedance_correction_table: transformer.impedance_correction_table_2,
+            load_drop_comp: [transformer.load_drop_comp_r2, transformer.load_drop_comp_x2],
+            connection_ang: transformer.connection_ang_2,
+        }, revision));
+        lines.push(winding_line(&Winding {
+            volt: transformer.winding_3_volt,
+            nominal_volt: transformer.nominal_volt3,
+            angle: transformer.angle3,
+            rates: [
+                transformer.w3_rate1, transformer.w3_rate2, transformer.w3_rate3, transformer.w3_rate4, transformer.w3_rate5, transformer.w3_rate6,
+                transformer.w3_rate7, transformer.w3_rate8, transformer.w3_rate9, transformer.w3_rate10, transformer.w3_rate11, transformer.w3_rate12,
+            ],
+            control_mode: transformer.control_mode_3,
+            controlled_bus_id: transformer.controlled_bus_id_3,
+            limits: [transformer.rma3, transformer.rmi3, transformer.vma3, transformer.vmi3],
+            tap_positions: transformer.tap_positions_3,
+        tap_step: transformer.tap_step_3,
+            impedance_correction_table: transformer.impedance_correction_table_3,
+            load_drop_comp: [transformer.load_drop_comp_r3, transformer.load_drop_comp_x3],
+            connection_ang: transformer.connection_ang_3,
+        }, revision));
+    } else {
+        //Two winding transformers only carry the winding 2 voltages
+        line.field(transformer.winding_2_volt).field(transformer.nominal_volt2);
+        lines.push(line.take());
+    }
+    lines
+}
+
+/// The data written on each winding line of a transformer record
+struct Winding {
+    volt: f64,
+    nominal_volt: f64,
+    angle: f64,
+    rates: [f64; 12],
+    control_mode: i32,
+    controlled_bus_id: i32,
+    limits: [f64; 4],
+    tap_positions: i32,
+    tap_step: f64,
+    impedance_correction_table: i32,
+    load_drop_comp: [f64; 2],
+    connection_ang: f64,
+}
+
+fn winding_line(winding: &Winding, revision: i8) -> Line {
+    let mut line: Line = Line::new();
+    line.field(winding.volt).field(winding.nominal_volt).field(winding.angle);
+    if revision >= 34 {
+        line.fields(&winding.rates);
+    } else {
+        line.fields(&winding.rates[..3]);
+    }
+    line.field(winding.control_mode).field(winding.controlled_bus_id);
+    //The controlled node is not kept
+    if revision >= 34 {
+        line.field(0);
+    }
+    line.fields(&winding.limits).field(winding.tap_positions);
+    if revision >= 36 {
+        line.field(winding.tap_step);
+    }
+    line.field(winding.impedance_correction_table)
+        .fields(&winding.load_drop_comp).field(winding.connection_ang);
+    line
+}
+
+fn area_line(area: &Area) -> Line {
+    let mut line: Line = Line::new();
+    line.field(area.area_id).field(area.swing_bus_id).field(area.desired_interchange).field(area.mw_tolerance).quoted(&area.name);
+    line
+}
+
+fn two_terminal_dc_lines(dc_line: &TwoTerminalDc, revision: i8) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::with_capacity(3);
+    let mut line: Line = Line::new();
+    line.quoted(&dc_line.name).field(dc_line.power_ctrl_mode).field(dc_line.resistance).field(dc_line.sending_pow).field(dc_line.voltage)
+        .field(dc_line.voltage_ctrl_mode).field(dc_line.r_comp).field(dc_line.dc_pow_margin).quoted(&dc_line.metered_end)
+        .field(dc_line.min_volt).field(dc_line.iteration_limit).field(dc_line.acceleration_fac);
+    lines.push(line.take());
+
+    line.field(dc_line.rec_bus_id).field(dc_line.rec_num_bridges).field(dc_line.rec_max_firing_angle).field(dc_line.rec_min_firing_angle)
+        .field(dc_line.rec_comm_xfmr_r).field(dc_line.rec_comm_xfmr_x).field(dc_line.rec_base_volt_ac).field(dc_line.rec_xfmr_ratio)
+        .field(dc_line.rec_tap_setting).field(dc_line.rec_max_tap_set).field(dc_line.rec_min_tap_set).field(dc_line.rec_tap_step)
+        .field(dc_line.rec_comm_bus_id);
+    if revision >= 34 {
+        line.field(dc_line.rec_comm_bus_node);
+    }
+    line.field(dc_line.rec_from_bus).field(dc_line.rec_to_bus).quoted(&dc_line.rec_ckt_id).field(dc_line.rec_comm_cap_x);
+    lines.push(line.take());
+
+    line.field(dc_line.inv_bus_id).field(dc_line.inv_num_bridges).field(dc_line.inv_max_firing_angle).field(dc_line.inv_min_firing_angle)
+        .field(dc_line.inv_comm_xfmr_r).field(dc_line.inv_comm_xfmr_x).field(dc_line.inv_base_volt_ac).field(dc_line.inv_xfmr_ratio)
+        .field(dc_line.inv_tap_setting).field(dc_line.inv_max_tap_set).field(dc_line.inv_min_tap_set).field(dc_line.inv_tap_step)
+        .field(dc_line.inv_comm_bus_id);
+    if revision >= 34 {
+        line.field(dc_line.inv_comm_bus_node);
+    }
+    line.field(dc_line.inv_from_bus).field(dc_line.inv_to_bus).quoted(&dc_line.inv_ckt_id).field(dc_line.inv_comm_cap_x);
+    lines.push(line);
+    lines
+}
+
+fn vsc_dc_lines(dc_line: &VSCDc, revision: i8) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::with_capacity(3);
+    let mut line: Line = Line::new();
+    line.quoted(&dc_line.name).field(dc_line.status).field(dc_line.resistance)
+        .field(dc_line.owner1).field(dc_line.owner1_percent).field(dc_line.owner2).field(dc_line.owner2_percent)
+        .field(dc_line.owner3).field(dc_line.owner3_percent).field(dc_line.owner4).field(dc_line.owner4_percent);
+    lines.push(line.take());
+
+    line.field(dc_line.converter_1_bus_id).field(dc_line.converter_1_dc_control).field(dc_line.converter_1_ac_control)
+        .field(dc_line.dc_setpoint_1).field(dc_line.ac_setpoint_1).field(dc_line.cont_loss_coeff_1).field(dc_line.prop_loss_coeff_1)
+        .field(dc_line.min_converter_loss_1).field(dc_line.mva_rating_1).field(dc_line.ac_rating_1).field(dc_line.power_weighting_factor_1)
+        .field(dc_line.max_reac_1).field(dc_line.min_reac_1).field(dc_line.control_bus_id_1);
+    if revision >= 34 {
+        line.field(dc_line.control_node_1);
+    }
+    line.field(dc_line.control_bus_percent_mvar_1);
+    lines.push(line.take());
+
+    line.field(dc_line.converter_2_bus_id).field(dc_line.converter_2_dc_control).field(dc_line.converter_2_ac_control)
+        .field(dc_line.dc_setpoint_2).field(dc_line.ac_setpoint_2).field(dc_line.cont_loss_coeff_2).field(dc_line.prop_loss_coeff_2)
+        .field(dc_line.min_converter_loss_2).field(dc_line.mva_rating_2).field(dc_line.ac_rating_2).field(dc_line.power_weighting_factor_2)
+        .field(dc_line.max_reac_2).field(dc_line.min_reac_2).field(dc_line.control_bus_id_2);
+    if revision >= 34 {
+        line.field(dc_line.control_node_2);
+    }
+    line.field(dc_line.control_bus_percent_mvar_2);
+    lines.push(line);
+    lines
+}
+
+fn impedance_correction_lines(table: &ImpedanceCorrectionTable, revision: i8) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::new();
+    let mut line: Line = Line::new();
+    line.field(table.index);
+    //V33 tables are written on a single line of (T, F) pairs
+    if revision <= 33 {
+        for entry in &table.correction_table_entries {
+            line.field(entry.tap).field(entry.real_complex_scaling_factor);
+        }
+        lines.push(line);
+        return lines;
+    }
+    //V34+ tables span as many lines as needed and end with an all zero entry
+    for (i, entry) in table.correction_table_entries.iter().enumerate() {
+        if i > 0 && i % CORRECTION_ENTRIES_PER_LINE == 0 {
+            lines.push(line.take());
+        }
+        line.field(entry.tap).field(entry.real_complex_scaling_factor).field(entry.imag_complex_scaling_factor);
+    }
+    line.fields(&[0.0, 0.0, 0.0]);
+    lines.push(line);
+    lines
+}
+
+fn multiterminal_dc_lines(dc_line: &MultiTermDCLine) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::with_capacity(3);
+    let mut line: Line = Line::new();
+    line.quoted(&dc_line.name).field(dc_line.num_converters).field(dc_line.num_dc_buses).field(dc_line.num_dc_links).field(dc_line.control_mode)
+        .field(dc_line.positive_ac_conv_bus_id).field(dc_line.mode_switch_dc_volt).field(dc_line.negative_ac_conv_bus_id);
+    lines.push(line.take());
+
+    line.field(dc_line.ac_conv_bus_1_id).field(dc_line.series_bridges).field(dc_line.max_alpha_gamma).field(dc_line.min_alpha_gamma)
+        .field(dc_line.bridge_r).field(dc_line.bridge_x).field(dc_line.base_ac_volt).field(dc_line.xfmr_ratio).field(dc_line.xfmr_tap)
+        .field(dc_line.xfmr_max_tap).field(dc_line.xfmr_min_tap).field(dc_line.conv_setpoint).field(dc_line.conv_participation_factor)
+        .field(dc_line.rec_margin).field(dc_line.conv_code);
+    lines.push(line.take());
+
+    line.field(dc_line.dc_bus_num).field(dc_line.ac_conv_bus_2_id).field(dc_line.area).field(dc_line.zone).quoted(&dc_line.dc_bus_name)
+        .field(dc_line.dc_bus_num_2).field(dc_line.resist_to_ground).field(dc_line.owner);
+    lines.push(line);
+    lines
+}
+
+fn multisection_line(section_line: &MultiSectionLine) -> Line {
+    let mut line: Line = Line::new();
+    line.field(section_line.from_bus).field(section_line.to_bus).quoted(&section_line.circuit).field(section_line.meter_end);
+    //Only write the dummy buses in use
+    let used: usize = section_line.dummy_bus_ids.iter().rposition(|&id| id != 0).map_or(0, |last| last + 1);
+    line.fields(&section_line.dummy_bus_ids[..used]);
+    line
+}
+
+fn zone_line(zone: &Zone) -> Line {
+    let mut line: Line = Line::new();
+    line.field(zone.zone_id).quoted(&zone.zone_name);
+    line
+}
+
+fn area_transfer_line(transfer: &InterAreaTransfer) -> Line {
+    let mut line: Line = Line::new();
+    line.field(transfer.from_area).field(transfer.to_area).quoted(&transfer.transfer_id).field(transfer.power_transfer);
+    line
+}
+
+fn owner_line(owner: &Owner) -> Line {
+    let mut line: Line = Line::new();
+    line.field(owner.owner_id).quoted(&owner.owner_name);
+    line
+}
+
+fn facts_line(facts: &Facts, revision: i8) -> Line {
+    let mut line: Line = Line::new();
+    line.quoted(&facts.deivce_name).field(facts.from_bus).field(facts.to_bus).field(facts.control_mode)
+        .field(facts.desired_active_power).field(facts.desired_reactive_power).field(facts.from_bus_volt_setpoint)
+        .field(facts.max_shunt_current).field(facts.max_bridge_active_power).field(facts.to_bus_min_voltage).field(facts.to_bus_max_voltage)
+        .field(facts.max_series_voltage).field(facts.max_series_current).field(facts.series_reactance).field(facts.shunt_mvar_share)
+        .field(facts.owner).field(facts.series_setpoint_1).field(facts.series_setpoint_2).field(facts.volt_ref_code).field(facts.regulated_bus_id);
+    if revision >= 34 {
+        line.field(facts.regulated_bus_node);
+    }
+    line.quoted(&facts.master_device_name);
+    line
+}
+
+fn switched_shunt_line(shunt: &SwitchedShunt, revision: i8) -> Line {
+    let mut line: Line = Line::new();
+    line.field(shunt.bus_id);
+    if revision >= 35 {
+        line.quoted(&shunt.id);
+    }
+    line.field(shunt.control_mode).field(shunt.adjust_method).field(shunt.status).field(shunt.upper_limit).field(shunt.x_lower_limit)
+        .field(shunt.controlled_bus_id);
+    if revision >= 34 {
+        line.field(shunt.controlled_bus_node);
+    }
+    line.field(shunt.mvar_contribution_perc).quoted(&shunt.controlled_bus_name).field(shunt.b_init);
+    for block in 0..shunt.steps.len().min(shunt.b_increment.len()) {
+        if revision >= 34 {
+            line.field(shunt.block_status.get(block).copied().unwrap_or(1));
+        }
+        line.field(shunt.steps[block]).field(shunt.b_increment[block]);
+    }
+    line
+}
+
+fn gne_device_lines(gne: &GneDevice) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::new();
+    let mut line: Line = Line::new();
+    line.quoted(&gne.name).quoted(&gne.model).field(gne.num_terminals).fields(&gne.bus_ids).field(gne.num_real).field(gne.num_int).field(gne.num_char);
+    lines.push(line.take());
+    line.field(gne.status).field(gne.owner).field(gne.metered_end);
+    lines.push(line.take());
+    for chunk in gne.real_data.chunks(GNE_VALUES_PER_LINE) {
+        line.fields(chunk);
+        lines.push(line.take());
+    }
+    for chunk in gne.int_data.chunks(GNE_VALUES_PER_LINE) {
+        line.fields(chunk);
+        lines.push(line.take());
+    }
+    for chunk in gne.char_data.chunks(GNE_VALUES_PER_LINE) {
+        chunk.iter().for_each(|value| { line.quoted(value); });
+        lines.push(line.take());
+    }
+    lines
+}
+
+fn induction_machine_line(machine: &InductionMachine) -> Line {
+    let mut line: Line = Line::new();
+    line.field(machine.bus_id).quoted(&machine.id).field(machine.status).field(machine.standard_code).field(machine.design_code)
+        .field(machine.area).field(machine.zone).field(machine.owner).field(machine.torque_code).field(machine.base_power_code)
+        .field(machine.sbase).field(machine.rated_voltage).field(machine.power_code).field(machine.sched_active_power).field(machine.inertia)
+        .fields(&machine.torque_params).field(machine.arm_resistance).field(machine.arm_reactance).field(machine.mag_reactance)
+        .field(machine.windng1_resist).field(machine.windng1_react).field(machine.windng2_resist).field(machine.windng2_react)
+        .field(machine.windng3_react).field(machine.volt_point_1).field(machine.v_sat1).field(machine.volt_point_2).field(machine.v_sat2)
+        .field(machine.stator_current_1).field(machine.stator_current_2).field(machine.saturation_mult);
+    line
+}
+
+//Each substation is followed by its node, switching device and terminal blocks, each with its own terminator
+fn substation_lines(substation: &Substation) -> Vec<Line> {
+    let mut lines: Vec<Line> = Vec::new();
+    let mut line: Line = Line::new();
+    line.field(substation.id).quoted(&substation.name).field(substation.latitude).field(substation.longitude).field(substation.ground_resistance);
+    lines.push(line.take());
+    lines.push(Line { text: "0 / BEGIN SUBSTATION NODE DATA".to_string() });
+    for node in &substation.nodes {
+        line.field(node.node).quoted(&node.name).field(node.bus_id).field(node.status).field(node.vm_pu).field(node.va_deg);
+        lines.push(line.take());
+    }
+    lines.push(Line { text: "0 / END OF SUBSTATION NODE DATA, BEGIN SUBSTATION SWITCHING DEVICE DATA".to_string() });
+    for device in &substation.switching_devices {
+        line.field(device.from_node).field(device.to_node).quoted(&device.circuit).quoted(&device.name).field(device.device_type)
+            .field(device.status).field(device.normal_status).field(device.x).field(device.rate1).field(device.rate2).field(device.rate3);
+        lines.push(line.take());
+    }
+    lines.push(Line { text: "0 / END OF SUBSTATION SWITCHING DEVICE DATA, BEGIN SUBSTATION TERMINAL DATA".to_string() });
+    for terminal in &substation.terminals {
+        line.field(terminal.bus_id).field(terminal.node).quoted(&terminal.equipment_type);
+        //Only the buses the equipment connects to are written before its ID
+        if terminal.other_bus_id != 0 {
+            line.field(terminal.other_bus_id);
+        }
+        if terminal.third_bus_id != 0 {
+            line.field(terminal.third_bus_id);
+        }
+        line.quoted(&terminal.id);
+        lines.push(line.take());
+    }
+    lines.push(Line { text: "0 / END OF SUBSTATION TERMINAL DATA".to_string() });
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::psse::{components::impedance_correction::ImpedanceCorrectionEntry, error::Diagnostic, options::ParseOptions, parsers::fast_parser::parse_fast, pssedata::SourceMap};
+
+    use super::*;
+
+    fn round_trip(fixture: &str, revision: i8) {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(format!("{}/test-cases/{}", dir, fixture), &ParseOptions::strict()).unwrap();
+        let mut raw: Vec<u8> = Vec::new();
+        write_raw(&data, &mut raw, revision).unwrap();
+        let filepath = std::env::temp_dir().join(format!("pf_gat_parser_round_trip_v{}.RAW", revision));
+        std::fs::write(&filepath, &raw).unwrap();
+        let (written, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&filepath, &ParseOptions::strict()).unwrap();
+        std::fs::remove_file(&filepath).unwrap();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(written, data);
+    }
+
+    #[test]
+    fn round_trip_v33() {
+        round_trip("TEST_parse_psse_v33.RAW", 33);
+    }
+
+    #[test]
+    fn correction_tables_v33() {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(format!("{}/test-cases/TEST_parse_psse_v33.RAW", dir), &ParseOptions::strict()).unwrap();
+        //V33 tables are (T, F) pairs with a real factor
+        let entries: &[ImpedanceCorrectionEntry] = &data.impedance_correction[0].correction_table_entries;
+        assert_eq!(entries.len(), 11);
+        assert_eq!((entries[0].tap, entries[0].real_complex_scaling_factor, entries[0].imag_complex_scaling_factor), (-78.90123, 1.23456, 0.0));
+        assert_eq!((entries[10].tap, entries[10].real_complex_scaling_factor), (78.90123, 1.23456));
+        let lines: Vec<Line> = impedance_correction_lines(&data.impedance_correction[0], 33);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].text.split(',').count(), 23);
+    }
+
+    #[test]
+    fn unsupported_revisions() {
+        for revision in [30, 32, 37] {
+            let err: io::Error = write_raw(&PSSEData::default(), Vec::new(), revision).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+
+    #[test]
+    fn round_trip_v34_v35() {
+        round_trip("TEST_parse_psse_v34.RAW", 34);
+        round_trip("TEST_parse_psse_v35.RAW", 35);
+    }
+
+    #[test]
+    fn round_trip_v36() {
+        round_trip("TEST_parse_psse_v36.RAW", 36);
+    }
+
+    #[test]
+    fn quotes_names() {
+        let mut line: Line = Line::new();
+        line.field(1).quoted("NORTH, 1").quoted("O'NEIL").field(1.5);
+        assert_eq!(line.text, "1, 'NORTH, 1', \"O'NEIL\", 1.5".to_string());
+    }
+}
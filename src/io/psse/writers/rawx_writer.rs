@@ -0,0 +1,229 @@
+use std::io::{self, Write};
+
+use serde_json::{Number, Value};
+
+use crate::io::psse::{components::structs::*, conversion::convert_revision, parsers::{rawx::{RawxTable, CASEID_FIELDS, GNE_COUNT_FIELDS, GNE_FIELDS, IMPCOR_FIELDS, MULTI_TERMINAL_TABLES, MULTI_TERMINAL_TEXT, RAWX_REVISION, SUBSTATION_DEVICE_FIELDS, SUBSTATION_FIELDS, SUBSTATION_NODE_FIELDS, SUBSTATION_TERMINAL_FIELDS, TABLES}, sections::section_order}, pssedata::{PSSEData, PSSEDatatype}, tokenizer::Tokenizer};
+
+use super::raw_writer::section_records;
+
+/// Writes a [`PSSEData`] struct as a PSS/E .RAWX (JSON) file
+///
+/// Each section is written as a table of the `network` object in .RAW section order, with the `fields` named as in
+/// [`parse_rawx`](crate::io::psse::parsers::rawx::parse_rawx) and one row of `data` per record. Fields a record does not use
+/// (the third winding of a two winding transformer, ...) are written as `null`. Cases from revisions before 35 are first
+/// mapped with [`convert_revision`], and tables with no records are left out.
+///
+/// ```no_run
+/// use pf_gat_parser::prelude::*;
+///
+/// let case: PSSEData = read_raw("case.raw").unwrap();
+/// let file = std::fs::File::create("case.rawx").unwrap();
+/// write_rawx(&case, std::io::BufWriter::new(file)).unwrap();
+/// ```
+pub fn write_rawx<W: Write>(data: &PSSEData, mut writer: W) -> io::Result<()> {
+    let converted: PSSEData;
+    let data: &PSSEData = if data.header.revision < RAWX_REVISION {
+        converted = convert_revision(data, RAWX_REVISION).0;
+        &converted
+    } else {
+        data
+    };
+    let header: &HeaderInfo = &data.header;
+    //Case titles are not kept, so both titles are left blank
+    let caseid: Vec<Value> = vec![
+        header.ic.into(), number(header.sbase), header.revision.max(RAWX_REVISION).into(), header.transformer_rating_code.into(),
+        header.branch_rating_code.into(), number(header.system_frequency), "".into(), "".into(),
+    ];
+
+    writeln!(writer, "{{")?;
+    writeln!(writer, "  \"network\":{{")?;
+    write!(writer, "    \"caseid\":{{\n      \"fields\":{},\n      \"data\":{}\n    }}", json(&CASEID_FIELDS), json(&caseid))?;
+    for &datatype in section_order(RAWX_REVISION) {
+        match datatype {
+            PSSEDatatype::ImpedanceCorrection => {
+                let rows: Vec<Vec<Value>> = data.impedance_correction.iter().flat_map(|table| {
+                    table.correction_table_entries.iter().map(|entry| vec![
+                        table.index.into(), number(entry.tap), number(entry.real_complex_scaling_factor), number(entry.imag_complex_scaling_factor),
+                    ])
+                }).collect();
+                write_table(&mut writer, "impcor", &IMPCOR_FIELDS, &rows)?;
+            }
+            PSSEDatatype::MultiTerminalDc => {
+                let records: Vec<Vec<String>> = section_records(data, datatype, RAWX_REVISION);
+                //Every line of the record is a row of its own table, the converter and DC bus rows start with the DC line name
+                for (line, (name, fields)) in MULTI_TERMINAL_TABLES.iter().enumerate() {
+                    let rows: Vec<Vec<Value>> = records.iter().map(|record| {
+                        let mut row: Vec<Value> = Vec::with_capacity(fields.len());
+                        if line > 0 {
+                            row.push(Tokenizer::new(&record[0]).next().unwrap_or_default().into());
+                        }
+                        row.extend(json_values(&record[line], &fields[row.len()..], &MULTI_TERMINAL_TEXT));
+                        row
+                    }).collect();
+                    write_table(&mut writer, name, fields, &rows)?;
+                }
+            }
+            PSSEDatatype::GneDevice => write_gne_devices(&mut writer, &data.gne_devices)?,
+            PSSEDatatype::Substation => write_substations(&mut writer, &data.substations)?,
+            _ => {
+                let Some(layout) = TABLES.iter().find(|layout| layout.section == datatype) else { continue };
+                let fields: Vec<&str> = layout.lines.concat();
+                let rows: Vec<Vec<Value>> = section_records(data, datatype, RAWX_REVISION).iter().map(|record| table_row(layout, record)).collect();
+                write_table(&mut writer, layout.name, &fields, &rows)?;
+            }
+        }
+    }
+    writeln!(writer, "\n  }}")?;
+    writeln!(writer, "}}")?;
+    writer.flush()
+}
+
+/// Writes a table of the `network` object, one row of data to a line
+fn write_table<W: Write, S: AsRef<str>>(writer: &mut W, name: &str, fields: &[S], rows: &[Vec<Value>]) -> io::Result<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let fields: Vec<&str> = fields.iter().map(AsRef::as_ref).collect();
+    write!(writer, ",\n    \"{}\":{{\n      \"fields\":{},\n      \"data\":[", name, json(&fields))?;
+    for (i, row) in rows.iter().enumerate() {
+        let separator: &str = if i == 0 { "" } else { "," };
+        write!(writer, "{}\n        {}", separator, json(row))?;
+    }
+    write!(writer, "\n      ]\n    }}")
+}
+
+fn json<T: Clone + Into<Value>>(values: &[T]) -> String {
+    Value::from(values.to_vec()).to_string()
+}
+
+fn number(value: f64) -> Value {
+    Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+/// Lays the .RAW lines of a record out as a row of the table, in the order of its fields
+fn table_row(layout: &RawxTable, record: &[String]) -> Vec<Value> {
+    let mut row: Vec<Value> = Vec::new();
+    for (line, fields) in layout.lines.iter().enumerate() {
+        let mut values: Vec<Value> = record.get(line).map(|text| json_values(text, fields, layout.text)).unwrap_or_default();
+        values.resize(fields.len(), Value::Null);
+        row.extend(values);
+    }
+    row
+}
+
+/// Converts the fields of a .RAW line to JSON values, keeping text fields as strings
+fn json_values(line: &str, fields: &[&str], text: &[&str]) -> Vec<Value> {
+    Tokenizer::new(line).zip(fields).map(|(token, field)| {
+        if text.contains(field) {
+            Value::String(token.to_string())
+        } else if token.is_empty() {
+            Value::Null
+        } else if let Ok(value) = token.parse::<i64>() {
+            value.into()
+        } else {
+            token.parse::<f64>().ok().and_then(Number::from_f64).map_or_else(|| token.into(), Value::Number)
+        }
+    }).collect()
+}
+
+fn write_gne_devices<W: Write>(writer: &mut W, devices: &[GneDevice]) -> io::Result<()> {
+    //The numbered fields run up to the largest count of any device
+    let most = |count: fn(&GneDevice) -> usize| devices.iter().map(count).max().unwrap_or(0);
+    let counts: [usize; 4] = [most(|gne| gne.bus_ids.len()), most(|gne| gne.real_data.len()), most(|gne| gne.int_data.len()), most(|gne| gne.char_data.len())];
+    let mut fields: Vec<String> = GNE_FIELDS.iter().map(|field| field.to_string()).collect();
+    fields.extend((1..=counts[0]).map(|i| format!("bus{}", i)));
+    fields.extend(GNE_COUNT_FIELDS.iter().map(|field| field.to_string()));
+    for (prefix, &count) in ["real", "intg", "char"].iter().zip(&counts[1..]) {
+        fields.extend((1..=count).map(|i| format!("{}{}", prefix, i)));
+    }
+
+    let padded = |mut values: Vec<Value>, count: usize| {
+        values.resize(count, Value::Null);
+        values
+    };
+    let rows: Vec<Vec<Value>> = devices.iter().map(|gne| {
+        let mut row: Vec<Value> = vec![gne.name.as_str().into(), gne.model.as_str().into(), gne.num_terminals.into()];
+        row.extend(padded(gne.bus_ids.iter().map(|&id| id.into()).collect(), counts[0]));
+        row.extend([gne.num_real.into(), gne.num_int.into(), gne.num_char.into(), gne.status.into(), gne.owner.into(), gne.metered_end.into()]);
+        row.extend(padded(gne.real_data.iter().map(|&value| number(value)).collect(), counts[1]));
+        row.extend(padded(gne.int_data.iter().map(|&value| value.into()).collect(), counts[2]));
+        row.extend(padded(gne.char_data.iter().map(|value| value.as_str().into()).collect(), counts[3]));
+        row
+    }).collect();
+    write_table(writer, "gne", &fields, &rows)
+}
+
+fn write_substations<W: Write>(writer: &mut W, substations: &[Substation]) -> io::Result<()> {
+    let rows: Vec<Vec<Value>> = substations.iter().map(|substation| vec![
+        substation.id.into(), substation.name.as_str().into(), number(substation.latitude), number(substation.longitude), number(substation.ground_resistance),
+    ]).collect();
+    write_table(writer, "sub", &SUBSTATION_FIELDS, &rows)?;
+    let rows: Vec<Vec<Value>> = substations.iter().flat_map(|substation| substation.nodes.iter().map(|node| vec![
+        substation.id.into(), node.node.into(), node.name.as_str().into(), node.bus_id.into(), node.status.into(), number(node.vm_pu), number(node.va_deg),
+    ])).collect();
+    write_table(writer, "subnode", &SUBSTATION_NODE_FIELDS, &rows)?;
+    let rows: Vec<Vec<Value>> = substations.iter().flat_map(|substation| substation.switching_devices.iter().map(|device| vec![
+        substation.id.into(), device.from_node.into(), device.to_node.into(), device.circuit.as_str().into(), device.name.as_str().into(),
+        device.device_type.into(), device.status.into(), device.normal_status.into(), number(device.x), number(device.rate1), number(device.rate2),
+        number(device.rate3),
+    ])).collect();
+    write_table(writer, "subswd", &SUBSTATION_DEVICE_FIELDS, &rows)?;
+    let rows: Vec<Vec<Value>> = substations.iter().flat_map(|substation| substation.terminals.iter().map(|terminal| vec![
+        substation.id.into(), terminal.node.into(), terminal.equipment_type.as_str().into(), terminal.id.as_str().into(), terminal.bus_id.into(),
+        terminal.other_bus_id.into(), terminal.third_bus_id.into(),
+    ])).collect();
+    write_table(writer, "subterm", &SUBSTATION_TERMINAL_FIELDS, &rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::psse::{components::{multiterminal_dc_line::parse_multiterminal_dc_line, transformer::parse_transformers}, error::Diagnostic, options::ParseOptions, parsers::{fast_parser::parse_fast, rawx::parse_rawx}, pssedata::SourceMap, record::SectionContext};
+
+    use super::*;
+
+    fn round_trip(fixture: &str) {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(format!("{}/test-cases/{}", dir, fixture), &ParseOptions::strict()).unwrap();
+        let mut rawx: Vec<u8> = Vec::new();
+        write_rawx(&data, &mut rawx).unwrap();
+        let filepath = std::env::temp_dir().join(format!("pf_gat_parser_round_trip_{}.rawx", fixture));
+        std::fs::write(&filepath, &rawx).unwrap();
+        let (written, diagnostics): (PSSEData, Vec<Diagnostic>) = parse_rawx(&filepath, &ParseOptions::strict()).unwrap();
+        std::fs::remove_file(&filepath).unwrap();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(written, convert_revision(&data, RAWX_REVISION).0);
+    }
+
+    #[test]
+    fn round_trip_rawx() {
+        round_trip("TEST_parse_psse_v35.RAW");
+        //Older cases are written as V35
+        round_trip("TEST_parse_psse_v33.RAW");
+        round_trip("TEST_parse_psse_v34.RAW");
+    }
+
+    #[test]
+    fn round_trip_split_records() {
+        //Two winding transformers leave the third winding fields empty and multi-terminal lines are split over three tables
+        let transformer_str: &'static str = "1234, 5678, 0, '1 ', 1, 1, 1, 0.0, 0.0, 2, 'TESTXFMR', 1, 1, 1.0, 0, 1.0, 0, 1.0, 0, 1.0, 'YNd1'
+2.6E-04, 2.36E-02, 100.0
+0.9804, 0.0, 0.0, 420.0, 480.0, 530.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1, 0, 0, 1.5, 0.51, 1.5, 0.51, 159, 0, 0.0, 0.0, 0.0
+1.0, 0.0";
+        let multiterminal_str: &'static str = "'MTDC 1', 2, 3, 2, 1, 401, 0.0, 402
+401, 2, 80.0, 5.0, 0.0, 16.0, 345.0, 0.5, 1.0, 1.2, 0.8, 500.0, 1.0, 0.0, 1
+1, 401, 1, 1, 'DC BUS 1', 0, 0.0, 1";
+        let mut data: PSSEData = PSSEData { header: HeaderInfo { sbase: 100.0, revision: 35, ..Default::default() }, ..Default::default() };
+        let lines: Vec<&[u8]> = transformer_str.lines().map(|line| line.as_bytes()).collect();
+        data.transformers = parse_transformers(&lines, &SectionContext::default(), 35).unwrap();
+        let lines: Vec<&[u8]> = multiterminal_str.lines().map(|line| line.as_bytes()).collect();
+        data.multi_terminal_line = parse_multiterminal_dc_line(&lines, &SectionContext::default()).unwrap();
+
+        let mut rawx: Vec<u8> = Vec::new();
+        write_rawx(&data, &mut rawx).unwrap();
+        let filepath = std::env::temp_dir().join("pf_gat_parser_split_records.rawx");
+        std::fs::write(&filepath, &rawx).unwrap();
+        let (written, _): (PSSEData, Vec<Diagnostic>) = parse_rawx(&filepath, &ParseOptions::strict()).unwrap();
+        std::fs::remove_file(&filepath).unwrap();
+        assert_eq!(written, data);
+    }
+}
@@ -1 +1,60 @@
-pub mod io;
\ No newline at end of file
+//! # pf_gat_parser
+//!
+//! A fast parser for PSS/E `.raw` power system model files.
+//!
+//! The simplest way to load a case is [`read_raw`], which returns a [`PSSEData`]
+//! containing every parsed section of the file.
+//!
+//! ```no_run
+//! use pf_gat_parser::prelude::*;
+//!
+//! let case: PSSEData = read_raw("case.raw").unwrap();
+//! for bus in &case.buses {
+//!     println!("{} {} kV", bus.name, bus.base_kv);
+//! }
+//! ```
+//!
+//! Best-effort parsing that falls back to the PSS/E defaults and reports what it substituted
+//! is available through [`parse_fast`] with [`ParseOptions::lenient`].
+//!
+//! Everything needed to work with a parsed case can be brought into scope with
+//! `use pf_gat_parser::prelude::*;`.
+
+use std::path::Path;
+
+pub mod io;
+pub mod powerflow;
+pub mod prelude;
+
+pub use io::psse::components::codes;
+pub use io::psse::components::structs;
+pub use io::psse::change::{apply_change_file, apply_changes, ChangeReport, ChangedRecord};
+pub use io::psse::conversion::{convert_revision, ConversionLoss, LossKind};
+pub use io::psse::encoding::Encoding;
+pub use io::psse::error::{Diagnostic, DiagnosticKind, ParseError};
+pub use io::psse::index::CaseIndex;
+pub use io::psse::options::{ParseMode, ParseOptions};
+pub use io::psse::parsers::archive::open_case;
+pub use io::psse::parsers::fast_parser::{parse_bytes, parse_fast, parse_reader};
+pub use io::psse::parsers::rawx::parse_rawx;
+pub use io::psse::plausibility::{check_plausibility, Finding, PlausibilityReport, PlausibilityRules, Rule, Severity};
+pub use io::psse::pssedata::{PSSEData, PSSEDatatype, SourceMap};
+pub use io::psse::validation::{validate, IssueKind, ValidationIssue, ValidationReport};
+pub use io::psse::writers::raw_writer::write_raw;
+pub use io::psse::writers::rawx_writer::write_rawx;
+pub use powerflow::ac::{AcPowerFlow, BusKind, Mismatch, PowerFlowOptions};
+pub use powerflow::dc::{dc_power_flow, DcPowerFlow, Island};
+pub use powerflow::error::PowerFlowError;
+pub use powerflow::fdlf::{fast_decoupled, FdlfVariant};
+pub use powerflow::newton::newton_raphson;
+pub use powerflow::ybus::{build_ybus, Ybus};
+
+/// Reads and parses a PSS/E .RAW file into a [`PSSEData`] struct
+///
+/// This is the stable entry point of the crate, currently backed by [`open_case`] in strict mode,
+/// so gzip, zstd and zip compressed cases are read as well.
+/// A malformed record returns a [`ParseError`] pointing to its line, section and field.
+pub fn read_raw<P: AsRef<Path>>(path: P) -> Result<PSSEData, ParseError> {
+    let (data, _, _) = open_case(path, &ParseOptions::strict())?;
+    Ok(data)
+}
@@ -0,0 +1,417 @@
+use std::collections::HashMap;
+
+use num_complex::Complex64;
+
+use crate::io::psse::{components::codes::BusType, pssedata::PSSEData};
+
+use super::{error::PowerFlowError, lu::minimum_degree, ybus::{Element, Network, Ybus}};
+
+/// Role of a bus in an AC power flow
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BusKind {
+    /// Swing bus holding the voltage magnitude and angle of its island
+    Slack,
+    /// Bus whose voltage magnitude is held by machines, at the bus itself or remotely
+    Pv,
+    /// Bus with scheduled active and reactive power
+    Pq,
+}
+
+/// Options of the AC power flow solvers
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerFlowOptions {
+    /// ## Largest active or reactive power mismatch at any bus for the solution to have converged, in MW and Mvar
+    pub tolerance_mva: f64,
+    /// ## Iterations before giving up
+    pub max_iterations: usize,
+    /// ## Whether machines reaching their reactive power limits stop holding voltage, their bus switching from PV to PQ
+    pub enforce_q_limits: bool,
+    /// ## Whether to start from 1.0 p.u. at the swing bus angle instead of the voltages of the case
+    pub flat_start: bool,
+}
+
+impl Default for PowerFlowOptions {
+    fn default() -> Self {
+        PowerFlowOptions { tolerance_mva: 0.1, max_iterations: 20, enforce_q_limits: true, flat_start: false }
+    }
+}
+
+/// Largest power mismatches of an iteration
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Mismatch {
+    /// ## Largest absolute active power mismatch in MW
+    pub p_mw: f64,
+    /// ## Bus of the largest active power mismatch
+    pub p_bus: i32,
+    /// ## Largest absolute reactive power mismatch in Mvar
+    pub q_mvar: f64,
+    /// ## Bus of the largest reactive power mismatch
+    pub q_bus: i32,
+}
+
+impl Mismatch {
+    /// The larger of the active and reactive power mismatches
+    pub fn max_mva(&self) -> f64 {
+        self.p_mw.max(self.q_mvar)
+    }
+}
+
+/// Solution of an AC power flow
+///
+/// Buses of islands without a swing bus are left out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AcPowerFlow {
+    /// ## Whether the mismatches fell below the tolerance
+    pub converged: bool,
+    /// ## Iterations taken
+    pub iterations: usize,
+    /// ## Largest mismatches before each iteration, the last being those of the solution
+    pub trace: Vec<Mismatch>,
+    /// ## Voltage magnitude of each bus in p.u.
+    pub vm_pu: HashMap<i32, f64>,
+    /// ## Voltage angle of each bus in degrees
+    pub va_deg: HashMap<i32, f64>,
+    /// ## Role of each bus at the solution, PV buses whose machines reached a reactive power limit being PQ
+    pub bus_kinds: HashMap<i32, BusKind>,
+    /// ## Active power of each in-service machine in MW, keyed by (bus, ID), the swing bus machines taking up the losses
+    pub pgen_mw: HashMap<(i32, String), f64>,
+    /// ## Reactive power of each in-service machine in Mvar, keyed by (bus, ID)
+    pub qgen_mvar: HashMap<(i32, String), f64>,
+}
+
+impl AcPowerFlow {
+    /// Writes the solution into `Bus.vm_pu` and `Bus.va_deg` and the machine outputs into `Generator.pgen` and `Generator.qgen`
+    /// of the case that was solved
+    ///
+    /// A solution that did not converge is not written, returning [`PowerFlowError::NotConverged`].
+    pub fn write_to(&self, data: &mut PSSEData) -> Result<(), PowerFlowError> {
+        if !self.converged {
+            return Err(PowerFlowError::NotConverged { iterations: self.iterations });
+        }
+        for bus in &mut data.buses {
+            if let (Some(&vm), Some(&va)) = (self.vm_pu.get(&bus.id), self.va_deg.get(&bus.id)) {
+                bus.vm_pu = vm;
+                bus.va_deg = va;
+            }
+        }
+        for generator in &mut data.generators {
+            let key: (i32, String) = (generator.bus_id, generator.id.clone());
+            if let (Some(&p), Some(&q)) = (self.pgen_mw.get(&key), self.qgen_mvar.get(&key)) {
+                generator.pgen = p;
+                generator.qgen = q;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Machines holding the voltage magnitude of a bus, sharing their reactive power by RMPCT
+pub(crate) struct VoltageControl {
+    pub(crate) bus: usize,
+    pub(crate) setpoint: f64,
+    //Position of the machine in the case and its share of the reactive power
+    pub(crate) machines: Vec<(usize, f64)>,
+    //Share of the reactive power at each machine bus
+    pub(crate) bus_shares: Vec<(usize, f64)>,
+    pub(crate) q_max: f64,
+    pub(crate) q_min: f64,
+    /// Whether the machines still hold the voltage, rather than sitting at a reactive power limit
+    pub(crate) active: bool,
+}
+
+/// Iterate of an AC power flow
+pub(crate) struct AcState {
+    pub(crate) vm: Vec<f64>,
+    pub(crate) va: Vec<f64>,
+    /// Reactive power of each [`VoltageControl`] in p.u.
+    pub(crate) q: Vec<f64>,
+}
+
+/// Network, bus classification and scheduled injections of a case, shared by the AC solvers
+///
+/// Each island with a swing bus is solved around its first swing bus. Machines at generator buses (and further swing
+/// buses) hold the voltage of their regulated bus at VS, or of their own bus when IREG is blank, not in service, in
+/// another island or a swing bus. Machines at load buses inject their PG and QG. Loads are modeled from their constant
+/// power, current and admittance components.
+pub(crate) struct AcModel {
+    pub(crate) network: Network,
+    pub(crate) elements: Vec<Element>,
+    pub(crate) ybus: Ybus,
+    /// Role of each bus, None for buses of islands without a swing bus
+    pub(crate) kinds: Vec<Option<BusKind>>,
+    pub(crate) controls: Vec<VoltageControl>,
+    pub(crate) control_of: Vec<Option<usize>>,
+    /// Buses with power flow equations (neither swing buses nor unsolved), in bus order
+    pub(crate) equations: Vec<usize>,
+    /// Position of each bus in `equations`
+    pub(crate) equation_of: Vec<Option<usize>>,
+    p_gen: Vec<f64>,
+    q_fixed: Vec<f64>,
+    //Constant power, current and admittance load components at 1.0 p.u.
+    loads: Vec<[Complex64; 3]>,
+    //Machines at each swing bus and their share of its power
+    slack_machines: Vec<Vec<(usize, f64)>>,
+    slack_voltage: Vec<f64>,
+}
+
+impl AcModel {
+    pub(crate) fn new(data: &PSSEData) -> Self {
+        let network: Network = Network::new(data);
+        let elements: Vec<Element> = network.elements(data);
+        let ybus: Ybus = network.ybus(data, &elements);
+        let n: usize = network.len();
+        let sbase: f64 = network.sbase;
+
+        let mut island_of: Vec<usize> = vec![0; n];
+        let mut kinds: Vec<Option<BusKind>> = vec![None; n];
+        for (island, buses) in network.islands(&elements).iter().enumerate() {
+            let slack: Option<usize> = buses.iter().copied().find(|&bus| network.bus_types[bus] == BusType::Swing);
+            for &bus in buses {
+                island_of[bus] = island;
+                if slack.is_some() {
+                    kinds[bus] = Some(if Some(bus) == slack { BusKind::Slack } else { BusKind::Pq });
+                }
+            }
+        }
+
+        let mut p_gen: Vec<f64> = vec![0.0; n];
+        let mut q_fixed: Vec<f64> = vec![0.0; n];
+        let mut slack_machines: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+        let mut slack_voltage: Vec<f64> = vec![0.0; n];
+        let mut controls: Vec<VoltageControl> = Vec::new();
+        let mut control_of: Vec<Option<usize>> = vec![None; n];
+        for (position, generator) in data.generators.iter().enumerate().filter(|(_, generator)| generator.status != 0) {
+            let Some(bus) = network.index(generator.bus_id) else { continue };
+            let Some(kind) = kinds[bus] else { continue };
+            p_gen[bus] += generator.pgen / sbase;
+            if kind == BusKind::Slack {
+                if slack_machines[bus].is_empty() {
+                    slack_voltage[bus] = generator.voltage_set;
+                }
+                slack_machines[bus].push((position, generator.rmpct));
+            } else if matches!(network.bus_types[bus], BusType::Generator | BusType::Swing) {
+                let regulated: usize = match network.index(generator.reg_bus_id) {
+                    Some(regulated) if generator.reg_bus_id != 0 && island_of[regulated] == island_of[bus] && kinds[regulated] != Some(BusKind::Slack) => regulated,
+                    _ => bus,
+                };
+                let control: usize = *control_of[regulated].get_or_insert_with(|| {
+                    controls.push(VoltageControl { bus: regulated, setpoint: generator.voltage_set, machines: Vec::new(), bus_shares: Vec::new(), q_max: 0.0, q_min: 0.0, active: true });
+                    controls.len() - 1
+                });
+                controls[control].machines.push((position, generator.rmpct));
+                controls[control].q_max += generator.qmax / sbase;
+                controls[control].q_min += generator.qmin / sbase;
+                kinds[regulated] = Some(BusKind::Pv);
+            } else {
+                q_fixed[bus] += generator.qgen / sbase;
+            }
+        }
+        for machines in slack_machines.iter_mut() {
+            share(machines);
+        }
+        for control in controls.iter_mut() {
+            share(&mut control.machines);
+            let mut bus_shares: HashMap<usize, f64> = HashMap::new();
+            for &(position, machine_share) in &control.machines {
+                *bus_shares.entry(network.index(data.generators[position].bus_id).unwrap_or(0)).or_default() += machine_share;
+            }
+            control.bus_shares = bus_shares.into_iter().collect();
+            control.bus_shares.sort_by_key(|&(bus, _)| bus);
+        }
+        for (bus, voltage) in slack_voltage.iter_mut().enumerate() {
+            if kinds[bus] == Some(BusKind::Slack) && (slack_machines[bus].is_empty() || *voltage <= 0.0) {
+                *voltage = data.buses.iter().find(|record| record.id == network.bus_ids[bus]).map_or(1.0, |record| record.vm_pu);
+            }
+        }
+
+        let mut loads: Vec<[Complex64; 3]> = vec![[Complex64::new(0.0, 0.0); 3]; n];
+        for load in data.loads.iter().filter(|load| load.status != 0) {
+            if let Some(bus) = network.index(load.bus_id) {
+                //YQ is negative for inductive load
+                loads[bus][0] += Complex64::new(load.pl_mw, load.ql_mvar) / sbase;
+                loads[bus][1] += Complex64::new(load.ip_mw, load.iq_mvar) / sbase;
+                loads[bus][2] += Complex64::new(load.yp_mw, -load.yq_mvar) / sbase;
+            }
+        }
+
+        let equations: Vec<usize> = (0..n).filter(|&bus| matches!(kinds[bus], Some(BusKind::Pv | BusKind::Pq))).collect();
+        let mut equation_of: Vec<Option<usize>> = vec![None; n];
+        for (position, &bus) in equations.iter().enumerate() {
+            equation_of[bus] = Some(position);
+        }
+        AcModel { network, elements, ybus, kinds, controls, control_of, equations, equation_of, p_gen, q_fixed, loads, slack_machines, slack_voltage }
+    }
+
+    /// Starting point from the case voltages, or 1.0 p.u. at the angle of the swing bus of each island for a flat start
+    pub(crate) fn initial_state(&self, data: &PSSEData, flat_start: bool) -> AcState {
+        let n: usize = self.network.len();
+        let mut vm: Vec<f64> = vec![1.0; n];
+        let mut va: Vec<f64> = vec![0.0; n];
+        for bus in &data.buses {
+            if let Some(i) = self.network.index(bus.id) {
+                if bus.vm_pu > 0.0 {
+                    vm[i] = bus.vm_pu;
+                }
+                va[i] = bus.va_deg.to_radians();
+            }
+        }
+        if flat_start {
+            for buses in self.network.islands(&self.elements) {
+                let angle: f64 = buses.iter().find(|&&bus| self.kinds[bus] == Some(BusKind::Slack)).map_or(0.0, |&slack| va[slack]);
+                for bus in buses {
+                    vm[bus] = 1.0;
+                    va[bus] = angle;
+                }
+            }
+        }
+        for (bus, voltage) in self.slack_voltage.iter().enumerate() {
+            if self.kinds[bus] == Some(BusKind::Slack) {
+                vm[bus] = *voltage;
+            }
+        }
+        for control in &self.controls {
+            vm[control.bus] = control.setpoint;
+        }
+        let q: Vec<f64> = self.controls.iter()
+            .map(|control| control.machines.iter().map(|&(position, _)| data.generators[position].qgen).sum::<f64>() / self.network.sbase)
+            .collect();
+        AcState { vm, va, q }
+    }
+
+    /// Positions in `equations` of the buses in minimum degree order of the bus admittance matrix, for factoring the solver matrices
+    pub(crate) fn equation_order(&self) -> Vec<usize> {
+        minimum_degree(&self.ybus.matrix).into_iter().filter_map(|bus| self.equation_of[bus]).collect()
+    }
+
+    /// Scale of the reactive power of each [`VoltageControl`] in the solver matrices, the self admittance of the regulated bus,
+    /// which keeps the regulated reactive power columns on par with the voltage magnitude columns
+    pub(crate) fn control_scales(&self) -> Vec<f64> {
+        self.controls.iter().map(|control| self.ybus.matrix.get(control.bus, control.bus).norm().max(1.0)).collect()
+    }
+
+    /// Whether the voltage magnitude of `bus` is held by machines still regulating it
+    pub(crate) fn is_regulated(&self, bus: usize) -> bool {
+        self.control_of[bus].is_some_and(|control| self.controls[control].active)
+    }
+
+    pub(crate) fn voltages(&self, state: &AcState) -> Vec<Complex64> {
+        state.vm.iter().zip(&state.va).map(|(&vm, &va)| Complex64::from_polar(vm, va)).collect()
+    }
+
+    /// Scheduled injection of each bus in p.u. at the voltage magnitudes of `state`, loads taken out
+    pub(crate) fn scheduled(&self, state: &AcState) -> Vec<Complex64> {
+        let mut scheduled: Vec<Complex64> = (0..self.network.len())
+            .map(|bus| {
+                let [constant, current, admittance]: [Complex64; 3] = self.loads[bus];
+                let vm: f64 = state.vm[bus];
+                Complex64::new(self.p_gen[bus], self.q_fixed[bus]) - constant - current * vm - admittance * vm * vm
+            })
+            .collect();
+        for (control, q) in self.controls.iter().zip(&state.q) {
+            for &(bus, bus_share) in &control.bus_shares {
+                scheduled[bus].im += q * bus_share;
+            }
+        }
+        scheduled
+    }
+
+    /// Derivative of the scheduled injection of `bus` with respect to its voltage magnitude
+    pub(crate) fn scheduled_derivative(&self, bus: usize, vm: f64) -> Complex64 {
+        let [_, current, admittance]: [Complex64; 3] = self.loads[bus];
+        -current - admittance * 2.0 * vm
+    }
+
+    /// Calculated less scheduled injection of each bus in p.u.
+    pub(crate) fn mismatches(&self, state: &AcState) -> Vec<Complex64> {
+        let voltages: Vec<Complex64> = self.voltages(state);
+        let currents: Vec<Complex64> = self.ybus.matrix.mat_vec(&voltages).unwrap_or_else(|_| vec![Complex64::new(0.0, 0.0); voltages.len()]);
+        let scheduled: Vec<Complex64> = self.scheduled(state);
+        voltages.iter().zip(&currents).zip(&scheduled).map(|((v, i), s)| v * i.conj() - s).collect()
+    }
+
+    /// Largest mismatches of the buses with power flow equations
+    pub(crate) fn largest(&self, mismatches: &[Complex64]) -> Mismatch {
+        let mut largest: Mismatch = Mismatch::default();
+        for &bus in &self.equations {
+            //Not a number once the iterations diverged, counted as infinite so it is never taken as converged
+            let magnitude = |value: f64| if value.is_nan() { f64::INFINITY } else { value.abs() * self.network.sbase };
+            let (p, q): (f64, f64) = (magnitude(mismatches[bus].re), magnitude(mismatches[bus].im));
+            if p > largest.p_mw {
+                largest.p_mw = p;
+                largest.p_bus = self.network.bus_ids[bus];
+            }
+            if q > largest.q_mvar {
+                largest.q_mvar = q;
+                largest.q_bus = self.network.bus_ids[bus];
+            }
+        }
+        largest
+    }
+
+    /// Holds the machines past their reactive power limits at the limit, their regulated bus becoming PQ,
+    /// returning whether any were switched
+    pub(crate) fn switch_limited(&mut self, state: &mut AcState, tolerance: f64) -> bool {
+        let tolerance: f64 = tolerance / self.network.sbase;
+        let mut switched: bool = false;
+        for (control, q) in self.controls.iter_mut().zip(state.q.iter_mut()) {
+            if !control.active || control.q_max < control.q_min {
+                continue;
+            }
+            if *q > control.q_max + tolerance || *q < control.q_min - tolerance {
+                *q = q.clamp(control.q_min, control.q_max);
+                control.active = false;
+                self.kinds[control.bus] = Some(BusKind::Pq);
+                switched = true;
+            }
+        }
+        switched
+    }
+
+    /// Solution of the state reached by a solver
+    pub(crate) fn solution(&self, data: &PSSEData, state: &AcState, converged: bool, iterations: usize, trace: Vec<Mismatch>) -> AcPowerFlow {
+        let sbase: f64 = self.network.sbase;
+        let mut solution: AcPowerFlow = AcPowerFlow { converged, iterations, trace, ..AcPowerFlow::default() };
+        for (bus, kind) in self.kinds.iter().enumerate() {
+            if let Some(kind) = kind {
+                let id: i32 = self.network.bus_ids[bus];
+                solution.vm_pu.insert(id, state.vm[bus]);
+                solution.va_deg.insert(id, state.va[bus].to_degrees());
+                solution.bus_kinds.insert(id, *kind);
+            }
+        }
+        let key = |position: usize| (data.generators[position].bus_id, data.generators[position].id.clone());
+        for position in 0..data.generators.len() {
+            let generator = &data.generators[position];
+            let Some(bus) = self.network.index(generator.bus_id) else { continue };
+            if generator.status != 0 && self.kinds[bus].is_some() {
+                solution.pgen_mw.insert(key(position), generator.pgen);
+                solution.qgen_mvar.insert(key(position), generator.qgen);
+            }
+        }
+        for (control, q) in self.controls.iter().zip(&state.q) {
+            for &(position, machine_share) in &control.machines {
+                solution.qgen_mvar.insert(key(position), q * machine_share * sbase);
+            }
+        }
+        let mismatches: Vec<Complex64> = self.mismatches(state);
+        for (bus, machines) in self.slack_machines.iter().enumerate() {
+            //The swing bus mismatch is what its machines generate beyond their schedule
+            for &(position, machine_share) in machines {
+                solution.pgen_mw.insert(key(position), data.generators[position].pgen + mismatches[bus].re * machine_share * sbase);
+                solution.qgen_mvar.insert(key(position), mismatches[bus].im * machine_share * sbase);
+            }
+        }
+        solution
+    }
+}
+
+//Turns the RMPCT of each machine into its share, equal shares when they are all zero
+fn share(machines: &mut [(usize, f64)]) {
+    let total: f64 = machines.iter().map(|&(_, rmpct)| rmpct.max(0.0)).sum();
+    let count: f64 = machines.len() as f64;
+    for (_, rmpct) in machines.iter_mut() {
+        *rmpct = if total > 0.0 { rmpct.max(0.0) / total } else { 1.0 / count };
+    }
+}
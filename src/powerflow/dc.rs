@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+
+use num_complex::Complex64;
+use sparsetools::{coo::Coo, csr::CSR};
+
+use crate::io::psse::{components::codes::BusType, pssedata::PSSEData};
+
+use super::{error::PowerFlowError, lu::SparseLu, ybus::{Element, Network, ZERO_IMPEDANCE_X}};
+
+/// Solution of a DC power flow
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DcPowerFlow {
+    /// ## Voltage angle of each bus of a solved island in degrees
+    pub angles_deg: HashMap<i32, f64>,
+    /// ## Active power flow of each in-service branch and two-winding transformer in MW
+    /// Keyed by (from bus, to bus, circuit) of the record, measured at the from bus towards the to bus
+    pub flows_mw: HashMap<(i32, i32, String), f64>,
+    /// ## Active power flow into each winding of the in-service three-winding transformers from its bus in MW
+    /// Keyed by (I, J, K, circuit) of the record, 0.0 for windings out of service
+    pub winding_flows_mw: HashMap<(i32, i32, i32, String), [f64; 3]>,
+    /// ## Islands of the case, each solved around its own swing bus
+    pub islands: Vec<Island>,
+}
+
+/// A group of buses connected by in-service branches and transformers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Island {
+    /// ## Bus numbers of the island
+    pub buses: Vec<i32>,
+    /// ## Swing bus (IDE = 3) of the island, None when it has none and was left unsolved
+    pub slack: Option<i32>,
+    /// ## Active power injected at the swing bus to balance the island in MW
+    pub slack_mw: f64,
+}
+
+//Susceptance and phase shift of the DC model of a series admittance behind a ratio
+fn dc_parameters(series: Complex64, ratio: Complex64) -> (f64, f64) {
+    let x: f64 = (1.0 / series).im;
+    let x: f64 = if x == 0.0 { ZERO_IMPEDANCE_X } else { x };
+    (1.0 / (x * ratio.norm()), ratio.arg())
+}
+
+/// Solves the DC power flow of a case
+///
+/// The B' matrix is built from the reactances of the in-service branches and transformers (resistances and shunts are
+/// neglected), with the reactance of transformers scaled by their off-nominal ratio and their phase shifts entering as
+/// injections. Bus injections are the generation (PG) of in-service machines less the load (PL + IP + YP at 1.0 p.u.)
+/// of in-service loads and the conductance (GL) of in-service fixed shunts.
+///
+/// Each island is solved with its swing bus (IDE = 3) at the angle VA of the case taking up the imbalance,
+/// the first swing bus in case order being used when there are several. Islands without a swing bus are left unsolved.
+///
+/// ```no_run
+/// use pf_gat_parser::prelude::*;
+///
+/// let case: PSSEData = read_raw("case.raw").unwrap();
+/// let solution: DcPowerFlow = dc_power_flow(&case).unwrap();
+/// println!("{:.1} MW", solution.flows_mw[&(876543, 347, "1".to_string())]);
+/// ```
+pub fn dc_power_flow(data: &PSSEData) -> Result<DcPowerFlow, PowerFlowError> {
+    let network: Network = Network::new(data);
+    let elements: Vec<Element> = network.elements(data);
+    let n: usize = network.len();
+    let sbase: f64 = network.sbase;
+
+    //B' and the injections equivalent to the phase shifts
+    let mut stamps: Coo<usize, f64> = Coo::with_size(n, n);
+    let mut shifts: Vec<f64> = vec![0.0; n];
+    for element in &elements {
+        match element {
+            Element::TwoPort { from, to, series, ratio, .. } => {
+                let (b, shift): (f64, f64) = dc_parameters(*series, *ratio);
+                stamps.push(*from, *from, b);
+                stamps.push(*to, *to, b);
+                stamps.push(*from, *to, -b);
+                stamps.push(*to, *from, -b);
+                shifts[*from] += b * shift;
+                shifts[*to] -= b * shift;
+            }
+            Element::Star { windings, .. } => {
+                let parameters: Vec<(f64, f64)> = windings.iter().map(|winding| dc_parameters(winding.series, winding.ratio)).collect();
+                let star: f64 = parameters.iter().map(|(b, _)| b).sum();
+                let star_shift: f64 = parameters.iter().map(|(b, shift)| b * shift).sum::<f64>() / star;
+                for (i, &(b_i, shift_i)) in windings.iter().zip(&parameters) {
+                    stamps.push(i.bus, i.bus, b_i);
+                    for (j, &(b_j, _)) in windings.iter().zip(&parameters) {
+                        stamps.push(i.bus, j.bus, -b_i * b_j / star);
+                    }
+                    shifts[i.bus] += b_i * (shift_i - star_shift);
+                }
+            }
+        }
+    }
+    let b_prime: CSR<usize, f64> = stamps.to_csr();
+
+    let mut injections: Vec<f64> = vec![0.0; n];
+    for generator in data.generators.iter().filter(|generator| generator.status != 0) {
+        if let Some(i) = network.index(generator.bus_id) {
+            injections[i] += generator.pgen / sbase;
+        }
+    }
+    for load in data.loads.iter().filter(|load| load.status != 0) {
+        if let Some(i) = network.index(load.bus_id) {
+            injections[i] -= (load.pl_mw + load.ip_mw + load.yp_mw) / sbase;
+        }
+    }
+    for shunt in data.fixed_shunts.iter().filter(|shunt| shunt.status != 0) {
+        if let Some(i) = network.index(shunt.bus_id) {
+            injections[i] -= shunt.gl_mw / sbase;
+        }
+    }
+
+    let mut solution: DcPowerFlow = DcPowerFlow::default();
+    let mut angles: Vec<Option<f64>> = vec![None; n];
+    for buses in network.islands(&elements) {
+        let slack: Option<usize> = buses.iter().copied().find(|&bus| network.bus_types[bus] == BusType::Swing);
+        let mut island: Island = Island { buses: buses.iter().map(|&bus| network.bus_ids[bus]).collect(), slack: slack.map(|bus| network.bus_ids[bus]), slack_mw: 0.0 };
+        if let Some(slack) = slack {
+            let slack_angle: f64 = data.buses.iter().find(|bus| bus.id == network.bus_ids[slack]).map_or(0.0, |bus| bus.va_deg.to_radians());
+            for (bus, angle) in buses.iter().zip(solve_island(&b_prime, &buses, slack, slack_angle, &injections, &shifts).map_err(|bus| PowerFlowError::Singular { bus: network.bus_ids[bus] })?) {
+                angles[*bus] = Some(angle);
+            }
+            let slack_row: f64 = (b_prime.rowptr()[slack]..b_prime.rowptr()[slack + 1])
+                .map(|entry| b_prime.values()[entry] * angles[b_prime.colidx()[entry]].unwrap_or(0.0))
+                .sum();
+            island.slack_mw = (slack_row - shifts[slack]) * sbase;
+        }
+        solution.islands.push(island);
+    }
+
+    for element in &elements {
+        match element {
+            Element::TwoPort { key, from, to, series, ratio, .. } => {
+                if let (Some(theta_from), Some(theta_to)) = (angles[*from], angles[*to]) {
+                    let (b, shift): (f64, f64) = dc_parameters(*series, *ratio);
+                    solution.flows_mw.insert(key.clone(), b * (theta_from - theta_to - shift) * sbase);
+                }
+            }
+            Element::Star { key, windings, .. } => {
+                if windings.iter().any(|winding| angles[winding.bus].is_none()) {
+                    continue;
+                }
+                let parameters: Vec<(f64, f64)> = windings.iter().map(|winding| dc_parameters(winding.series, winding.ratio)).collect();
+                let star: f64 = parameters.iter().map(|(b, _)| b).sum();
+                let star_angle: f64 = windings.iter().zip(&parameters).map(|(winding, (b, shift))| b * (angles[winding.bus].unwrap_or(0.0) - shift)).sum::<f64>() / star;
+                let mut flows: [f64; 3] = [0.0; 3];
+                for (winding, (b, shift)) in windings.iter().zip(&parameters) {
+                    flows[winding.number] = b * (angles[winding.bus].unwrap_or(0.0) - shift - star_angle) * sbase;
+                }
+                solution.winding_flows_mw.insert(key.clone(), flows);
+            }
+        }
+    }
+    solution.angles_deg = angles.iter().enumerate().filter_map(|(bus, angle)| angle.map(|angle| (network.bus_ids[bus], angle.to_degrees()))).collect();
+    Ok(solution)
+}
+
+//Angles of the buses of an island in radians, the error being the bus where B' is singular
+fn solve_island(b_prime: &CSR<usize, f64>, buses: &[usize], slack: usize, slack_angle: f64, injections: &[f64], shifts: &[f64]) -> Result<Vec<f64>, usize> {
+    //Position of each bus other than the slack in the reduced system
+    let mut reduced: HashMap<usize, usize> = HashMap::with_capacity(buses.len());
+    for &bus in buses.iter().filter(|&&bus| bus != slack) {
+        reduced.insert(bus, reduced.len());
+    }
+    let mut stamps: Coo<usize, f64> = Coo::with_size(reduced.len(), reduced.len());
+    let mut rhs: Vec<f64> = vec![0.0; reduced.len()];
+    for &bus in buses.iter().filter(|&&bus| bus != slack) {
+        let row: usize = reduced[&bus];
+        rhs[row] = injections[bus] + shifts[bus];
+        for entry in b_prime.rowptr()[bus]..b_prime.rowptr()[bus + 1] {
+            let col: usize = b_prime.colidx()[entry];
+            match reduced.get(&col) {
+                Some(&reduced_col) => stamps.push(row, reduced_col, b_prime.values()[entry]),
+                None => rhs[row] -= b_prime.values()[entry] * slack_angle,
+            }
+        }
+    }
+    let order: Vec<usize> = buses.iter().copied().filter(|&bus| bus != slack).collect();
+    let angles: Vec<f64> = if reduced.is_empty() { Vec::new() } else { SparseLu::factor(&stamps.to_csr()).map_err(|row| order[row])?.solve(&rhs) };
+    Ok(buses.iter().map(|bus| if *bus == slack { slack_angle } else { angles[reduced[bus]] }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::psse::{error::Diagnostic, options::ParseOptions, parsers::fast_parser::parse_bytes, pssedata::SourceMap};
+
+    use super::*;
+
+    const CASE: &str = "0, 100.0, 33, 0, 0, 60.0
+DC POWER FLOW CASE
+
+1, 'ONE', 138.0, 3, 1, 1, 1, 1.0, 0.0
+2, 'TWO', 138.0, 2, 1, 1, 1, 1.0, 0.0
+3, 'THREE', 138.0, 1, 1, 1, 1, 1.0, 0.0
+4, 'FOUR', 138.0, 3, 1, 1, 1, 1.0, 5.0
+5, 'FIVE', 138.0, 1, 1, 1, 1, 1.0, 0.0
+6, 'SIX', 138.0, 1, 1, 1, 1, 1.0, 0.0
+0 / END OF BUS DATA, BEGIN LOAD DATA
+3, '1', 1, 1, 1, 90.0, 10.0, 10.0, 0.0, 0.0, 0.0, 1, 1, 0
+3, '2', 0, 1, 1, 50.0, 10.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0
+6, '1', 1, 1, 1, 20.0, 5.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0
+0 / END OF LOAD DATA, BEGIN FIXED SHUNT DATA
+0 / END OF FIXED SHUNT DATA, BEGIN GENERATOR DATA
+2, '1', 50.0, 0.0, 50.0, -50.0, 1.0, 0, 100.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1, 100.0, 100.0, 0.0, 1, 1.0
+0 / END OF GENERATOR DATA, BEGIN BRANCH DATA
+1, 2, '1', 0.01, 0.1, 0.02, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+2, 3, '1', 0.01, 0.1, 0.02, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+1, 3, '1', 0.02, 0.2, 0.04, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+0 / END OF BRANCH DATA, BEGIN TRANSFORMER DATA
+4, 5, 0, '1', 1, 1, 1, 0.0, 0.0, 2, 'PS', 1, 1, 1.0, 0, 1.0, 0, 1.0, 0, 1.0, 'YNyn0'
+0.0, 0.1, 100.0
+1.0, 0.0, 10.0, 100.0, 100.0, 100.0, 0, 0, 1.1, 0.9, 1.1, 0.9, 33, 0, 0.0, 0.0, 0.0
+1.0, 0.0
+0 / END OF TRANSFORMER DATA
+Q
+";
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn dc_power_flow_islands() {
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(CASE.as_bytes(), &ParseOptions::strict()).unwrap();
+        let solution: DcPowerFlow = dc_power_flow(&data).unwrap();
+        assert_eq!(solution.islands.len(), 3);
+
+        //100 MW of load at bus 3 (the out of service load is ignored), 50 MW generated at bus 2 and the rest from the swing bus
+        assert_eq!(solution.islands[0], Island { buses: vec![1, 2, 3], slack: Some(1), slack_mw: solution.islands[0].slack_mw });
+        assert_close(solution.islands[0].slack_mw, 50.0);
+        assert_close(solution.angles_deg[&2], (-0.0125f64).to_degrees());
+        assert_close(solution.angles_deg[&3], (-0.075f64).to_degrees());
+        assert_close(solution.flows_mw[&(1, 2, "1".to_string())], 12.5);
+        assert_close(solution.flows_mw[&(2, 3, "1".to_string())], 62.5);
+        assert_close(solution.flows_mw[&(1, 3, "1".to_string())], 37.5);
+
+        //The phase shifter carries no power to the unloaded bus, which sits 10 degrees behind the swing bus angle of 5 degrees
+        assert_eq!(solution.islands[1].slack, Some(4));
+        assert_close(solution.islands[1].slack_mw, 0.0);
+        assert_close(solution.angles_deg[&4], 5.0);
+        assert_close(solution.angles_deg[&5], -5.0);
+        assert_close(solution.flows_mw[&(4, 5, "1".to_string())], 0.0);
+
+        //Bus 6 has no swing bus and is left unsolved
+        assert_eq!(solution.islands[2], Island { buses: vec![6], slack: None, slack_mw: 0.0 });
+        assert!(!solution.angles_deg.contains_key(&6));
+    }
+}
@@ -0,0 +1,21 @@
+use std::{error::Error, fmt};
+
+/// Errors that can occur while solving a power flow
+#[derive(Debug, Clone, PartialEq)]
+pub enum PowerFlowError {
+    /// The network equations could not be solved, the matrix becoming singular at the bus
+    Singular { bus: i32 },
+    /// The mismatches were still above the tolerance after the last iteration
+    NotConverged { iterations: usize },
+}
+
+impl fmt::Display for PowerFlowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PowerFlowError::Singular { bus } => write!(f, "network matrix is singular at bus {}", bus),
+            PowerFlowError::NotConverged { iterations } => write!(f, "power flow did not converge in {} iterations", iterations),
+        }
+    }
+}
+
+impl Error for PowerFlowError {}
@@ -0,0 +1,235 @@
+use num_complex::Complex64;
+use sparsetools::{coo::Coo, csr::CSR};
+
+use crate::io::psse::pssedata::PSSEData;
+
+use super::{ac::{AcModel, AcPowerFlow, AcState, Mismatch, PowerFlowOptions}, error::PowerFlowError, lu::SparseLu, ybus::{Element, Winding, ZERO_IMPEDANCE_X}};
+
+/// Decoupling scheme of the fast decoupled power flow
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FdlfVariant {
+    /// Resistances left out of B' (the XB scheme)
+    Xb,
+    /// Resistances left out of B'' (the BX scheme)
+    Bx,
+}
+
+/// Solves the AC power flow of a case with the fast decoupled method
+///
+/// The angles and voltage magnitudes are updated in alternating half iterations on the constant B' and B'' matrices,
+/// which are factored once. B' leaves out the shunts, off-nominal ratios and phase shifts of the network and B'' the phase
+/// shifts, resistances being left out of B' by [`FdlfVariant::Xb`] and of B'' by [`FdlfVariant::Bx`].
+///
+/// Buses, machines and loads are modeled as for [`newton_raphson`](crate::newton_raphson), with the same options and
+/// solution, each entry of the trace being the largest mismatches before an iteration (a pair of half iterations).
+/// Fast decoupled iterations converge linearly, so they usually take more iterations than Newton-Raphson to the same tolerance.
+///
+/// ```no_run
+/// use pf_gat_parser::prelude::*;
+///
+/// let case: PSSEData = read_raw("case.raw").unwrap();
+/// let options: PowerFlowOptions = PowerFlowOptions { max_iterations: 50, ..PowerFlowOptions::default() };
+/// let solution: AcPowerFlow = fast_decoupled(&case, FdlfVariant::Xb, &options).unwrap();
+/// for mismatch in &solution.trace {
+///     println!("{:.3} MW at bus {}, {:.3} Mvar at bus {}", mismatch.p_mw, mismatch.p_bus, mismatch.q_mvar, mismatch.q_bus);
+/// }
+/// ```
+pub fn fast_decoupled(data: &PSSEData, variant: FdlfVariant, options: &PowerFlowOptions) -> Result<AcPowerFlow, PowerFlowError> {
+    let mut model: AcModel = AcModel::new(data);
+    let mut state: AcState = model.initial_state(data, options.flat_start);
+    let order: Vec<usize> = model.equation_order();
+    let scales: Vec<f64> = model.control_scales();
+    let singular = |model: &AcModel, row: usize| PowerFlowError::Singular { bus: model.network.bus_ids[model.equations[row]] };
+
+    let (b_p, b_pp): (CSR<usize, f64>, CSR<usize, f64>) = decoupled_matrices(&model, data, variant);
+    let p_lu: SparseLu = SparseLu::factor_ordered(&b_p, order.clone()).map_err(|row| singular(&model, row))?;
+    let mut q_lu: SparseLu = SparseLu::factor_ordered(&q_matrix(&model, &b_pp, &scales), order.clone()).map_err(|row| singular(&model, row))?;
+
+    let mut trace: Vec<Mismatch> = Vec::new();
+    let mut iterations: usize = 0;
+    let converged: bool = loop {
+        let mismatches: Vec<Complex64> = model.mismatches(&state);
+        let largest: Mismatch = model.largest(&mismatches);
+        trace.push(largest);
+        if largest.max_mva() < options.tolerance_mva {
+            if !(options.enforce_q_limits && model.switch_limited(&mut state, options.tolerance_mva)) {
+                break true;
+            }
+            q_lu = SparseLu::factor_ordered(&q_matrix(&model, &b_pp, &scales), order.clone()).map_err(|row| singular(&model, row))?;
+        }
+        //A diverging solution is not iterated further
+        if iterations == options.max_iterations || !largest.max_mva().is_finite() {
+            break false;
+        }
+        //Angles from the active power mismatches
+        let rhs: Vec<f64> = model.equations.iter().map(|&bus| mismatches[bus].re / state.vm[bus]).collect();
+        for (&bus, step) in model.equations.iter().zip(p_lu.solve(&rhs)) {
+            state.va[bus] -= step;
+        }
+        //Voltage magnitudes, or regulated reactive power, from the reactive power mismatches at the new angles
+        let mismatches: Vec<Complex64> = model.mismatches(&state);
+        let rhs: Vec<f64> = model.equations.iter().map(|&bus| mismatches[bus].im / state.vm[bus]).collect();
+        for (&bus, step) in model.equations.iter().zip(q_lu.solve(&rhs)) {
+            if model.is_regulated(bus) {
+                if let Some(control) = model.control_of[bus] {
+                    state.q[control] -= step * scales[control];
+                }
+            } else {
+                state.vm[bus] -= step;
+            }
+        }
+        iterations += 1;
+    };
+    Ok(model.solution(data, &state, converged, iterations, trace))
+}
+
+//B' and B'' over the buses with power flow equations
+fn decoupled_matrices(model: &AcModel, data: &PSSEData, variant: FdlfVariant) -> (CSR<usize, f64>, CSR<usize, f64>) {
+    let (p_elements, pp_elements): (Vec<Element>, Vec<Element>) = model.elements.iter()
+        .map(|element| (decoupled(element, variant == FdlfVariant::Xb, false), decoupled(element, variant == FdlfVariant::Bx, true)))
+        .unzip();
+    let n: usize = model.network.len();
+    let mut stamps: Coo<usize, Complex64> = Coo::with_size(n, n);
+    for element in &p_elements {
+        element.stamp(&mut stamps);
+    }
+    let b_p: CSR<usize, Complex64> = stamps.to_csr();
+    let b_pp: CSR<usize, Complex64> = model.network.ybus(data, &pp_elements).matrix;
+    (reduce(model, &b_p), reduce(model, &b_pp))
+}
+
+//Copy of a branch or transformer for B' (without shunts or ratios) or B'' (without phase shifts), with or without its resistance
+fn decoupled(element: &Element, drop_resistance: bool, keep_shunts: bool) -> Element {
+    let series = |series: Complex64| {
+        if !drop_resistance {
+            return series;
+        }
+        let x: f64 = (1.0 / series).im;
+        Complex64::new(0.0, -1.0 / if x == 0.0 { ZERO_IMPEDANCE_X } else { x })
+    };
+    let ratio = |ratio: Complex64| Complex64::new(if keep_shunts { ratio.norm() } else { 1.0 }, 0.0);
+    let shunt = |shunt: Complex64| if keep_shunts { shunt } else { Complex64::new(0.0, 0.0) };
+    match element {
+        Element::TwoPort { key, from, to, series: y, ratio: a, from_shunt, to_shunt } => Element::TwoPort {
+            key: key.clone(),
+            from: *from,
+            to: *to,
+            series: series(*y),
+            ratio: ratio(*a),
+            from_shunt: shunt(*from_shunt),
+            to_shunt: shunt(*to_shunt),
+        },
+        Element::Star { key, windings, magnetizing } => Element::Star {
+            key: key.clone(),
+            windings: windings.iter().map(|winding| Winding { number: winding.number, bus: winding.bus, series: series(winding.series), ratio: ratio(winding.ratio) }).collect(),
+            magnetizing: magnetizing.filter(|_| keep_shunts),
+        },
+    }
+}
+
+//Negative susceptances of the buses with power flow equations
+fn reduce(model: &AcModel, matrix: &CSR<usize, Complex64>) -> CSR<usize, f64> {
+    let size: usize = model.equations.len();
+    let mut stamps: Coo<usize, f64> = Coo::with_size(size, size);
+    for (row, &bus) in model.equations.iter().enumerate() {
+        for entry in matrix.rowptr()[bus]..matrix.rowptr()[bus + 1] {
+            if let Some(col) = model.equation_of[matrix.colidx()[entry]] {
+                stamps.push(row, col, -matrix.values()[entry].im);
+            }
+        }
+    }
+    stamps.to_csr()
+}
+
+//B'' with the columns of regulated buses replaced by the reactive power of the machines regulating them
+fn q_matrix(model: &AcModel, b_pp: &CSR<usize, f64>, scales: &[f64]) -> CSR<usize, f64> {
+    let size: usize = model.equations.len();
+    let mut stamps: Coo<usize, f64> = Coo::with_size(size, size);
+    for row in 0..size {
+        for entry in b_pp.rowptr()[row]..b_pp.rowptr()[row + 1] {
+            let col: usize = b_pp.colidx()[entry];
+            if !model.is_regulated(model.equations[col]) {
+                stamps.push(row, col, b_pp.values()[entry]);
+            }
+        }
+    }
+    for (control, scale) in model.controls.iter().zip(scales).filter(|(control, _)| control.active) {
+        let Some(col) = model.equation_of[control.bus] else { continue };
+        for &(bus, bus_share) in &control.bus_shares {
+            if let Some(row) = model.equation_of[bus] {
+                stamps.push(row, col, -bus_share * scale);
+            }
+        }
+    }
+    stamps.to_csr()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{io::psse::{error::Diagnostic, options::ParseOptions, parsers::fast_parser::parse_bytes, pssedata::SourceMap}, powerflow::newton::newton_raphson};
+
+    use super::*;
+
+    const CASE: &str = "0, 100.0, 33, 0, 0, 60.0
+FAST DECOUPLED CASE
+
+1, 'ONE', 230.0, 3, 1, 1, 1, 1.0, 0.0
+2, 'TWO', 230.0, 2, 1, 1, 1, 1.0, 0.0
+3, 'THREE', 230.0, 1, 1, 1, 1, 1.0, 0.0
+4, 'FOUR', 115.0, 1, 1, 1, 1, 1.0, 0.0
+5, 'FIVE', 115.0, 1, 1, 1, 1, 1.0, 0.0
+0 / END OF BUS DATA, BEGIN LOAD DATA
+3, '1', 1, 1, 1, 90.0, 30.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0
+4, '1', 1, 1, 1, 60.0, 20.0, 5.0, 2.0, 5.0, -2.0, 1, 1, 0
+5, '1', 1, 1, 1, 70.0, 25.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0
+0 / END OF LOAD DATA, BEGIN FIXED SHUNT DATA
+5, '1', 1, 0.0, 15.0
+0 / END OF FIXED SHUNT DATA, BEGIN GENERATOR DATA
+1, '1', 0.0, 0.0, 300.0, -300.0, 1.04, 0, 100.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1, 100.0, 300.0, 0.0, 1, 1.0
+2, '1', 120.0, 0.0, 100.0, -100.0, 1.02, 0, 100.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1, 100.0, 200.0, 0.0, 1, 1.0
+0 / END OF GENERATOR DATA, BEGIN BRANCH DATA
+1, 2, '1', 0.02, 0.06, 0.06, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+1, 3, '1', 0.08, 0.24, 0.05, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+2, 3, '1', 0.06, 0.18, 0.04, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+4, 5, '1', 0.04, 0.12, 0.02, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+0 / END OF BRANCH DATA, BEGIN TRANSFORMER DATA
+2, 4, 0, '1', 1, 1, 1, 0.0, 0.0, 2, 'T1', 1, 1, 1.0, 0, 1.0, 0, 1.0, 0, 1.0, 'YNyn0'
+0.005, 0.1, 100.0
+0.975, 0.0, 3.0, 100.0, 100.0, 100.0, 0, 0, 1.1, 0.9, 1.1, 0.9, 33, 0, 0.0, 0.0, 0.0
+1.0, 0.0
+3, 5, 0, '1', 1, 1, 1, 0.0, 0.0, 2, 'T2', 1, 1, 1.0, 0, 1.0, 0, 1.0, 0, 1.0, 'YNyn0'
+0.005, 0.12, 100.0
+1.0, 0.0, 0.0, 100.0, 100.0, 100.0, 0, 0, 1.1, 0.9, 1.1, 0.9, 33, 0, 0.0, 0.0, 0.0
+1.0, 0.0
+0 / END OF TRANSFORMER DATA
+Q
+";
+
+    #[test]
+    fn fast_decoupled_variants() {
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(CASE.as_bytes(), &ParseOptions::strict()).unwrap();
+        let options: PowerFlowOptions = PowerFlowOptions { tolerance_mva: 1e-6, max_iterations: 50, flat_start: true, ..PowerFlowOptions::default() };
+        let newton: AcPowerFlow = newton_raphson(&data, &options).unwrap();
+        assert!(newton.converged);
+        for variant in [FdlfVariant::Xb, FdlfVariant::Bx] {
+            let solution: AcPowerFlow = fast_decoupled(&data, variant, &options).unwrap();
+            assert!(solution.converged, "{:?}", variant);
+            assert!(solution.iterations > newton.iterations);
+            assert_eq!(solution.trace.len(), solution.iterations + 1);
+            assert!(solution.trace.last().unwrap().max_mva() < 1e-6);
+            //Same solution as Newton-Raphson, reached along another path
+            for (bus, vm) in &newton.vm_pu {
+                assert!((solution.vm_pu[bus] - vm).abs() < 1e-7);
+                assert!((solution.va_deg[bus] - newton.va_deg[bus]).abs() < 1e-5);
+            }
+            assert!((solution.qgen_mvar[&(2, "1".to_string())] - newton.qgen_mvar[&(2, "1".to_string())]).abs() < 1e-4);
+        }
+
+        //Stopping at the iteration limit
+        let limited: AcPowerFlow = fast_decoupled(&data, FdlfVariant::Bx, &PowerFlowOptions { max_iterations: 2, ..options }).unwrap();
+        assert!(!limited.converged);
+        assert_eq!(limited.iterations, 2);
+        assert_eq!(limited.trace.len(), 3);
+    }
+}
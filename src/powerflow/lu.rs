@@ -0,0 +1,204 @@
+use std::{cmp::Reverse, collections::{BinaryHeap, HashSet}};
+
+use sparsetools::{csr::CSR, Scalar};
+
+//Pivots smaller than this are treated as zero
+const PIVOT_TOLERANCE: f64 = 1e-12;
+//Fraction of the largest entry of a row the planned diagonal pivot must reach to be kept
+const PIVOT_THRESHOLD: f64 = 0.1;
+
+/// LU factors of a square sparse matrix, for the repeated solves of the power flow iterations
+///
+/// Rows are taken in minimum degree order on the pattern of A + Aᵀ to limit fill-in, each row pivoting on its
+/// diagonal unless that entry is small next to the rest of the row (threshold partial pivoting), as happens with the
+/// regulated reactive power columns of the Newton-Raphson Jacobian.
+pub(crate) struct SparseLu {
+    //Original row eliminated at each step
+    rows: Vec<usize>,
+    //Original column pivoted on at each step
+    cols: Vec<usize>,
+    //Strictly lower part of unit diagonal L, by step, indexed by step
+    lower: Vec<Vec<(usize, f64)>>,
+    //Upper part of U off the pivot, by step, indexed by original column
+    upper: Vec<Vec<(usize, f64)>>,
+    diagonal: Vec<f64>,
+}
+
+impl SparseLu {
+    /// Factors `matrix`, returning the (original) row whose pivot vanished if it is singular
+    pub(crate) fn factor(matrix: &CSR<usize, f64>) -> Result<Self, usize> {
+        Self::factor_ordered(matrix, minimum_degree(matrix))
+    }
+
+    /// Factors `matrix` taking its rows in the order `rows`, for matrices of the same pattern factored repeatedly
+    pub(crate) fn factor_ordered(matrix: &CSR<usize, f64>, rows: Vec<usize>) -> Result<Self, usize> {
+        let n: usize = matrix.rows();
+        let (rowptr, colidx, values): (&[usize], &[usize], &[f64]) = (matrix.rowptr(), matrix.colidx(), matrix.values());
+        let mut cols: Vec<usize> = Vec::with_capacity(n);
+        //Step at which each original column was pivoted on
+        let mut col_step: Vec<Option<usize>> = vec![None; n];
+        let mut lower: Vec<Vec<(usize, f64)>> = Vec::with_capacity(n);
+        let mut upper: Vec<Vec<(usize, f64)>> = Vec::with_capacity(n);
+        let mut diagonal: Vec<f64> = Vec::with_capacity(n);
+        //Dense work row with the list of its non-zero columns
+        let mut work: Vec<f64> = vec![0.0; n];
+        let mut filled: Vec<bool> = vec![false; n];
+        let mut pattern: Vec<usize> = Vec::new();
+        let mut pending: BinaryHeap<Reverse<usize>> = BinaryHeap::new();
+        for (step, &row) in rows.iter().enumerate() {
+            for entry in rowptr[row]..rowptr[row + 1] {
+                let col: usize = colidx[entry];
+                if !filled[col] {
+                    filled[col] = true;
+                    pattern.push(col);
+                    if let Some(earlier) = col_step[col] {
+                        pending.push(Reverse(earlier));
+                    }
+                }
+                work[col] += values[entry];
+            }
+            //Eliminate the columns of earlier steps in step order, as each one can fill in later ones
+            let mut l_row: Vec<(usize, f64)> = Vec::new();
+            while let Some(Reverse(earlier)) = pending.pop() {
+                let col: usize = cols[earlier];
+                let factor: f64 = work[col] / diagonal[earlier];
+                work[col] = 0.0;
+                if factor == 0.0 {
+                    continue;
+                }
+                l_row.push((earlier, factor));
+                for &(j, u) in &upper[earlier] {
+                    if !filled[j] {
+                        filled[j] = true;
+                        pattern.push(j);
+                        if let Some(later) = col_step[j] {
+                            pending.push(Reverse(later));
+                        }
+                    }
+                    work[j] -= factor * u;
+                }
+            }
+            let largest: Option<usize> = pattern.iter().copied()
+                .filter(|&col| col_step[col].is_none())
+                .max_by(|&a, &b| work[a].abs().total_cmp(&work[b].abs()));
+            let pivot: usize = match largest {
+                Some(largest) if work[largest].abs() >= PIVOT_TOLERANCE && work[largest].is_finite() => {
+                    if col_step[row].is_none() && work[row].abs() >= PIVOT_THRESHOLD * work[largest].abs() { row } else { largest }
+                }
+                _ => return Err(row),
+            };
+            col_step[pivot] = Some(step);
+            cols.push(pivot);
+            diagonal.push(work[pivot]);
+            let mut u_row: Vec<(usize, f64)> = Vec::new();
+            for &col in &pattern {
+                if col_step[col].is_none() && work[col] != 0.0 {
+                    u_row.push((col, work[col]));
+                }
+                work[col] = 0.0;
+                filled[col] = false;
+            }
+            pattern.clear();
+            lower.push(l_row);
+            upper.push(u_row);
+        }
+        Ok(SparseLu { rows, cols, lower, upper, diagonal })
+    }
+
+    /// Solves A x = b, returning x
+    pub(crate) fn solve(&self, rhs: &[f64]) -> Vec<f64> {
+        let mut y: Vec<f64> = self.rows.iter().map(|&row| rhs[row]).collect();
+        for step in 0..y.len() {
+            let sum: f64 = self.lower[step].iter().map(|&(earlier, l)| l * y[earlier]).sum();
+            y[step] -= sum;
+        }
+        let mut x: Vec<f64> = vec![0.0; y.len()];
+        for step in (0..y.len()).rev() {
+            let sum: f64 = self.upper[step].iter().map(|&(col, u)| u * x[col]).sum();
+            x[self.cols[step]] = (y[step] - sum) / self.diagonal[step];
+        }
+        x
+    }
+}
+
+/// Elimination order by minimum degree of the symmetric pattern of `matrix`, ties going to the lower index
+pub(crate) fn minimum_degree<T: Scalar>(matrix: &CSR<usize, T>) -> Vec<usize> {
+    let n: usize = matrix.rows();
+    let mut adjacent: Vec<HashSet<usize>> = vec![HashSet::new(); n];
+    for row in 0..n {
+        for &col in &matrix.colidx()[matrix.rowptr()[row]..matrix.rowptr()[row + 1]] {
+            if col != row {
+                adjacent[row].insert(col);
+                adjacent[col].insert(row);
+            }
+        }
+    }
+    let mut eliminated: Vec<bool> = vec![false; n];
+    let mut queue: BinaryHeap<Reverse<(usize, usize)>> = (0..n).map(|node| Reverse((adjacent[node].len(), node))).collect();
+    let mut order: Vec<usize> = Vec::with_capacity(n);
+    while let Some(Reverse((degree, node))) = queue.pop() {
+        //Skip nodes already eliminated or queued with a degree that has since changed
+        if eliminated[node] || degree != adjacent[node].len() {
+            continue;
+        }
+        eliminated[node] = true;
+        order.push(node);
+        let neighbours: Vec<usize> = std::mem::take(&mut adjacent[node]).into_iter().collect();
+        for &a in &neighbours {
+            adjacent[a].remove(&node);
+            for &b in &neighbours {
+                if a != b {
+                    adjacent[a].insert(b);
+                }
+            }
+            queue.push(Reverse((adjacent[a].len(), a)));
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use sparsetools::coo::Coo;
+
+    use super::*;
+
+    #[test]
+    fn solve_sparse() {
+        //Unsymmetric with a row and column coupling every other one, so the ordering leaves it for last
+        let entries: [(usize, usize, f64); 12] = [
+            (0, 0, 4.0), (0, 3, 1.0), (1, 1, 5.0), (1, 3, -2.0), (2, 2, 3.0), (2, 3, 1.0),
+            (3, 0, 2.0), (3, 1, 1.0), (3, 2, -1.0), (3, 3, 6.0), (1, 0, 1.0), (1, 1, 1.0),
+        ];
+        let mut coo: Coo<usize, f64> = Coo::with_size(4, 4);
+        for (row, col, value) in entries {
+            coo.push(row, col, value);
+        }
+        let matrix: CSR<usize, f64> = coo.to_csr();
+        let expected: Vec<f64> = vec![1.0, -2.0, 0.5, 3.0];
+        let rhs: Vec<f64> = matrix.mat_vec(&expected).unwrap();
+        let lu: SparseLu = SparseLu::factor(&matrix).unwrap();
+        for (x, expected) in lu.solve(&rhs).iter().zip(&expected) {
+            assert!((x - expected).abs() < 1e-12);
+        }
+
+        //A zero diagonal needs the columns swapped
+        let mut swapped: Coo<usize, f64> = Coo::with_size(3, 3);
+        for (row, col, value) in [(0, 1, 2.0), (1, 0, 1.0), (1, 1, 1.0), (1, 2, 1.0), (2, 2, 4.0), (2, 0, 1.0)] {
+            swapped.push(row, col, value);
+        }
+        let swapped: CSR<usize, f64> = swapped.to_csr();
+        let expected: Vec<f64> = vec![-1.0, 2.0, 0.5];
+        let x: Vec<f64> = SparseLu::factor(&swapped).unwrap().solve(&swapped.mat_vec(&expected).unwrap());
+        for (x, expected) in x.iter().zip(&expected) {
+            assert!((x - expected).abs() < 1e-12);
+        }
+
+        let mut singular: Coo<usize, f64> = Coo::with_size(2, 2);
+        singular.push(0, 0, 1.0);
+        singular.push(0, 1, 1.0);
+        singular.push(1, 0, 1.0);
+        singular.push(1, 1, 1.0);
+        assert!(SparseLu::factor(&singular.to_csr()).is_err());
+    }
+}
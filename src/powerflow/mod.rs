@@ -0,0 +1,9 @@
+//! Network models and power flow on a parsed [`PSSEData`](crate::PSSEData) case
+
+pub mod ac;
+pub mod dc;
+pub mod error;
+pub mod fdlf;
+pub(crate) mod lu;
+pub mod newton;
+pub mod ybus;
@@ -0,0 +1,225 @@
+use num_complex::Complex64;
+use sparsetools::{coo::Coo, csr::CSR};
+
+use crate::io::psse::pssedata::PSSEData;
+
+use super::{ac::{AcModel, AcPowerFlow, AcState, Mismatch, PowerFlowOptions}, error::PowerFlowError, lu::SparseLu};
+
+/// Solves the AC power flow of a case with the full Newton-Raphson method
+///
+/// The power flow equations are solved in polar form on the bus admittance matrix of [`build_ybus`](crate::build_ybus).
+/// Each island is solved around its first swing bus (IDE = 3), held at the VS of its machines and the VA of the case.
+/// In-service machines at generator buses (IDE = 2) hold the voltage of their regulated bus (IREG, or their own bus) at VS,
+/// the machines regulating a bus sharing the reactive power by RMPCT; machines at load buses inject their PG and QG.
+/// Loads are modeled from their constant power (PL, QL), current (IP, IQ) and admittance (YP, YQ) components.
+///
+/// With [`PowerFlowOptions::enforce_q_limits`], once converged the machines past their QT or QB limits are held there and their
+/// regulated bus switches from PV to PQ, the iterations then carrying on. A solution that does not converge within
+/// [`PowerFlowOptions::max_iterations`] is returned with `converged` false.
+///
+/// ```no_run
+/// use pf_gat_parser::prelude::*;
+///
+/// let mut case: PSSEData = read_raw("case.raw").unwrap();
+/// let solution: AcPowerFlow = newton_raphson(&case, &PowerFlowOptions::default()).unwrap();
+/// println!("converged in {} iterations", solution.iterations);
+/// solution.write_to(&mut case).unwrap();
+/// ```
+pub fn newton_raphson(data: &PSSEData, options: &PowerFlowOptions) -> Result<AcPowerFlow, PowerFlowError> {
+    let mut model: AcModel = AcModel::new(data);
+    let mut state: AcState = model.initial_state(data, options.flat_start);
+    //Rows of the P and Q of each bus taken together
+    let order: Vec<usize> = model.equation_order().into_iter().flat_map(|position| [2 * position, 2 * position + 1]).collect();
+    let scales: Vec<f64> = model.control_scales();
+    let mut trace: Vec<Mismatch> = Vec::new();
+    let mut iterations: usize = 0;
+    let converged: bool = loop {
+        let mismatches: Vec<Complex64> = model.mismatches(&state);
+        let largest: Mismatch = model.largest(&mismatches);
+        trace.push(largest);
+        if largest.max_mva() < options.tolerance_mva && !(options.enforce_q_limits && model.switch_limited(&mut state, options.tolerance_mva)) {
+            break true;
+        }
+        //A diverging solution is not iterated further
+        if iterations == options.max_iterations || !largest.max_mva().is_finite() {
+            break false;
+        }
+        let rhs: Vec<f64> = model.equations.iter().flat_map(|&bus| [mismatches[bus].re, mismatches[bus].im]).collect();
+        let lu: SparseLu = SparseLu::factor_ordered(&jacobian(&model, &state, &scales), order.clone())
+            .map_err(|row| PowerFlowError::Singular { bus: model.network.bus_ids[model.equations[row / 2]] })?;
+        let step: Vec<f64> = lu.solve(&rhs);
+        for (position, &bus) in model.equations.iter().enumerate() {
+            state.va[bus] -= step[2 * position];
+            if model.is_regulated(bus) {
+                if let Some(control) = model.control_of[bus] {
+                    state.q[control] -= step[2 * position + 1] * scales[control];
+                }
+            } else {
+                state.vm[bus] -= step[2 * position + 1];
+            }
+        }
+        iterations += 1;
+    };
+    Ok(model.solution(data, &state, converged, iterations, trace))
+}
+
+//Jacobian of the mismatches, with rows for the P and Q of each bus with power flow equations and columns for its angle and,
+//depending on whether it is regulated, its voltage magnitude or the reactive power of the machines regulating it
+fn jacobian(model: &AcModel, state: &AcState, scales: &[f64]) -> CSR<usize, f64> {
+    let size: usize = 2 * model.equations.len();
+    let mut stamps: Coo<usize, f64> = Coo::with_size(size, size);
+    let voltages: Vec<Complex64> = model.voltages(state);
+    let ybus: &CSR<usize, Complex64> = &model.ybus.matrix;
+    let j: Complex64 = Complex64::new(0.0, 1.0);
+    for (row, &i) in model.equations.iter().enumerate() {
+        let entries = ybus.rowptr()[i]..ybus.rowptr()[i + 1];
+        let current: Complex64 = entries.clone().map(|entry| ybus.values()[entry] * voltages[ybus.colidx()[entry]]).sum();
+        for entry in entries {
+            let k: usize = ybus.colidx()[entry];
+            let Some(col) = model.equation_of[k] else { continue };
+            let branch: Complex64 = voltages[i] * (ybus.values()[entry] * voltages[k]).conj();
+            let mut by_angle: Complex64 = -j * branch;
+            let mut by_magnitude: Complex64 = branch / state.vm[k];
+            if k == i {
+                by_angle += j * voltages[i] * current.conj();
+                by_magnitude += current.conj() * voltages[i] / state.vm[i] - model.scheduled_derivative(i, state.vm[i]);
+            }
+            stamps.push(2 * row, 2 * col, by_angle.re);
+            stamps.push(2 * row + 1, 2 * col, by_angle.im);
+            if !model.is_regulated(k) {
+                stamps.push(2 * row, 2 * col + 1, by_magnitude.re);
+                stamps.push(2 * row + 1, 2 * col + 1, by_magnitude.im);
+            }
+        }
+    }
+    for (control, scale) in model.controls.iter().zip(scales).filter(|(control, _)| control.active) {
+        let Some(col) = model.equation_of[control.bus] else { continue };
+        for &(bus, bus_share) in &control.bus_shares {
+            if let Some(row) = model.equation_of[bus] {
+                stamps.push(2 * row + 1, 2 * col + 1, -bus_share * scale);
+            }
+        }
+    }
+    stamps.to_csr()
+}
+
+#[cfg(test)]
+mod tests {
+    use sparsetools::csr::CSR;
+
+    use crate::{io::psse::{error::Diagnostic, options::ParseOptions, parsers::fast_parser::parse_bytes, pssedata::SourceMap}, powerflow::{ac::BusKind, ybus::{build_ybus, Ybus}}};
+
+    use super::*;
+
+    const CASE: &str = "0, 100.0, 33, 0, 0, 60.0
+NEWTON-RAPHSON CASE
+
+1, 'ONE', 138.0, 3, 1, 1, 1, 1.0, 0.0
+2, 'TWO', 138.0, 2, 1, 1, 1, 1.0, 0.0
+3, 'THREE', 138.0, 1, 1, 1, 1, 1.0, 0.0
+4, 'FOUR', 69.0, 1, 1, 1, 1, 1.0, 0.0
+0 / END OF BUS DATA, BEGIN LOAD DATA
+3, '1', 1, 1, 1, 80.0, 30.0, 10.0, 5.0, 10.0, -5.0, 1, 1, 0
+4, '1', 1, 1, 1, 40.0, 10.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0
+0 / END OF LOAD DATA, BEGIN FIXED SHUNT DATA
+3, '1', 1, 0.0, 20.0
+0 / END OF FIXED SHUNT DATA, BEGIN GENERATOR DATA
+1, '1', 0.0, 0.0, 300.0, -300.0, 1.02, 0, 100.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1, 100.0, 300.0, 0.0, 1, 1.0
+2, '1', 60.0, 0.0, 100.0, -100.0, 1.01, 0, 100.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1, 75.0, 100.0, 0.0, 1, 1.0
+2, '2', 20.0, 0.0, 100.0, -100.0, 1.01, 0, 100.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1, 25.0, 100.0, 0.0, 1, 1.0
+0 / END OF GENERATOR DATA, BEGIN BRANCH DATA
+1, 2, '1', 0.01, 0.1, 0.02, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+2, 3, '1', 0.01, 0.1, 0.02, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+1, 3, '1', 0.02, 0.2, 0.04, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+0 / END OF BRANCH DATA, BEGIN TRANSFORMER DATA
+3, 4, 0, '1', 1, 1, 1, 0.0, 0.0, 2, 'T1', 1, 1, 1.0, 0, 1.0, 0, 1.0, 0, 1.0, 'YNyn0'
+0.005, 0.08, 100.0
+1.025, 0.0, 0.0, 100.0, 100.0, 100.0, 0, 0, 1.1, 0.9, 1.1, 0.9, 33, 0, 0.0, 0.0, 0.0
+1.0, 0.0
+0 / END OF TRANSFORMER DATA
+Q
+";
+
+    fn case() -> PSSEData {
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(CASE.as_bytes(), &ParseOptions::strict()).unwrap();
+        data
+    }
+
+    //Power leaving each bus into the network at the solution, from the bus admittance matrix
+    fn injections(data: &PSSEData, solution: &AcPowerFlow) -> Vec<Complex64> {
+        let ybus: Ybus = build_ybus(data);
+        let matrix: &CSR<usize, Complex64> = &ybus.matrix;
+        let voltages: Vec<Complex64> = ybus.bus_ids.iter().map(|id| Complex64::from_polar(solution.vm_pu[id], solution.va_deg[id].to_radians())).collect();
+        let currents: Vec<Complex64> = matrix.mat_vec(&voltages).unwrap();
+        voltages.iter().zip(&currents).map(|(v, i)| v * i.conj() * 100.0).collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() < tolerance, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn newton_raphson_solution() {
+        let mut data: PSSEData = case();
+        let solution: AcPowerFlow = newton_raphson(&data, &PowerFlowOptions { tolerance_mva: 1e-6, ..PowerFlowOptions::default() }).unwrap();
+        assert!(solution.converged);
+        assert!(solution.iterations <= 5);
+        assert_eq!(solution.trace.len(), solution.iterations + 1);
+        assert!(solution.trace.last().unwrap().max_mva() < 1e-6);
+        assert_eq!(solution.bus_kinds[&2], BusKind::Pv);
+        assert_close(solution.vm_pu[&1], 1.02, 1e-12);
+        assert_close(solution.vm_pu[&2], 1.01, 1e-12);
+
+        //The ZIP load of bus 3 at its solved voltage and the generation of the machines balance the network injections
+        let s: Vec<Complex64> = injections(&data, &solution);
+        let v3: f64 = solution.vm_pu[&3];
+        assert_close(s[2].re, -(80.0 + 10.0 * v3 + 10.0 * v3 * v3), 1e-5);
+        assert_close(s[2].im, -(30.0 + 5.0 * v3 + 5.0 * v3 * v3), 1e-5);
+        assert_close(s[3].re, -40.0, 1e-5);
+        assert_close(s[1].re, 80.0, 1e-5);
+        assert_close(s[0].re, solution.pgen_mw[&(1, "1".to_string())], 1e-5);
+        //Reactive power of bus 2 is shared 75 / 25 by RMPCT
+        let q2: f64 = solution.qgen_mvar[&(2, "1".to_string())] + solution.qgen_mvar[&(2, "2".to_string())];
+        assert_close(s[1].im, q2, 1e-5);
+        assert_close(solution.qgen_mvar[&(2, "1".to_string())], 0.75 * q2, 1e-9);
+
+        solution.write_to(&mut data).unwrap();
+        assert_eq!(data.buses[2].vm_pu, v3);
+        assert_eq!(data.generators[0].pgen, solution.pgen_mw[&(1, "1".to_string())]);
+        assert_eq!(data.generators[1].qgen, solution.qgen_mvar[&(2, "1".to_string())]);
+    }
+
+    #[test]
+    fn newton_raphson_controls() {
+        //Machines at their reactive power limits switch their bus to PQ
+        let mut data: PSSEData = case();
+        data.generators[1].qmax = 5.0;
+        data.generators[2].qmax = 5.0;
+        let solution: AcPowerFlow = newton_raphson(&data, &PowerFlowOptions::default()).unwrap();
+        assert!(solution.converged);
+        assert_eq!(solution.bus_kinds[&2], BusKind::Pq);
+        assert_close(solution.qgen_mvar[&(2, "1".to_string())] + solution.qgen_mvar[&(2, "2".to_string())], 10.0, 1e-9);
+        assert!(solution.vm_pu[&2] < 1.01);
+        let unlimited: AcPowerFlow = newton_raphson(&data, &PowerFlowOptions { enforce_q_limits: false, ..PowerFlowOptions::default() }).unwrap();
+        assert_eq!(unlimited.bus_kinds[&2], BusKind::Pv);
+
+        //Remote regulation holds the voltage of bus 4 instead of bus 2
+        let mut data: PSSEData = case();
+        for generator in data.generators.iter_mut().skip(1) {
+            generator.reg_bus_id = 4;
+            generator.voltage_set = 1.0;
+        }
+        let solution: AcPowerFlow = newton_raphson(&data, &PowerFlowOptions { tolerance_mva: 1e-6, flat_start: true, ..PowerFlowOptions::default() }).unwrap();
+        assert!(solution.converged);
+        assert_eq!(solution.bus_kinds[&4], BusKind::Pv);
+        assert_eq!(solution.bus_kinds[&2], BusKind::Pq);
+        assert_close(solution.vm_pu[&4], 1.0, 1e-12);
+        let s: Vec<Complex64> = injections(&data, &solution);
+        assert_close(s[1].im, solution.qgen_mvar[&(2, "1".to_string())] + solution.qgen_mvar[&(2, "2".to_string())], 1e-5);
+
+        //Without converging, the solution is not written back
+        let unconverged: AcPowerFlow = newton_raphson(&data, &PowerFlowOptions { max_iterations: 0, flat_start: true, ..PowerFlowOptions::default() }).unwrap();
+        assert!(!unconverged.converged);
+        assert_eq!(unconverged.write_to(&mut data), Err(PowerFlowError::NotConverged { iterations: 0 }));
+    }
+}
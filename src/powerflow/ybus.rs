@@ -0,0 +1,407 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use num_complex::Complex64;
+use sparsetools::{coo::Coo, csr::CSR};
+
+use crate::io::psse::{components::{codes::BusType, structs::*}, pssedata::PSSEData};
+
+//PSS/E zero impedance line threshold (THRSHZ), used in place of a zero impedance
+pub(crate) const ZERO_IMPEDANCE_X: f64 = 0.0001;
+
+/// Bus admittance matrix of a case
+///
+/// Rows and columns are the in-service (non isolated) buses in the order of [`PSSEData::buses`],
+/// with admittances in p.u. on the system MVA base.
+#[derive(Clone)]
+pub struct Ybus {
+    /// ## Admittance matrix, row and column i being bus `bus_ids[i]`
+    pub matrix: CSR<usize, Complex64>,
+    /// ## Bus numbers of the rows and columns
+    pub bus_ids: Vec<i32>,
+    positions: HashMap<i32, usize>,
+}
+
+impl Ybus {
+    /// Row and column of bus `bus_id`, None if the bus is isolated or not in the case
+    pub fn index(&self, bus_id: i32) -> Option<usize> {
+        self.positions.get(&bus_id).copied()
+    }
+
+    /// Number of buses in the matrix
+    pub fn len(&self) -> usize {
+        self.bus_ids.len()
+    }
+
+    /// Whether the case has no in-service buses
+    pub fn is_empty(&self) -> bool {
+        self.bus_ids.is_empty()
+    }
+
+    /// Admittance between buses `from_bus` and `to_bus` (the diagonal when they are the same), zero if either is not in the matrix
+    pub fn get(&self, from_bus: i32, to_bus: i32) -> Complex64 {
+        match (self.index(from_bus), self.index(to_bus)) {
+            (Some(i), Some(j)) => self.matrix.get(i, j),
+            _ => Complex64::new(0.0, 0.0),
+        }
+    }
+}
+
+/// Builds the bus admittance matrix of a case
+///
+/// Only in-service equipment between in-service buses is included:
+/// * Branches as pi-models from R, X and B, with the GI + jBI and GJ + jBJ line shunts at their ends
+/// * Two-winding transformers with the off-nominal turns ratio and phase shift on the winding 1 side, three-winding transformers
+///   as a star of their windings reduced to the three buses. Turns ratios, impedances and magnetizing admittances are read
+///   according to the CW, CZ and CM codes, the magnetizing admittance being at the winding 1 bus. Windings taken out by the
+///   transformer status are left out.
+/// * Fixed shunts (GL + jBL) and the initial admittance BINIT of switched shunts, at 1.0 p.u. voltage
+///
+/// Branches and transformer windings with no impedance get the PSS/E zero impedance threshold of 0.0001 p.u. reactance.
+///
+/// ```no_run
+/// use pf_gat_parser::prelude::*;
+///
+/// let case: PSSEData = read_raw("case.raw").unwrap();
+/// let ybus: Ybus = build_ybus(&case);
+/// println!("{} buses, {} non-zeros", ybus.len(), ybus.matrix.nnz());
+/// ```
+pub fn build_ybus(data: &PSSEData) -> Ybus {
+    let network: Network = Network::new(data);
+    let elements: Vec<Element> = network.elements(data);
+    network.ybus(data, &elements)
+}
+
+/// In-service buses of a case and the branches and transformers between them
+pub(crate) struct Network {
+    pub(crate) bus_ids: Vec<i32>,
+    pub(crate) bus_types: Vec<BusType>,
+    pub(crate) base_kv: Vec<f64>,
+    pub(crate) positions: HashMap<i32, usize>,
+    pub(crate) sbase: f64,
+}
+
+/// A branch or transformer connecting buses of the [`Network`]
+pub(crate) enum Element {
+    /// A pi-model between `from` and `to`, with an ideal transformer of ratio `ratio` on the `from` side
+    TwoPort { key: (i32, i32, String), from: usize, to: usize, series: Complex64, ratio: Complex64, from_shunt: Complex64, to_shunt: Complex64 },
+    /// The windings of a three-winding transformer meeting at the star point, out of service windings being left out
+    Star { key: (i32, i32, i32, String), windings: Vec<Winding>, magnetizing: Option<(usize, Complex64)> },
+}
+
+/// A winding of a three-winding transformer between its bus and the star point
+pub(crate) struct Winding {
+    /// Winding number, from 0
+    pub(crate) number: usize,
+    pub(crate) bus: usize,
+    pub(crate) series: Complex64,
+    /// Ratio of the ideal transformer on the bus side
+    pub(crate) ratio: Complex64,
+}
+
+impl Network {
+    pub(crate) fn new(data: &PSSEData) -> Self {
+        let buses: Vec<&Bus> = data.buses.iter().filter(|bus| bus.bus_type() != BusType::Isolated).collect();
+        let mut positions: HashMap<i32, usize> = HashMap::with_capacity(buses.len());
+        let mut bus_ids: Vec<i32> = Vec::with_capacity(buses.len());
+        let mut bus_types: Vec<BusType> = Vec::with_capacity(buses.len());
+        let mut base_kv: Vec<f64> = Vec::with_capacity(buses.len());
+        for bus in buses {
+            if let Entry::Vacant(entry) = positions.entry(bus.id) {
+                entry.insert(bus_ids.len());
+                bus_ids.push(bus.id);
+                bus_types.push(bus.bus_type());
+                base_kv.push(bus.base_kv);
+            }
+        }
+        Network { bus_ids, bus_types, base_kv, positions, sbase: data.header.sbase }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.bus_ids.len()
+    }
+
+    pub(crate) fn index(&self, bus_id: i32) -> Option<usize> {
+        self.positions.get(&bus_id).copied()
+    }
+
+    /// The in-service branches and transformers of the case whose buses are all in service
+    pub(crate) fn elements(&self, data: &PSSEData) -> Vec<Element> {
+        let mut elements: Vec<Element> = Vec::with_capacity(data.branches.len() + data.transformers.len());
+        for branch in data.branches.iter().filter(|branch| branch.status != 0) {
+            if let Some(element) = self.branch(branch) {
+                elements.push(element);
+            }
+        }
+        for transformer in data.transformers.iter().filter(|transformer| transformer.status != 0) {
+            let element: Option<Element> = if transformer.tertiary_bus == 0 { self.two_winding(transformer) } else { self.three_winding(transformer) };
+            if let Some(element) = element {
+                elements.push(element);
+            }
+        }
+        elements
+    }
+
+    fn branch(&self, branch: &Branch) -> Option<Element> {
+        let (from, to): (usize, usize) = (self.index(branch.from_bus)?, self.index(branch.to_bus)?);
+        let charging: Complex64 = Complex64::new(0.0, branch.b / 2.0);
+        Some(Element::TwoPort {
+            key: (branch.from_bus, branch.to_bus, branch.circuit.clone()),
+            from,
+            to,
+            series: admittance(branch.r, branch.x),
+            ratio: Complex64::new(1.0, 0.0),
+            from_shunt: charging + Complex64::new(branch.gi, branch.bi),
+            to_shunt: charging + Complex64::new(branch.gj, branch.bj),
+        })
+    }
+
+    fn two_winding(&self, transformer: &Transformer) -> Option<Element> {
+        let (from, to): (usize, usize) = (self.index(transformer.from_bus)?, self.index(transformer.to_bus)?);
+        let t1: f64 = self.turns_ratio(transformer.cw, transformer.winding_1_volt, transformer.nominal_volt1, from);
+        let t2: f64 = self.turns_ratio(transformer.cw, transformer.winding_2_volt, transformer.nominal_volt2, to);
+        let (r, x): (f64, f64) = self.impedance(transformer.cz, transformer.r12, transformer.x12, transformer.sbase12);
+        Some(Element::TwoPort {
+            key: (transformer.from_bus, transformer.to_bus, transformer.circuit.clone()),
+            from,
+            to,
+            //The ratio is taken as t1 / t2, leaving the series admittance on the winding 2 side of it
+            series: admittance(r, x) / (t2 * t2),
+            ratio: Complex64::from_polar(t1 / t2, transformer.angle1.to_radians()),
+            from_shunt: self.magnetizing(transformer),
+            to_shunt: Complex64::new(0.0, 0.0),
+        })
+    }
+
+    fn three_winding(&self, transformer: &Transformer) -> Option<Element> {
+        let buses: [usize; 3] = [self.index(transformer.from_bus)?, self.index(transformer.to_bus)?, self.index(transformer.tertiary_bus)?];
+        let z12: Complex64 = self.impedance_complex(transformer.cz, transformer.r12, transformer.x12, transformer.sbase12);
+        let z23: Complex64 = self.impedance_complex(transformer.cz, transformer.r23, transformer.x23, transformer.sbase23);
+        let z31: Complex64 = self.impedance_complex(transformer.cz, transformer.r31, transformer.x31, transformer.sbase31);
+        let star: [Complex64; 3] = [(z12 + z31 - z23) / 2.0, (z12 + z23 - z31) / 2.0, (z23 + z31 - z12) / 2.0];
+        //Each winding's ratio is taken against the star point at 1.0, so unlike two_winding the star admittances are not rescaled
+        let ratios: [Complex64; 3] = [
+            Complex64::from_polar(self.turns_ratio(transformer.cw, transformer.winding_1_volt, transformer.nominal_volt1, buses[0]), transformer.angle1.to_radians()),
+            Complex64::from_polar(self.turns_ratio(transformer.cw, transformer.winding_2_volt, transformer.nominal_volt2, buses[1]), transformer.angle2.to_radians()),
+            Complex64::from_polar(self.turns_ratio(transformer.cw, transformer.winding_3_volt, transformer.nominal_volt3, buses[2]), transformer.angle3.to_radians()),
+        ];
+        //STAT 2, 3 and 4 take winding 2, 3 and 1 out of service
+        let out: Option<usize> = match transformer.status { 2 => Some(1), 3 => Some(2), 4 => Some(0), _ => None };
+        let windings: Vec<Winding> = (0..3)
+            .filter(|&number| Some(number) != out)
+            .map(|number| Winding { number, bus: buses[number], series: admittance(star[number].re, star[number].im), ratio: ratios[number] })
+            .collect();
+        let magnetizing: Option<(usize, Complex64)> = (out != Some(0)).then(|| (buses[0], self.magnetizing(transformer)));
+        Some(Element::Star { key: (transformer.from_bus, transformer.to_bus, transformer.tertiary_bus, transformer.circuit.clone()), windings, magnetizing })
+    }
+
+    /// Bus admittance matrix of `elements` and the in-service shunts of the case
+    pub(crate) fn ybus(&self, data: &PSSEData, elements: &[Element]) -> Ybus {
+        let mut stamps: Coo<usize, Complex64> = Coo::with_size(self.len(), self.len());
+        for element in elements {
+            element.stamp(&mut stamps);
+        }
+        for shunt in data.fixed_shunts.iter().filter(|shunt| shunt.status != 0) {
+            if let Some(i) = self.index(shunt.bus_id) {
+                stamps.push(i, i, Complex64::new(shunt.gl_mw, shunt.bl_mvar) / self.sbase);
+            }
+        }
+        for shunt in data.switched_shunts.iter().filter(|shunt| shunt.status != 0) {
+            if let Some(i) = self.index(shunt.bus_id) {
+                stamps.push(i, i, Complex64::new(0.0, shunt.b_init) / self.sbase);
+            }
+        }
+        Ybus { matrix: stamps.to_csr(), bus_ids: self.bus_ids.clone(), positions: self.positions.clone() }
+    }
+
+    /// Groups the buses connected by `elements` into islands, each in the order of the buses
+    pub(crate) fn islands(&self, elements: &[Element]) -> Vec<Vec<usize>> {
+        let mut parent: Vec<usize> = (0..self.len()).collect();
+        fn root(parent: &mut [usize], mut bus: usize) -> usize {
+            while parent[bus] != bus {
+                parent[bus] = parent[parent[bus]];
+                bus = parent[bus];
+            }
+            bus
+        }
+        for element in elements {
+            let buses: Vec<usize> = match element {
+                Element::TwoPort { from, to, .. } => vec![*from, *to],
+                Element::Star { windings, .. } => windings.iter().map(|winding| winding.bus).collect(),
+            };
+            for pair in buses.windows(2) {
+                let (a, b): (usize, usize) = (root(&mut parent, pair[0]), root(&mut parent, pair[1]));
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+        let mut islands: Vec<Vec<usize>> = Vec::new();
+        let mut island_of: HashMap<usize, usize> = HashMap::new();
+        for bus in 0..self.len() {
+            let island: usize = *island_of.entry(root(&mut parent, bus)).or_insert(islands.len());
+            if island == islands.len() {
+                islands.push(Vec::new());
+            }
+            islands[island].push(bus);
+        }
+        islands
+    }
+
+    //Off-nominal turns ratio of a winding in p.u. of its bus base voltage
+    fn turns_ratio(&self, cw: i8, windv: f64, nomv: f64, bus: usize) -> f64 {
+        let base_kv: f64 = self.base_kv[bus];
+        match cw {
+            2 if base_kv > 0.0 => windv / base_kv,
+            3 if base_kv > 0.0 && nomv > 0.0 => windv * nomv / base_kv,
+            _ => windv,
+        }
+    }
+
+    //Winding impedance in p.u. on the system MVA base
+    fn impedance(&self, cz: i8, r: f64, x: f64, winding_base: f64) -> (f64, f64) {
+        let winding_base: f64 = if winding_base > 0.0 { winding_base } else { self.sbase };
+        let (r, x): (f64, f64) = match cz {
+            2 => (r, x),
+            //Load loss in W and impedance magnitude
+            3 => {
+                let r: f64 = r / 1e6 / winding_base;
+                (r, (x * x - r * r).max(0.0).sqrt())
+            }
+            _ => return (r, x),
+        };
+        (r * self.sbase / winding_base, x * self.sbase / winding_base)
+    }
+
+    fn impedance_complex(&self, cz: i8, r: f64, x: f64, winding_base: f64) -> Complex64 {
+        let (r, x): (f64, f64) = self.impedance(cz, r, x, winding_base);
+        Complex64::new(r, x)
+    }
+
+    //Magnetizing admittance in p.u. on the system MVA base
+    fn magnetizing(&self, transformer: &Transformer) -> Complex64 {
+        match transformer.cm {
+            //No-load loss in W and exciting current in p.u. on SBASE1-2
+            2 => {
+                let winding_base: f64 = if transformer.sbase12 > 0.0 { transformer.sbase12 } else { self.sbase };
+                let g: f64 = transformer.mag1 / 1e6 / winding_base;
+                let b: f64 = -(transformer.mag2 * transformer.mag2 - g * g).max(0.0).sqrt();
+                Complex64::new(g, b) * winding_base / self.sbase
+            }
+            _ => Complex64::new(transformer.mag1, transformer.mag2),
+        }
+    }
+}
+
+impl Element {
+    /// Adds the admittances of the element to the bus admittance matrix
+    pub(crate) fn stamp(&self, stamps: &mut Coo<usize, Complex64>) {
+        match self {
+            Element::TwoPort { from, to, series, ratio, from_shunt, to_shunt, .. } => {
+                stamps.push(*from, *from, series / ratio.norm_sqr() + from_shunt);
+                stamps.push(*to, *to, series + to_shunt);
+                stamps.push(*from, *to, -series / ratio.conj());
+                stamps.push(*to, *from, -series / ratio);
+            }
+            //The star point is eliminated, leaving the admittances between the winding buses
+            Element::Star { windings, magnetizing, .. } => {
+                let star: Complex64 = windings.iter().map(|winding| winding.series).sum();
+                for i in windings {
+                    stamps.push(i.bus, i.bus, i.series / i.ratio.norm_sqr());
+                    for j in windings {
+                        stamps.push(i.bus, j.bus, -(i.series / i.ratio.conj()) * (j.series / j.ratio) / star);
+                    }
+                }
+                if let Some((bus, admittance)) = magnetizing {
+                    stamps.push(*bus, *bus, *admittance);
+                }
+            }
+        }
+    }
+}
+
+fn admittance(r: f64, x: f64) -> Complex64 {
+    if r == 0.0 && x == 0.0 {
+        return Complex64::new(0.0, -1.0 / ZERO_IMPEDANCE_X);
+    }
+    Complex64::new(1.0, 0.0) / Complex64::new(r, x)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::psse::{error::Diagnostic, options::ParseOptions, parsers::fast_parser::parse_bytes, pssedata::SourceMap};
+
+    use super::*;
+
+    const CASE: &str = "0, 100.0, 33, 0, 0, 60.0
+YBUS CASE
+
+1, 'ONE', 138.0, 3, 1, 1, 1, 1.0, 0.0
+2, 'TWO', 138.0, 1, 1, 1, 1, 1.0, 0.0
+3, 'THREE', 69.0, 1, 1, 1, 1, 1.0, 0.0
+4, 'FOUR', 13.8, 1, 1, 1, 1, 1.0, 0.0
+5, 'FIVE', 13.8, 4, 1, 1, 1, 1.0, 0.0
+0 / END OF BUS DATA, BEGIN LOAD DATA
+0 / END OF LOAD DATA, BEGIN FIXED SHUNT DATA
+2, '1', 1, 0.0, 10.0
+0 / END OF FIXED SHUNT DATA, BEGIN GENERATOR DATA
+0 / END OF GENERATOR DATA, BEGIN BRANCH DATA
+1, 2, '1', 0.0, 0.1, 0.02, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+1, 2, '2', 0.0, 0.1, 0.02, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 0, 1, 0.0, 1, 1.0
+2, 5, '1', 0.0, 0.1, 0.02, 100.0, 100.0, 100.0, 0.0, 0.0, 0.0, 0.0, 1, 1, 0.0, 1, 1.0
+0 / END OF BRANCH DATA, BEGIN TRANSFORMER DATA
+2, 3, 0, '1', 1, 2, 1, 0.0, -0.01, 2, 'T1', 1, 1, 1.0, 0, 1.0, 0, 1.0, 0, 1.0, 'YNyn0'
+0.0, 0.2, 50.0
+1.05, 0.0, 30.0, 100.0, 100.0, 100.0, 0, 0, 1.1, 0.9, 1.1, 0.9, 33, 0, 0.0, 0.0, 0.0
+1.0, 0.0
+1, 2, 4, '1', 1, 1, 1, 0.0, 0.0, 2, 'T3', 1, 1, 1.0, 0, 1.0, 0, 1.0, 0, 1.0, 'YNyn0d1'
+0.0, 0.1, 100.0, 0.0, 0.1, 100.0, 0.0, 0.1, 100.0, 1.0, 0.0
+1.0, 0.0, 0.0, 100.0, 100.0, 100.0, 0, 0, 1.1, 0.9, 1.1, 0.9, 33, 0, 0.0, 0.0, 0.0
+1.0, 0.0, 0.0, 100.0, 100.0, 100.0, 0, 0, 1.1, 0.9, 1.1, 0.9, 33, 0, 0.0, 0.0, 0.0
+1.0, 0.0, 0.0, 100.0, 100.0, 100.0, 0, 0, 1.1, 0.9, 1.1, 0.9, 33, 0, 0.0, 0.0, 0.0
+0 / END OF TRANSFORMER DATA
+Q
+";
+
+    fn assert_close(actual: Complex64, expected: Complex64) {
+        assert!((actual - expected).norm() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn build_ybus_elements() {
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(CASE.as_bytes(), &ParseOptions::strict()).unwrap();
+        let ybus: Ybus = build_ybus(&data);
+        //The isolated bus is left out along with the branch to it
+        assert_eq!(ybus.bus_ids, vec![1, 2, 3, 4]);
+        assert_eq!(ybus.index(5), None);
+
+        //Out of service branch circuit 2 is left out, the three-winding transformer star of j0.05 per winding
+        //reduces to j0.15 between each pair of buses
+        let line: Complex64 = Complex64::new(0.0, -10.0);
+        let winding_pair: Complex64 = Complex64::new(0.0, -1.0 / 0.15);
+        assert_close(ybus.get(1, 2), -line - winding_pair);
+        assert_close(ybus.get(2, 1), -line - winding_pair);
+        assert_close(ybus.get(1, 1), line + Complex64::new(0.0, 0.01) + 2.0 * winding_pair);
+
+        //Transformer impedance of j0.2 on 50 MVA (CZ = 2) is j0.4 on the system base,
+        //with a ratio of 1.05 at 30 degrees and a magnetizing admittance of -j0.01 (CM = 1) at bus 2
+        let series: Complex64 = Complex64::new(0.0, -2.5);
+        let ratio: Complex64 = Complex64::from_polar(1.05, 30f64.to_radians());
+        assert_close(ybus.get(2, 3), -series / ratio.conj());
+        assert_close(ybus.get(3, 2), -series / ratio);
+        assert_close(ybus.get(3, 3), series);
+        let shunt: Complex64 = Complex64::new(0.0, 0.1);
+        assert_close(ybus.get(2, 2), line + Complex64::new(0.0, 0.01) + series / ratio.norm_sqr() + Complex64::new(0.0, -0.01) + shunt + 2.0 * winding_pair);
+        assert_close(ybus.get(4, 4), 2.0 * winding_pair);
+    }
+
+    #[test]
+    fn two_winding_ratio_on_both_windings() {
+        //WINDV2 of 0.95 on the winding 2 side of transformer 2-3
+        let case: String = CASE.replacen("1.0, 0.0\n1, 2, 4", "0.95, 0.0\n1, 2, 4", 1);
+        let (data, _, _): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_bytes(case.as_bytes(), &ParseOptions::strict()).unwrap();
+        let ybus: Ybus = build_ybus(&data);
+        let series: Complex64 = Complex64::new(0.0, -2.5);
+        let (t1, t2): (Complex64, f64) = (Complex64::from_polar(1.05, 30f64.to_radians()), 0.95);
+        assert_close(ybus.get(2, 3), -series / (t1.conj() * t2));
+        assert_close(ybus.get(3, 2), -series / (t1 * t2));
+        assert_close(ybus.get(3, 3), series / (t2 * t2));
+    }
+}
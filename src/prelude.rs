@@ -0,0 +1,9 @@
+//! Re-exports of the most commonly used types and functions
+//!
+//! ```
+//! use pf_gat_parser::prelude::*;
+//! ```
+
+pub use crate::codes::*;
+pub use crate::structs::*;
+pub use crate::{apply_change_file, apply_changes, build_ybus, check_plausibility, convert_revision, dc_power_flow, fast_decoupled, newton_raphson, open_case, parse_bytes, parse_fast, parse_rawx, parse_reader, read_raw, validate, AcPowerFlow, BusKind, CaseIndex, ChangeReport, ChangedRecord, ConversionLoss, DcPowerFlow, Diagnostic, DiagnosticKind, Encoding, FdlfVariant, Mismatch, ParseError, ParseMode, ParseOptions, PlausibilityReport, PlausibilityRules, PowerFlowError, PowerFlowOptions, PSSEData, PSSEDatatype, SourceMap, ValidationReport, write_raw, write_rawx, Ybus};
@@ -0,0 +1,45 @@
+use pf_gat_parser::prelude::*;
+
+fn test_case(name: &str) -> String {
+    format!("{}/test-cases/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+#[test]
+fn read_raw_v33() {
+    let data: PSSEData = pf_gat_parser::read_raw(test_case("TEST_parse_psse_v33.RAW")).unwrap();
+    assert_eq!(data.header.revision, 33);
+    assert_eq!(data.header.sbase, 923.45);
+    assert_eq!(data.buses.len(), 1);
+    assert_eq!(data.buses[0].name, "TESTBUS".to_string());
+    assert_eq!(data.loads[0].bus_id, 347);
+    assert_eq!(data.generators[0].bus_id, 24568);
+    assert_eq!(data.branches[0].to_bus, 876543);
+    assert_eq!(data.transformers[0].to_bus, 24569);
+    assert_eq!(data.owners[0].owner_name, "OWNER21".to_string());
+}
+
+#[test]
+fn read_raw_matches_parse_fast() {
+    let path: String = test_case("TEST_parse_psse_v33.RAW");
+    let from_read_raw: PSSEData = read_raw(&path).unwrap();
+    let (from_parse_fast, _, diagnostics): (PSSEData, SourceMap, Vec<Diagnostic>) = parse_fast(&path, &ParseOptions::default()).unwrap();
+    assert!(diagnostics.is_empty());
+    assert_eq!(from_read_raw.buses, from_parse_fast.buses);
+    assert_eq!(from_read_raw.transformers, from_parse_fast.transformers);
+}
+
+#[test]
+fn read_raw_missing_file() {
+    match read_raw(test_case("DOES_NOT_EXIST.RAW")) {
+        Err(ParseError::Io(err)) => assert_eq!(err.kind(), std::io::ErrorKind::NotFound),
+        other => panic!("expected an io error, got {:?}", other),
+    }
+}
+
+#[test]
+fn structs_are_public() {
+    // Component structs can be built by dependent crates through the public surface
+    let bus: Bus = Bus { id: 1, name: "ONE".to_string(), ..Default::default() };
+    let load: pf_gat_parser::structs::Load = Load { bus_id: bus.id, ..Default::default() };
+    assert_eq!(load.bus_id, 1);
+}